@@ -1,10 +1,10 @@
 use bgf::{graphics::colors, Window};
 
-fn main() {
-    let mut window = Window::new(1280, 720, "Basic Example");
-    let mut renderer = window.build_renderer(1);
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut window = Window::new(1280, 720, "Basic Example")?;
+    let mut renderer = window.build_renderer(1)?;
 
-    let can_pooper_texture = renderer.load_texture("assets/textures/can_pooper.png");
+    let can_pooper_texture = renderer.load_texture("assets/textures/can_pooper.png")?;
 
     window.show();
 
@@ -24,4 +24,6 @@ fn main() {
 
         window.update();
     }
+
+    return Ok(());
 }
@@ -0,0 +1,16 @@
+#version 430 core
+out vec4 out_color;
+
+in vec2 uv;
+
+// The blurred highlights, and the scene from before the bloom.
+uniform sampler2D input_texture;
+uniform sampler2D effect_input;
+uniform float intensity = 1.0;
+
+void main() {
+    vec4 scene = texture(effect_input, uv);
+    vec3 bloom = texture(input_texture, uv).rgb;
+
+    out_color = vec4(scene.rgb + bloom * intensity, scene.a);
+}
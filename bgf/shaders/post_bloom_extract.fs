@@ -0,0 +1,15 @@
+#version 430 core
+out vec4 out_color;
+
+in vec2 uv;
+
+uniform sampler2D input_texture;
+uniform float threshold = 0.8;
+
+void main() {
+    vec4 color = texture(input_texture, uv);
+    float brightness = max(color.r, max(color.g, color.b));
+    float contribution = max(brightness - threshold, 0.0) / max(brightness, 0.0001);
+
+    out_color = vec4(color.rgb * contribution, 1.0);
+}
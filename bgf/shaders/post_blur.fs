@@ -0,0 +1,25 @@
+#version 430 core
+out vec4 out_color;
+
+in vec2 uv;
+
+uniform sampler2D input_texture;
+uniform vec2 texel_size;
+
+// (1, 0) for the horizontal pass and (0, 1) for the vertical one.
+uniform vec2 direction;
+uniform float radius = 1.0;
+
+const float weights[5] = float[](0.227027, 0.1945946, 0.1216216, 0.054054, 0.016216);
+
+void main() {
+    vec2 offset = direction * texel_size * radius;
+    vec4 result = texture(input_texture, uv) * weights[0];
+
+    for (int i = 1; i < 5; i++) {
+        result += texture(input_texture, uv + offset * float(i)) * weights[i];
+        result += texture(input_texture, uv - offset * float(i)) * weights[i];
+    }
+
+    out_color = result;
+}
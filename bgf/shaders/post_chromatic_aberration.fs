@@ -0,0 +1,20 @@
+#version 430 core
+out vec4 out_color;
+
+in vec2 uv;
+
+uniform sampler2D input_texture;
+uniform vec2 texel_size;
+
+// How far apart the channels are at the edges of the screen, in pixels.
+uniform float amount = 3.0;
+
+void main() {
+    vec2 offset = (uv - 0.5) * 2.0 * amount * texel_size;
+
+    float red = texture(input_texture, uv + offset).r;
+    vec4 center = texture(input_texture, uv);
+    float blue = texture(input_texture, uv - offset).b;
+
+    out_color = vec4(red, center.g, blue, center.a);
+}
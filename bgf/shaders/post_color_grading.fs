@@ -0,0 +1,33 @@
+#version 430 core
+out vec4 out_color;
+
+in vec2 uv;
+
+uniform sampler2D input_texture;
+
+// A strip of lut_size slices that are lut_size by lut_size pixels each, with
+// red going right within a slice, green going down and blue going from slice
+// to slice.
+uniform sampler2D lut;
+uniform float lut_size = 16.0;
+uniform float intensity = 1.0;
+
+vec3 grade(vec3 color) {
+    float blue = clamp(color.b, 0.0, 1.0) * (lut_size - 1.0);
+    float slice = floor(blue);
+    float next_slice = min(slice + 1.0, lut_size - 1.0);
+
+    float x = (clamp(color.r, 0.0, 1.0) * (lut_size - 1.0) + 0.5) / (lut_size * lut_size);
+    float y = (clamp(color.g, 0.0, 1.0) * (lut_size - 1.0) + 0.5) / lut_size;
+
+    vec3 a = texture(lut, vec2(slice / lut_size + x, y)).rgb;
+    vec3 b = texture(lut, vec2(next_slice / lut_size + x, y)).rgb;
+
+    return mix(a, b, blue - slice);
+}
+
+void main() {
+    vec4 color = texture(input_texture, uv);
+
+    out_color = vec4(mix(color.rgb, grade(color.rgb), intensity), color.a);
+}
@@ -0,0 +1,10 @@
+#version 430 core
+out vec4 out_color;
+
+in vec2 uv;
+
+uniform sampler2D input_texture;
+
+void main() {
+    out_color = texture(input_texture, uv);
+}
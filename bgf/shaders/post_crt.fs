@@ -0,0 +1,33 @@
+#version 430 core
+out vec4 out_color;
+
+in vec2 uv;
+
+uniform sampler2D input_texture;
+uniform vec2 texel_size;
+uniform float time;
+
+uniform float curvature = 0.1;
+uniform float scanline_intensity = 0.25;
+uniform float flicker = 0.02;
+
+void main() {
+    // Bend the picture like the glass of an old screen.
+    vec2 centered = uv * 2.0 - 1.0;
+    centered += centered * (centered.yx * centered.yx) * curvature;
+    vec2 curved_uv = centered * 0.5 + 0.5;
+
+    if (curved_uv.x < 0.0 || curved_uv.x > 1.0 || curved_uv.y < 0.0 || curved_uv.y > 1.0) {
+        out_color = vec4(0.0, 0.0, 0.0, 1.0);
+        return;
+    }
+
+    vec4 color = texture(input_texture, curved_uv);
+
+    // One dark line per row of pixels in the input.
+    float scanline = 0.5 + 0.5 * sin(curved_uv.y / texel_size.y * 6.2831853);
+    color.rgb *= 1.0 - scanline_intensity * scanline;
+    color.rgb *= 1.0 + flicker * sin(time * 60.0);
+
+    out_color = color;
+}
@@ -0,0 +1,12 @@
+#version 430 core
+out vec2 uv;
+
+uniform bool flip_y;
+
+void main() {
+    // A single triangle that covers the whole screen.
+    vec2 position = vec2((gl_VertexID << 1) & 2, gl_VertexID & 2);
+
+    gl_Position = vec4(position * 2.0 - 1.0, 0.0, 1.0);
+    uv = vec2(position.x, flip_y ? 1.0 - position.y : position.y);
+}
@@ -0,0 +1,16 @@
+#version 430 core
+out vec4 out_color;
+
+in vec2 uv;
+
+uniform sampler2D input_texture;
+uniform float intensity = 0.5;
+uniform float radius = 0.75;
+uniform float softness = 0.45;
+
+void main() {
+    vec4 color = texture(input_texture, uv);
+    float vignette = smoothstep(radius, radius - softness, length(uv - 0.5));
+
+    out_color = vec4(color.rgb * mix(1.0, vignette, intensity), color.a);
+}
@@ -0,0 +1,503 @@
+//! Sound effects and music. WAV, OGG Vorbis and FLAC files can be loaded.
+//! Sounds are decoded all at once when they're loaded, and music is decoded
+//! a little at a time while it plays.
+//!
+//! Every sound plays on a bus. The mixer starts with `master`, `music`, `sfx`
+//! and `voice` buses, and more can be added with `Audio::add_bus`. Each bus
+//! has its own volume and effects, and all of them go through `master`.
+//!
+//! Sounds played with `Audio::play_at` are positional. They get quieter and
+//! pan to the side depending on where they are compared to the listener,
+//! which is usually wherever the camera is.
+
+mod backend;
+mod bus;
+mod decoder;
+mod effects;
+mod mixer;
+mod settings;
+mod spatial;
+
+#[cfg(feature = "cpal")]
+pub use backend::CpalBackend;
+pub use backend::{AudioBackend, NullBackend, WavFileBackend};
+pub use effects::{Effect, LowPass, Reverb};
+pub use mixer::Mixer;
+pub use settings::{BusVolume, VolumeSettings};
+pub use spatial::{Attenuation, Rolloff};
+
+use crate::math::Vector2;
+use crate::Error;
+use bus::{Bus, Duck};
+use mixer::{SoundData, Source, StreamDecoder};
+use spatial::Spatial;
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+
+/// A fully decoded sound. Cloning it is cheap, and the samples are shared.
+#[derive(Clone)]
+pub struct Sound {
+    data: Arc<SoundData>,
+}
+
+impl Sound {
+    pub fn load(path: &str) -> Result<Sound, Error> {
+        let mut decoder = decoder::open(path)?;
+        let mut samples = Vec::new();
+
+        while let Some(chunk) = decoder.next_chunk()? {
+            samples.extend_from_slice(&chunk);
+        }
+
+        return Ok(Sound::from_samples(
+            samples,
+            decoder.channels(),
+            decoder.sample_rate(),
+        ));
+    }
+
+    /// Makes a sound from interleaved samples between -1 and 1.
+    pub fn from_samples(samples: Vec<f32>, channels: u16, sample_rate: u32) -> Sound {
+        Sound {
+            data: Arc::new(SoundData {
+                samples,
+                channels,
+                sample_rate,
+            }),
+        }
+    }
+
+    pub fn get_channels(&self) -> u16 {
+        self.data.channels
+    }
+
+    pub fn get_sample_rate(&self) -> u32 {
+        self.data.sample_rate
+    }
+
+    /// In seconds.
+    pub fn get_duration(&self) -> f64 {
+        let frames = self.data.samples.len() / self.data.channels.max(1) as usize;
+        return frames as f64 / self.data.sample_rate.max(1) as f64;
+    }
+}
+
+/// How a sound starts out. These can all be changed later through its
+/// `SoundHandle`, but setting them here means the first few milliseconds
+/// aren't played with the wrong settings.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlaySettings {
+    pub volume: f32,
+    /// From -1 (left) to 1 (right).
+    pub pan: f32,
+    /// A multiplier for the speed, which also changes the pitch.
+    pub pitch: f32,
+    pub looping: bool,
+    /// In seconds. 0 starts at full volume.
+    pub fade_in: f64,
+    /// `None` plays sounds on `sfx` and music on `music`.
+    pub bus: Option<String>,
+}
+
+impl Default for PlaySettings {
+    fn default() -> PlaySettings {
+        PlaySettings {
+            volume: 1.0,
+            pan: 0.0,
+            pitch: 1.0,
+            looping: false,
+            fade_in: 0.0,
+            bus: None,
+        }
+    }
+}
+
+// How far ahead music is decoded in `Audio::update`, on top of the time the
+// frame took.
+const STREAM_AHEAD_SECONDS: f64 = 1.0;
+
+pub struct Audio {
+    mixer: Arc<Mutex<Mixer>>,
+    backend: Box<dyn AudioBackend>,
+    default_attenuation: Attenuation,
+    // The decoding half of each piece of music that's playing.
+    streams: RefCell<Vec<StreamDecoder>>,
+}
+
+impl Audio {
+    pub fn new(mut backend: impl AudioBackend + 'static) -> Result<Audio, Error> {
+        let mixer = Arc::new(Mutex::new(Mixer::new(backend.get_sample_rate())));
+        backend.start(mixer.clone())?;
+
+        log::info!(
+            "Started audio at {} Hz",
+            mixer.lock().unwrap().get_sample_rate()
+        );
+
+        return Ok(Audio {
+            mixer,
+            backend: Box::new(backend),
+            default_attenuation: Attenuation::default(),
+            streams: RefCell::new(Vec::new()),
+        });
+    }
+
+    pub fn play(&self, sound: &Sound) -> SoundHandle {
+        return self.play_with(sound, PlaySettings::default());
+    }
+
+    pub fn play_with(&self, sound: &Sound, settings: PlaySettings) -> SoundHandle {
+        return self.add_voice(Source::Buffered(sound.data.clone()), "sfx", settings, None);
+    }
+
+    /// Plays a sound at a position in the world, using the default
+    /// attenuation. Its pan is added to the pan from where it is.
+    pub fn play_at(
+        &self,
+        sound: &Sound,
+        position: Vector2<f32>,
+        settings: PlaySettings,
+    ) -> SoundHandle {
+        let spatial = Spatial {
+            position,
+            velocity: Vector2 { x: 0.0, y: 0.0 },
+            attenuation: self.default_attenuation,
+        };
+
+        return self.add_voice(
+            Source::Buffered(sound.data.clone()),
+            "sfx",
+            settings,
+            Some(spatial),
+        );
+    }
+
+    /// Streams the file from disk while it plays. It's decoded a little ahead
+    /// of time in `update`, so that has to be called every frame for the
+    /// music to keep going. Looping music opens the file again every time it
+    /// gets to the end.
+    pub fn play_music(&self, path: &str, settings: PlaySettings) -> Result<SoundHandle, Error> {
+        let (stream, mut decoder) = mixer::open_stream(path, settings.looping)?;
+
+        // The start is decoded straight away, so there's no gap before the
+        // next update.
+        let ahead = seconds_to_frames(decoder.get_sample_rate(), STREAM_AHEAD_SECONDS);
+        decoder.fill(ahead);
+
+        let handle = self.add_voice(Source::Streamed(stream), "music", settings, None);
+        self.streams.borrow_mut().push(decoder);
+
+        return Ok(handle);
+    }
+
+    fn add_voice(
+        &self,
+        source: Source,
+        default_bus: &str,
+        settings: PlaySettings,
+        spatial: Option<Spatial>,
+    ) -> SoundHandle {
+        let mut mixer = self.mixer.lock().unwrap();
+        let fade_length = seconds_to_frames(mixer.get_sample_rate(), settings.fade_in);
+
+        let bus_name = settings.bus.as_deref().unwrap_or(default_bus);
+        let bus = match mixer.bus_index(bus_name) {
+            Some(bus) => bus,
+            None => {
+                log::warn!("There's no audio bus called {}, using master", bus_name);
+                0
+            }
+        };
+
+        let voice = mixer.add_voice(source, bus);
+        voice.volume = settings.volume;
+        voice.pan = settings.pan;
+        voice.pitch = settings.pitch;
+        voice.looping = settings.looping;
+        voice.spatial = spatial;
+
+        if fade_length > 0 {
+            voice.set_fade_gain(0.0);
+            voice.fade(1.0, fade_length, false);
+        }
+
+        return SoundHandle {
+            id: voice.id,
+            mixer: self.mixer.clone(),
+        };
+    }
+
+    pub fn stop_all(&self) {
+        self.mixer.lock().unwrap().voices.clear();
+    }
+
+    /// Adds a bus that's mixed into `master`. Adding one that already exists
+    /// doesn't do anything.
+    pub fn add_bus(&self, name: &str) {
+        let mut mixer = self.mixer.lock().unwrap();
+
+        if mixer.bus_index(name).is_none() {
+            mixer.buses.push(Bus::new(name));
+        }
+    }
+
+    // Unknown bus names are logged and ignored, the same way sounds played on
+    // them go to master instead, so a typo doesn't break anything.
+    fn with_bus(&self, name: &str, f: impl FnOnce(&mut Bus)) {
+        let mut mixer = self.mixer.lock().unwrap();
+
+        match mixer.bus_index(name) {
+            Some(bus) => f(&mut mixer.buses[bus]),
+            None => log::warn!("There's no audio bus called {}", name),
+        }
+    }
+
+    pub fn set_bus_volume(&self, name: &str, volume: f32) {
+        self.with_bus(name, |bus| bus.volume = volume.max(0.0));
+    }
+
+    pub fn get_bus_volume(&self, name: &str) -> Option<f32> {
+        let mixer = self.mixer.lock().unwrap();
+        return mixer.bus_index(name).map(|bus| mixer.buses[bus].volume);
+    }
+
+    pub fn set_bus_muted(&self, name: &str, muted: bool) {
+        self.with_bus(name, |bus| bus.muted = muted);
+    }
+
+    pub fn is_bus_muted(&self, name: &str) -> Option<bool> {
+        let mixer = self.mixer.lock().unwrap();
+        return mixer.bus_index(name).map(|bus| mixer.buses[bus].muted);
+    }
+
+    /// Effects run in the order they're added, before the bus's volume.
+    pub fn add_bus_effect(&self, name: &str, effect: impl Effect + 'static) {
+        self.with_bus(name, |bus| bus.effects.push(Box::new(effect)));
+    }
+
+    pub fn clear_bus_effects(&self, name: &str) {
+        self.with_bus(name, |bus| bus.effects.clear());
+    }
+
+    /// Turns `bus` down to `volume` while anything is playing on `trigger`,
+    /// like lowering the music while someone's talking. `attack` and
+    /// `release` are how many seconds it takes to go down and come back up.
+    pub fn duck(&self, bus: &str, trigger: &str, volume: f32, attack: f64, release: f64) {
+        let mut mixer = self.mixer.lock().unwrap();
+
+        let (target, trigger) = match (mixer.bus_index(bus), mixer.bus_index(trigger)) {
+            (Some(target), Some(trigger)) => (target, trigger),
+            _ => {
+                log::warn!(
+                    "Can't duck {} under {}, one of them doesn't exist",
+                    bus,
+                    trigger
+                );
+                return;
+            }
+        };
+
+        mixer
+            .ducks
+            .retain(|duck| duck.target != target || duck.trigger != trigger);
+
+        mixer.ducks.push(Duck {
+            target,
+            trigger,
+            volume: volume.clamp(0.0, 1.0),
+            attack,
+            release,
+            gain: 1.0,
+        });
+    }
+
+    pub fn stop_ducking(&self, bus: &str) {
+        let mut mixer = self.mixer.lock().unwrap();
+
+        if let Some(target) = mixer.bus_index(bus) {
+            mixer.ducks.retain(|duck| duck.target != target);
+        }
+    }
+
+    /// Where sounds are heard from, usually the camera. The velocity is in
+    /// world units per second, and is only used for Doppler.
+    pub fn set_listener(&self, position: Vector2<f32>, velocity: Vector2<f32>) {
+        let mut mixer = self.mixer.lock().unwrap();
+        mixer.listener.position = position;
+        mixer.listener.velocity = velocity;
+    }
+
+    /// Turns on Doppler, where sounds moving towards the listener are higher
+    /// pitched and ones moving away are lower. The speed is in world units
+    /// per second, and the lower it is, the stronger the effect. `None` turns
+    /// it off, which is the default.
+    pub fn set_speed_of_sound(&self, speed: Option<f32>) {
+        self.mixer.lock().unwrap().speed_of_sound = speed;
+    }
+
+    /// What `play_at` uses. Sounds that are already playing keep theirs.
+    pub fn set_default_attenuation(&mut self, attenuation: Attenuation) {
+        self.default_attenuation = attenuation;
+    }
+
+    pub fn get_volume_settings(&self) -> VolumeSettings {
+        let mixer = self.mixer.lock().unwrap();
+
+        return VolumeSettings {
+            buses: mixer
+                .buses
+                .iter()
+                .map(|bus| BusVolume {
+                    name: bus.name.clone(),
+                    volume: bus.volume,
+                    muted: bus.muted,
+                })
+                .collect(),
+        };
+    }
+
+    /// Buses that aren't in `settings` are left as they are, and buses that
+    /// don't exist yet are added.
+    pub fn apply_volume_settings(&self, settings: &VolumeSettings) {
+        for bus in &settings.buses {
+            self.add_bus(&bus.name);
+            self.set_bus_volume(&bus.name, bus.volume);
+            self.set_bus_muted(&bus.name, bus.muted);
+        }
+    }
+
+    /// Should be called once a frame. Music is decoded here, and backends
+    /// without their own audio thread mix `delta_time` seconds of audio.
+    pub fn update(&mut self, delta_time: f64) -> Result<(), Error> {
+        // The music is decoded before it's mixed, so backends that mix here
+        // don't run out of it.
+        self.streams.get_mut().retain_mut(|stream| {
+            let ahead = STREAM_AHEAD_SECONDS + delta_time.max(0.0);
+            stream.fill(seconds_to_frames(stream.get_sample_rate(), ahead))
+        });
+
+        return self.backend.update(delta_time);
+    }
+
+    /// Mixes the next `output.len() / 2` frames of interleaved stereo into
+    /// `output` directly, without going through the backend. Music only gets
+    /// decoded in `update`, so that still has to be called.
+    pub fn mix(&self, output: &mut [f32]) {
+        self.mixer.lock().unwrap().mix(output);
+    }
+
+    pub fn get_sample_rate(&self) -> u32 {
+        self.mixer.lock().unwrap().get_sample_rate()
+    }
+}
+
+fn seconds_to_frames(sample_rate: u32, seconds: f64) -> u64 {
+    (seconds.max(0.0) * sample_rate as f64).round() as u64
+}
+
+/// Controls a sound after it's started playing. Once the sound has finished
+/// or been stopped, all of these do nothing.
+#[derive(Clone)]
+pub struct SoundHandle {
+    id: u64,
+    mixer: Arc<Mutex<Mixer>>,
+}
+
+impl SoundHandle {
+    fn with_voice(&self, f: impl FnOnce(&mut mixer::Voice, u32)) {
+        let mut mixer = self.mixer.lock().unwrap();
+        let sample_rate = mixer.get_sample_rate();
+
+        if let Some(voice) = mixer.voice_mut(self.id) {
+            f(voice, sample_rate);
+        }
+    }
+
+    pub fn set_volume(&self, volume: f32) {
+        self.with_voice(|voice, _| voice.volume = volume);
+    }
+
+    /// From -1 (left) to 1 (right).
+    pub fn set_pan(&self, pan: f32) {
+        self.with_voice(|voice, _| voice.pan = pan);
+    }
+
+    pub fn set_pitch(&self, pitch: f32) {
+        self.with_voice(|voice, _| voice.pitch = pitch);
+    }
+
+    pub fn set_looping(&self, looping: bool) {
+        self.with_voice(|voice, _| voice.looping = looping);
+    }
+
+    /// Only does anything for sounds played with `Audio::play_at`.
+    pub fn set_position(&self, position: Vector2<f32>) {
+        self.with_voice(|voice, _| {
+            if let Some(spatial) = &mut voice.spatial {
+                spatial.position = position;
+            }
+        });
+    }
+
+    /// In world units per second, for Doppler.
+    pub fn set_velocity(&self, velocity: Vector2<f32>) {
+        self.with_voice(|voice, _| {
+            if let Some(spatial) = &mut voice.spatial {
+                spatial.velocity = velocity;
+            }
+        });
+    }
+
+    pub fn set_attenuation(&self, attenuation: Attenuation) {
+        self.with_voice(|voice, _| {
+            if let Some(spatial) = &mut voice.spatial {
+                spatial.attenuation = attenuation;
+            }
+        });
+    }
+
+    pub fn pause(&self) {
+        self.with_voice(|voice, _| voice.paused = true);
+    }
+
+    pub fn resume(&self) {
+        self.with_voice(|voice, _| voice.paused = false);
+    }
+
+    pub fn stop(&self) {
+        self.mixer.lock().unwrap().remove_voice(self.id);
+    }
+
+    /// Fades from silent up to full volume, resuming the sound if it was
+    /// paused.
+    pub fn fade_in(&self, seconds: f64) {
+        self.with_voice(|voice, sample_rate| {
+            voice.paused = false;
+            voice.set_fade_gain(0.0);
+            voice.fade(1.0, seconds_to_frames(sample_rate, seconds), false);
+        });
+    }
+
+    /// Fades down to silent and then stops the sound.
+    pub fn fade_out(&self, seconds: f64) {
+        self.with_voice(|voice, sample_rate| {
+            // At least one frame, since a fade that's already over never
+            // gets to stop the sound.
+            let frames = seconds_to_frames(sample_rate, seconds).max(1);
+            voice.fade(0.0, frames, true);
+        });
+    }
+
+    /// Whether the sound hasn't finished or been stopped yet. Paused sounds
+    /// are still playing.
+    pub fn is_playing(&self) -> bool {
+        self.mixer.lock().unwrap().voice_mut(self.id).is_some()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        match self.mixer.lock().unwrap().voice_mut(self.id) {
+            Some(voice) => voice.paused,
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,202 @@
+use super::Mixer;
+use crate::Error;
+use std::sync::{Arc, Mutex};
+
+/// Where the mixed audio goes. Backends with their own audio thread pull from
+/// the mixer whenever they need more, and the others mix in `update`.
+pub trait AudioBackend {
+    fn get_sample_rate(&self) -> u32;
+
+    /// Called once, when the backend is given to `Audio::new`.
+    fn start(&mut self, mixer: Arc<Mutex<Mixer>>) -> Result<(), Error>;
+
+    /// Called from `Audio::update`, once a frame.
+    fn update(&mut self, _delta_time: f64) -> Result<(), Error> {
+        return Ok(());
+    }
+}
+
+// Mixes however many frames `delta_time` is worth. The fraction of a frame
+// that's left over is carried to the next call, so nothing drifts.
+fn mix_elapsed(mixer: &Mutex<Mixer>, delta_time: f64, leftover: &mut f64, buffer: &mut Vec<f32>) {
+    let mut mixer = mixer.lock().unwrap();
+
+    let frames = delta_time.max(0.0) * mixer.get_sample_rate() as f64 + *leftover;
+    *leftover = frames.fract();
+
+    buffer.resize(frames as usize * 2, 0.0);
+    mixer.mix(buffer);
+}
+
+/// Mixes in `update` and throws the audio away, for when there's no sound
+/// device, like on a server or in tests. Sounds still play and end on time.
+pub struct NullBackend {
+    sample_rate: u32,
+    mixer: Option<Arc<Mutex<Mixer>>>,
+    leftover: f64,
+    buffer: Vec<f32>,
+}
+
+impl NullBackend {
+    pub fn new(sample_rate: u32) -> NullBackend {
+        NullBackend {
+            sample_rate,
+            mixer: None,
+            leftover: 0.0,
+            buffer: Vec::new(),
+        }
+    }
+}
+
+impl AudioBackend for NullBackend {
+    fn get_sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn start(&mut self, mixer: Arc<Mutex<Mixer>>) -> Result<(), Error> {
+        self.mixer = Some(mixer);
+        return Ok(());
+    }
+
+    fn update(&mut self, delta_time: f64) -> Result<(), Error> {
+        if let Some(mixer) = &self.mixer {
+            mix_elapsed(mixer, delta_time, &mut self.leftover, &mut self.buffer);
+        }
+
+        return Ok(());
+    }
+}
+
+/// Mixes in `update` and writes everything to a 32-bit float stereo WAV file.
+/// The file is finished when the backend is dropped.
+pub struct WavFileBackend {
+    path: String,
+    sample_rate: u32,
+    writer: Option<hound::WavWriter<std::io::BufWriter<std::fs::File>>>,
+    mixer: Option<Arc<Mutex<Mixer>>>,
+    leftover: f64,
+    buffer: Vec<f32>,
+}
+
+impl WavFileBackend {
+    pub fn new(path: &str, sample_rate: u32) -> WavFileBackend {
+        WavFileBackend {
+            path: path.to_string(),
+            sample_rate,
+            writer: None,
+            mixer: None,
+            leftover: 0.0,
+            buffer: Vec::new(),
+        }
+    }
+
+    fn backend_error(&self, error: hound::Error) -> Error {
+        Error::AudioBackend(format!("failed to write {}: {}", self.path, error))
+    }
+}
+
+impl AudioBackend for WavFileBackend {
+    fn get_sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn start(&mut self, mixer: Arc<Mutex<Mixer>>) -> Result<(), Error> {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: self.sample_rate,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+
+        let writer = hound::WavWriter::create(&self.path, spec)
+            .map_err(|error| self.backend_error(error))?;
+
+        self.writer = Some(writer);
+        self.mixer = Some(mixer);
+
+        return Ok(());
+    }
+
+    fn update(&mut self, delta_time: f64) -> Result<(), Error> {
+        let (Some(mixer), Some(writer)) = (&self.mixer, &mut self.writer) else {
+            return Ok(());
+        };
+
+        mix_elapsed(mixer, delta_time, &mut self.leftover, &mut self.buffer);
+
+        for sample in &self.buffer {
+            if let Err(error) = writer.write_sample(*sample) {
+                return Err(self.backend_error(error));
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+/// Plays through the default output device, with cpal. The mixer runs on
+/// cpal's audio thread, so `update` doesn't do anything.
+#[cfg(feature = "cpal")]
+pub struct CpalBackend {
+    device: cpal::Device,
+    config: cpal::StreamConfig,
+    stream: Option<cpal::Stream>,
+}
+
+#[cfg(feature = "cpal")]
+impl CpalBackend {
+    pub fn new() -> Result<CpalBackend, Error> {
+        use cpal::traits::{DeviceTrait, HostTrait};
+
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or_else(|| Error::AudioBackend("there's no output device".to_string()))?;
+
+        let default_config = device
+            .default_output_config()
+            .map_err(|error| Error::AudioBackend(error.to_string()))?;
+
+        // The mixer always makes stereo, so that's what's asked for, at the
+        // device's own sample rate.
+        let config = cpal::StreamConfig {
+            channels: 2,
+            sample_rate: default_config.sample_rate(),
+            buffer_size: cpal::BufferSize::Default,
+        };
+
+        return Ok(CpalBackend {
+            device,
+            config,
+            stream: None,
+        });
+    }
+}
+
+#[cfg(feature = "cpal")]
+impl AudioBackend for CpalBackend {
+    fn get_sample_rate(&self) -> u32 {
+        self.config.sample_rate.0
+    }
+
+    fn start(&mut self, mixer: Arc<Mutex<Mixer>>) -> Result<(), Error> {
+        use cpal::traits::{DeviceTrait, StreamTrait};
+
+        let stream = self
+            .device
+            .build_output_stream(
+                &self.config,
+                move |output: &mut [f32], _| mixer.lock().unwrap().mix(output),
+                |error| log::error!("Audio stream error: {}", error),
+                None,
+            )
+            .map_err(|error| Error::AudioBackend(error.to_string()))?;
+
+        stream
+            .play()
+            .map_err(|error| Error::AudioBackend(error.to_string()))?;
+
+        self.stream = Some(stream);
+
+        return Ok(());
+    }
+}
@@ -0,0 +1,88 @@
+use super::effects::Effect;
+
+/// A group of sounds that share a volume and effects. Every bus other than
+/// the master bus is mixed into the master bus.
+pub(super) struct Bus {
+    pub(super) name: String,
+    pub(super) volume: f32,
+    pub(super) muted: bool,
+    pub(super) effects: Vec<Box<dyn Effect>>,
+    pub(super) buffer: Vec<f32>,
+    // What the gain was at the end of the last mix. Changes are spread over
+    // a whole mix, so they don't click.
+    last_gain: f32,
+}
+
+impl Bus {
+    pub(super) fn new(name: &str) -> Bus {
+        Bus {
+            name: name.to_string(),
+            volume: 1.0,
+            muted: false,
+            effects: Vec::new(),
+            buffer: Vec::new(),
+            last_gain: 1.0,
+        }
+    }
+
+    /// Runs the effects on what's been mixed into the bus, and then applies
+    /// `volume`, `muted` and `ducking`.
+    pub(super) fn finish(&mut self, sample_rate: u32, ducking: f32) {
+        for effect in &mut self.effects {
+            effect.process(&mut self.buffer, sample_rate);
+        }
+
+        let gain = if self.muted {
+            0.0
+        } else {
+            self.volume * ducking
+        };
+
+        let frames = (self.buffer.len() / 2).max(1) as f32;
+
+        for (i, frame) in self.buffer.chunks_exact_mut(2).enumerate() {
+            let t = (i + 1) as f32 / frames;
+            let frame_gain = self.last_gain + (gain - self.last_gain) * t;
+
+            frame[0] *= frame_gain;
+            frame[1] *= frame_gain;
+        }
+
+        self.last_gain = gain;
+    }
+}
+
+/// Turns `target` down to `volume` while anything on `trigger` is playing.
+pub(super) struct Duck {
+    pub(super) target: usize,
+    pub(super) trigger: usize,
+    pub(super) volume: f32,
+    /// How long it takes to go down, and to come back up, in seconds.
+    pub(super) attack: f64,
+    pub(super) release: f64,
+    pub(super) gain: f32,
+}
+
+impl Duck {
+    /// Moves `gain` towards where it should be, `seconds` later.
+    pub(super) fn update(&mut self, triggered: bool, seconds: f64) {
+        let (target, time) = if triggered {
+            (self.volume, self.attack)
+        } else {
+            (1.0, self.release)
+        };
+
+        if time <= 0.0 {
+            self.gain = target;
+            return;
+        }
+
+        let step = ((1.0 - self.volume).abs() as f64 * seconds / time) as f32;
+
+        if self.gain < target {
+            self.gain = (self.gain + step).min(target);
+        } else {
+            self.gain = (self.gain - step).max(target);
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use crate::Error;
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read, Seek, SeekFrom};
+
+// About how many samples a WAV chunk has. It's rounded down to whole frames,
+// so that every chunk starts with the first channel. The other formats have
+// their own packet and block sizes.
+const WAV_CHUNK_SAMPLES: usize = 4096;
+
+/// Reads interleaved samples from an audio file, one chunk at a time.
+pub(super) trait Decoder: Send {
+    fn channels(&self) -> u16;
+
+    fn sample_rate(&self) -> u32;
+
+    /// Returns the next chunk of interleaved samples, or `None` at the end.
+    fn next_chunk(&mut self) -> Result<Option<Vec<f32>>, Error>;
+}
+
+/// Opens a WAV, OGG Vorbis or FLAC file, going by what's at the start of the
+/// file rather than its extension.
+pub(super) fn open(path: &str) -> Result<Box<dyn Decoder>, Error> {
+    let io_error = |error| Error::Io {
+        path: path.to_string(),
+        error,
+    };
+
+    let mut reader = BufReader::new(File::open(path).map_err(io_error)?);
+
+    let mut magic = [0u8; 4];
+    reader
+        .read_exact(&mut magic)
+        .map_err(|error| match error.kind() {
+            ErrorKind::UnexpectedEof => Error::AudioDecode {
+                path: path.to_string(),
+                message: "it's too short to be an audio file".to_string(),
+            },
+            _ => io_error(error),
+        })?;
+    reader.seek(SeekFrom::Start(0)).map_err(io_error)?;
+
+    match &magic {
+        b"RIFF" => WavDecoder::open(path, reader),
+        b"OggS" => VorbisDecoder::open(path, reader),
+        b"fLaC" => FlacDecoder::open(path, reader),
+        _ => Err(Error::AudioDecode {
+            path: path.to_string(),
+            message: "it's not a WAV, OGG Vorbis or FLAC file".to_string(),
+        }),
+    }
+}
+
+fn decode_error(path: &str, error: impl std::fmt::Display) -> Error {
+    Error::AudioDecode {
+        path: path.to_string(),
+        message: error.to_string(),
+    }
+}
+
+struct WavDecoder {
+    path: String,
+    reader: hound::WavReader<BufReader<File>>,
+}
+
+impl WavDecoder {
+    fn open(path: &str, reader: BufReader<File>) -> Result<Box<dyn Decoder>, Error> {
+        let reader = hound::WavReader::new(reader).map_err(|error| decode_error(path, error))?;
+
+        return Ok(Box::new(WavDecoder {
+            path: path.to_string(),
+            reader,
+        }));
+    }
+}
+
+impl Decoder for WavDecoder {
+    fn channels(&self) -> u16 {
+        self.reader.spec().channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.reader.spec().sample_rate
+    }
+
+    fn next_chunk(&mut self) -> Result<Option<Vec<f32>>, Error> {
+        let spec = self.reader.spec();
+        let channels = spec.channels.max(1) as usize;
+        let length = WAV_CHUNK_SAMPLES / channels * channels;
+
+        let chunk: Result<Vec<f32>, hound::Error> = match spec.sample_format {
+            hound::SampleFormat::Float => self.reader.samples::<f32>().take(length).collect(),
+            hound::SampleFormat::Int => {
+                let scale = 1.0 / (1u32 << (spec.bits_per_sample - 1)) as f32;
+
+                self.reader
+                    .samples::<i32>()
+                    .take(length)
+                    .map(|sample| sample.map(|sample| sample as f32 * scale))
+                    .collect()
+            }
+        };
+
+        let chunk = chunk.map_err(|error| decode_error(&self.path, error))?;
+
+        if chunk.is_empty() {
+            return Ok(None);
+        }
+
+        return Ok(Some(chunk));
+    }
+}
+
+struct VorbisDecoder {
+    path: String,
+    reader: lewton::inside_ogg::OggStreamReader<BufReader<File>>,
+}
+
+impl VorbisDecoder {
+    fn open(path: &str, reader: BufReader<File>) -> Result<Box<dyn Decoder>, Error> {
+        let reader = lewton::inside_ogg::OggStreamReader::new(reader)
+            .map_err(|error| decode_error(path, error))?;
+
+        return Ok(Box::new(VorbisDecoder {
+            path: path.to_string(),
+            reader,
+        }));
+    }
+}
+
+impl Decoder for VorbisDecoder {
+    fn channels(&self) -> u16 {
+        self.reader.ident_hdr.audio_channels as u16
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.reader.ident_hdr.audio_sample_rate
+    }
+
+    fn next_chunk(&mut self) -> Result<Option<Vec<f32>>, Error> {
+        loop {
+            let packet = self
+                .reader
+                .read_dec_packet_itl()
+                .map_err(|error| decode_error(&self.path, error))?;
+
+            match packet {
+                // Some packets don't have any audio in them.
+                Some(samples) if samples.is_empty() => continue,
+                Some(samples) => {
+                    return Ok(Some(
+                        samples
+                            .iter()
+                            .map(|sample| *sample as f32 / 32768.0)
+                            .collect(),
+                    ))
+                }
+                None => return Ok(None),
+            }
+        }
+    }
+}
+
+struct FlacDecoder {
+    path: String,
+    reader: claxon::FlacReader<BufReader<File>>,
+    // Reused between blocks.
+    buffer: Vec<i32>,
+}
+
+impl FlacDecoder {
+    fn open(path: &str, reader: BufReader<File>) -> Result<Box<dyn Decoder>, Error> {
+        let reader = claxon::FlacReader::new(reader).map_err(|error| decode_error(path, error))?;
+
+        return Ok(Box::new(FlacDecoder {
+            path: path.to_string(),
+            reader,
+            buffer: Vec::new(),
+        }));
+    }
+}
+
+impl Decoder for FlacDecoder {
+    fn channels(&self) -> u16 {
+        self.reader.streaminfo().channels as u16
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.reader.streaminfo().sample_rate
+    }
+
+    fn next_chunk(&mut self) -> Result<Option<Vec<f32>>, Error> {
+        let scale = 1.0 / (1u32 << (self.reader.streaminfo().bits_per_sample - 1)) as f32;
+        let buffer = std::mem::take(&mut self.buffer);
+
+        let block = match self.reader.blocks().read_next_or_eof(buffer) {
+            Ok(Some(block)) => block,
+            Ok(None) => return Ok(None),
+            Err(error) => return Err(decode_error(&self.path, error)),
+        };
+
+        // Blocks store each channel separately.
+        let mut chunk = Vec::with_capacity(block.len() as usize);
+
+        for i in 0..block.duration() {
+            for channel in 0..block.channels() {
+                chunk.push(block.sample(channel, i) as f32 * scale);
+            }
+        }
+
+        self.buffer = block.into_buffer();
+
+        return Ok(Some(chunk));
+    }
+}
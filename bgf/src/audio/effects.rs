@@ -0,0 +1,173 @@
+//! Effects that can be put on a mixer bus with `Audio::add_bus_effect`.
+
+use std::f32::consts::PI;
+
+/// Changes a bus's audio after its sounds have been mixed together, and
+/// before its volume is applied.
+pub trait Effect: Send {
+    /// `samples` is interleaved stereo.
+    fn process(&mut self, samples: &mut [f32], sample_rate: u32);
+}
+
+/// Takes out everything above `cutoff` Hz, like hearing things through a
+/// wall or underwater. It's a gentle filter, so it works best when the cutoff
+/// is fairly low.
+pub struct LowPass {
+    pub cutoff: f32,
+    previous: [f32; 2],
+}
+
+impl LowPass {
+    pub fn new(cutoff: f32) -> LowPass {
+        LowPass {
+            cutoff,
+            previous: [0.0, 0.0],
+        }
+    }
+}
+
+impl Effect for LowPass {
+    fn process(&mut self, samples: &mut [f32], sample_rate: u32) {
+        let amount = 1.0 - (-2.0 * PI * self.cutoff.max(0.0) / sample_rate as f32).exp();
+
+        for frame in samples.chunks_exact_mut(2) {
+            for (sample, previous) in frame.iter_mut().zip(self.previous.iter_mut()) {
+                *previous += amount * (*sample - *previous);
+                *sample = *previous;
+            }
+        }
+    }
+}
+
+// The lengths from Freeverb, which were picked for 44100 Hz. The right
+// channel's lines are a little longer so the two sides don't sound the same.
+const COMB_LENGTHS: [usize; 4] = [1116, 1188, 1277, 1356];
+const ALLPASS_LENGTHS: [usize; 2] = [556, 441];
+const STEREO_SPREAD: usize = 23;
+
+struct Comb {
+    buffer: Vec<f32>,
+    index: usize,
+    filtered: f32,
+}
+
+impl Comb {
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let output = self.buffer[self.index];
+        self.filtered = output * (1.0 - damping) + self.filtered * damping;
+        self.buffer[self.index] = input + self.filtered * feedback;
+        self.index = (self.index + 1) % self.buffer.len();
+
+        return output;
+    }
+}
+
+struct Allpass {
+    buffer: Vec<f32>,
+    index: usize,
+}
+
+impl Allpass {
+    fn process(&mut self, input: f32) -> f32 {
+        let delayed = self.buffer[self.index];
+        self.buffer[self.index] = input + delayed * 0.5;
+        self.index = (self.index + 1) % self.buffer.len();
+
+        return delayed - input;
+    }
+}
+
+struct ReverbChannel {
+    combs: Vec<Comb>,
+    allpasses: Vec<Allpass>,
+}
+
+impl ReverbChannel {
+    fn new(sample_rate: u32, spread: usize) -> ReverbChannel {
+        let scale = |length: usize| ((length + spread) * sample_rate as usize / 44100).max(1);
+
+        ReverbChannel {
+            combs: COMB_LENGTHS
+                .iter()
+                .map(|length| Comb {
+                    buffer: vec![0.0; scale(*length)],
+                    index: 0,
+                    filtered: 0.0,
+                })
+                .collect(),
+            allpasses: ALLPASS_LENGTHS
+                .iter()
+                .map(|length| Allpass {
+                    buffer: vec![0.0; scale(*length)],
+                    index: 0,
+                })
+                .collect(),
+        }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let mut output = 0.0;
+
+        for comb in &mut self.combs {
+            output += comb.process(input, feedback, damping);
+        }
+
+        for allpass in &mut self.allpasses {
+            output = allpass.process(output);
+        }
+
+        return output;
+    }
+}
+
+/// A small room reverb, based on Freeverb. All of the settings go from 0 to
+/// 1, and `mix` is how much of the reverb is heard over the original sound.
+pub struct Reverb {
+    pub room_size: f32,
+    pub damping: f32,
+    pub mix: f32,
+    // Made on the first call, once the sample rate is known.
+    channels: Option<(u32, [ReverbChannel; 2])>,
+}
+
+impl Reverb {
+    pub fn new(room_size: f32, damping: f32, mix: f32) -> Reverb {
+        Reverb {
+            room_size,
+            damping,
+            mix,
+            channels: None,
+        }
+    }
+}
+
+impl Effect for Reverb {
+    fn process(&mut self, samples: &mut [f32], sample_rate: u32) {
+        if !matches!(&self.channels, Some((rate, _)) if *rate == sample_rate) {
+            self.channels = Some((
+                sample_rate,
+                [
+                    ReverbChannel::new(sample_rate, 0),
+                    ReverbChannel::new(sample_rate, STEREO_SPREAD),
+                ],
+            ));
+        }
+
+        let (_, channels) = self.channels.as_mut().unwrap();
+
+        let feedback = 0.7 + 0.28 * self.room_size.clamp(0.0, 1.0);
+        let damping = 0.4 * self.damping.clamp(0.0, 1.0);
+        let mix = self.mix.clamp(0.0, 1.0);
+
+        for frame in samples.chunks_exact_mut(2) {
+            // The same quiet input goes into both sides, and the output is
+            // made louder again afterwards, like in Freeverb.
+            let input = (frame[0] + frame[1]) * 0.015;
+
+            for (sample, channel) in frame.iter_mut().zip(channels.iter_mut()) {
+                let wet = channel.process(input, feedback, damping) * 3.0;
+                *sample = *sample * (1.0 - mix) + wet * mix;
+            }
+        }
+    }
+}
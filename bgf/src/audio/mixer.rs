@@ -0,0 +1,536 @@
+use super::bus::{Bus, Duck};
+use super::decoder::{self, Decoder};
+use super::spatial::{Listener, Spatial};
+use crate::math::Vector2;
+use crate::Error;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Fully decoded audio, as interleaved samples.
+pub(super) struct SoundData {
+    pub(super) samples: Vec<f32>,
+    pub(super) channels: u16,
+    pub(super) sample_rate: u32,
+}
+
+impl SoundData {
+    fn frame_count(&self) -> u64 {
+        (self.samples.len() / self.channels.max(1) as usize) as u64
+    }
+
+    fn frame(&self, index: u64) -> Option<[f32; 2]> {
+        let channels = self.channels.max(1) as usize;
+        let start = index as usize * channels;
+        let frame = self.samples.get(start..start + channels)?;
+
+        return Some(to_stereo(frame));
+    }
+}
+
+// Mono is played on both sides, and anything past the first two channels is
+// left out.
+fn to_stereo(frame: &[f32]) -> [f32; 2] {
+    match frame {
+        [mono] => [*mono, *mono],
+        [left, right, ..] => [*left, *right],
+        _ => [0.0, 0.0],
+    }
+}
+
+/// The frames of a stream that have been decoded ahead of where it's playing.
+/// Files are only ever read by the `StreamDecoder`, so the mixer never has to
+/// wait on one while it's locked.
+struct StreamBuffer {
+    // The first one is frame number `start`, counting every time through the
+    // file one after the other.
+    frames: VecDeque<[f32; 2]>,
+    start: u64,
+    // Where each time through the file that's been decoded so far ends.
+    ends: VecDeque<u64>,
+    // Copied from the voice, so that the decoder knows whether to go back to
+    // the start of the file.
+    looping: bool,
+    // Nothing more is coming, because the file couldn't be decoded.
+    finished: bool,
+}
+
+impl StreamBuffer {
+    fn frame(&self, index: u64) -> Option<[f32; 2]> {
+        let offset = index.checked_sub(self.start)?;
+        return self.frames.get(offset as usize).copied();
+    }
+
+    fn discard_before(&mut self, index: u64) {
+        while self.start < index && !self.frames.is_empty() {
+            self.frames.pop_front();
+            self.start += 1;
+        }
+    }
+}
+
+/// Audio that's decoded a little at a time while it plays, for music. This is
+/// the half the mixer plays from.
+pub(super) struct Stream {
+    sample_rate: u32,
+    buffer: Arc<Mutex<StreamBuffer>>,
+}
+
+impl Stream {
+    // Streams run on from one time through the file into the next, so unlike
+    // buffered sounds, the position never has to wrap around.
+    fn sample(&mut self, position: f64, looping: bool) -> Option<[f32; 2]> {
+        let mut buffer = self.buffer.lock().unwrap();
+        let index = position as u64;
+
+        buffer.looping = looping;
+        buffer.discard_before(index);
+
+        if looping {
+            while buffer.ends.front().is_some_and(|end| *end <= index) {
+                buffer.ends.pop_front();
+            }
+        }
+
+        let end = buffer.ends.front().copied().unwrap_or(u64::MAX);
+
+        if index >= end {
+            return None;
+        }
+
+        let current = match buffer.frame(index) {
+            Some(frame) => frame,
+            None if buffer.finished => return None,
+            // The decoder has fallen behind, which is a gap rather than the
+            // end.
+            None => return Some([0.0, 0.0]),
+        };
+
+        let next = match index + 1 < end {
+            true => buffer.frame(index + 1).unwrap_or(current),
+            false => current,
+        };
+
+        return Some(interpolate(current, next, position));
+    }
+}
+
+/// The half of a stream that reads the file. `Audio` keeps these and fills
+/// them up in `Audio::update`, outside of the mixer's lock.
+pub(super) struct StreamDecoder {
+    path: String,
+    decoder: Box<dyn Decoder>,
+    buffer: Arc<Mutex<StreamBuffer>>,
+    // How many frames have been decoded altogether, and how many of those
+    // were from this time through the file.
+    decoded: u64,
+    decoded_this_time: u64,
+    at_end: bool,
+}
+
+pub(super) fn open_stream(path: &str, looping: bool) -> Result<(Stream, StreamDecoder), Error> {
+    let decoder = decoder::open(path)?;
+
+    let buffer = Arc::new(Mutex::new(StreamBuffer {
+        frames: VecDeque::new(),
+        start: 0,
+        ends: VecDeque::new(),
+        looping,
+        finished: false,
+    }));
+
+    let stream = Stream {
+        sample_rate: decoder.sample_rate(),
+        buffer: buffer.clone(),
+    };
+
+    let stream_decoder = StreamDecoder {
+        path: path.to_string(),
+        decoder,
+        buffer,
+        decoded: 0,
+        decoded_this_time: 0,
+        at_end: false,
+    };
+
+    return Ok((stream, stream_decoder));
+}
+
+impl StreamDecoder {
+    pub(super) fn get_sample_rate(&self) -> u32 {
+        self.decoder.sample_rate()
+    }
+
+    /// Decodes until there are at least `frames` frames ready ahead of where
+    /// the stream is playing. Returns false once the stream has stopped
+    /// playing or there's nothing more to decode, after which it can be
+    /// dropped.
+    pub(super) fn fill(&mut self, frames: u64) -> bool {
+        loop {
+            // The voice is gone, so nothing is playing this anymore.
+            if Arc::strong_count(&self.buffer) == 1 {
+                return false;
+            }
+
+            let (ready, looping) = {
+                let buffer = self.buffer.lock().unwrap();
+                (self.decoded.saturating_sub(buffer.start), buffer.looping)
+            };
+
+            if ready >= frames {
+                return true;
+            }
+
+            if self.at_end {
+                // Looping might still be turned on before it gets there.
+                if !looping {
+                    return true;
+                }
+
+                if !self.rewind() {
+                    return false;
+                }
+            }
+
+            match self.decoder.next_chunk() {
+                Ok(Some(chunk)) => {
+                    let channels = self.decoder.channels().max(1) as usize;
+                    let chunk_frames: Vec<[f32; 2]> =
+                        chunk.chunks_exact(channels).map(to_stereo).collect();
+
+                    self.decoded += chunk_frames.len() as u64;
+                    self.decoded_this_time += chunk_frames.len() as u64;
+                    self.buffer.lock().unwrap().frames.extend(chunk_frames);
+                }
+                Ok(None) => {
+                    self.buffer.lock().unwrap().ends.push_back(self.decoded);
+                    self.at_end = true;
+
+                    // An empty file would be opened again forever.
+                    if self.decoded_this_time == 0 {
+                        self.finish();
+                        return false;
+                    }
+                }
+                Err(error) => {
+                    log::error!("Stopped streaming {}: {}", self.path, error);
+                    self.buffer.lock().unwrap().ends.push_back(self.decoded);
+                    self.finish();
+                    return false;
+                }
+            }
+        }
+    }
+
+    // Opening the file again is the one way to go back to the start that
+    // works for every format.
+    fn rewind(&mut self) -> bool {
+        match decoder::open(&self.path) {
+            Ok(decoder) => {
+                self.decoder = decoder;
+                self.decoded_this_time = 0;
+                self.at_end = false;
+                return true;
+            }
+            Err(error) => {
+                log::error!("Failed to loop {}: {}", self.path, error);
+                self.finish();
+                return false;
+            }
+        }
+    }
+
+    fn finish(&mut self) {
+        self.buffer.lock().unwrap().finished = true;
+    }
+}
+
+pub(super) enum Source {
+    Buffered(Arc<SoundData>),
+    Streamed(Stream),
+}
+
+// `position` is between the two frames, and only its fraction is used.
+fn interpolate(current: [f32; 2], next: [f32; 2], position: f64) -> [f32; 2] {
+    let t = position.fract() as f32;
+
+    return [
+        current[0] + (next[0] - current[0]) * t,
+        current[1] + (next[1] - current[1]) * t,
+    ];
+}
+
+impl Source {
+    fn sample_rate(&self) -> u32 {
+        match self {
+            Source::Buffered(data) => data.sample_rate,
+            Source::Streamed(stream) => stream.sample_rate,
+        }
+    }
+
+    /// Returns the frame at `position`, interpolated between the two frames
+    /// around it. When a looping sound runs out, `position` wraps around.
+    fn sample(&mut self, position: &mut f64, looping: bool) -> Option<[f32; 2]> {
+        let data = match self {
+            Source::Buffered(data) => data,
+            Source::Streamed(stream) => return stream.sample(*position, looping),
+        };
+
+        loop {
+            let index = *position as u64;
+
+            if let Some(current) = data.frame(index) {
+                let next = data.frame(index + 1).unwrap_or(current);
+                return Some(interpolate(current, next, *position));
+            }
+
+            let length = data.frame_count();
+
+            if !looping || length == 0 {
+                return None;
+            }
+
+            *position %= length as f64;
+        }
+    }
+}
+
+struct Fade {
+    from: f32,
+    to: f32,
+    length: u64,
+    elapsed: u64,
+    stop_at_end: bool,
+}
+
+pub(super) struct Voice {
+    pub(super) id: u64,
+    source: Source,
+    pub(super) bus: usize,
+    // In frames of the source, which can be between two frames.
+    position: f64,
+    pub(super) volume: f32,
+    pub(super) pan: f32,
+    pub(super) pitch: f32,
+    pub(super) looping: bool,
+    pub(super) paused: bool,
+    fade: Option<Fade>,
+    fade_gain: f32,
+    pub(super) spatial: Option<Spatial>,
+}
+
+impl Voice {
+    pub(super) fn new(id: u64, source: Source, bus: usize) -> Voice {
+        Voice {
+            id,
+            source,
+            bus,
+            position: 0.0,
+            volume: 1.0,
+            pan: 0.0,
+            pitch: 1.0,
+            looping: false,
+            paused: false,
+            fade: None,
+            fade_gain: 1.0,
+            spatial: None,
+        }
+    }
+
+    /// Fades from the current fade level to `to` over `length` frames of the
+    /// output.
+    pub(super) fn fade(&mut self, to: f32, length: u64, stop_at_end: bool) {
+        if length == 0 {
+            self.fade_gain = to;
+            self.fade = None;
+            return;
+        }
+
+        self.fade = Some(Fade {
+            from: self.fade_gain,
+            to,
+            length,
+            elapsed: 0,
+            stop_at_end,
+        });
+    }
+
+    pub(super) fn set_fade_gain(&mut self, gain: f32) {
+        self.fade_gain = gain;
+        self.fade = None;
+    }
+
+    /// Adds the voice to `output`, and returns whether it's still playing
+    /// afterwards.
+    fn mix(
+        &mut self,
+        output: &mut [f32],
+        output_sample_rate: u32,
+        listener: &Listener,
+        speed_of_sound: Option<f32>,
+    ) -> bool {
+        if self.paused {
+            return true;
+        }
+
+        let (spatial_gain, spatial_pan, spatial_pitch) = match &self.spatial {
+            Some(spatial) => spatial.compute(listener, speed_of_sound),
+            None => (1.0, 0.0, 1.0),
+        };
+
+        let step = (self.pitch * spatial_pitch).max(0.0) as f64 * self.source.sample_rate() as f64
+            / output_sample_rate as f64;
+
+        // The louder side stays at full volume, so centered sounds aren't
+        // any quieter than they'd be without panning.
+        let pan = (self.pan + spatial_pan).clamp(-1.0, 1.0);
+        let left_gain = (1.0 - pan).min(1.0);
+        let right_gain = (1.0 + pan).min(1.0);
+
+        for frame in output.chunks_exact_mut(2) {
+            let sample = match self.source.sample(&mut self.position, self.looping) {
+                Some(sample) => sample,
+                None => return false,
+            };
+
+            let gain = self.volume * self.fade_gain * spatial_gain;
+            frame[0] += sample[0] * gain * left_gain;
+            frame[1] += sample[1] * gain * right_gain;
+
+            self.position += step;
+
+            if let Some(fade) = &mut self.fade {
+                fade.elapsed += 1;
+                let t = fade.elapsed as f32 / fade.length as f32;
+                self.fade_gain = fade.from + (fade.to - fade.from) * t.min(1.0);
+
+                if fade.elapsed >= fade.length {
+                    let stop = fade.stop_at_end;
+                    self.fade = None;
+
+                    if stop {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        return true;
+    }
+}
+
+/// The buses every mixer starts with. The first one is the master bus.
+pub(super) const DEFAULT_BUSES: [&str; 4] = ["master", "music", "sfx", "voice"];
+
+/// Mixes everything that's playing into interleaved stereo at a fixed
+/// sample rate. Backends pull audio out of it with `mix`.
+pub struct Mixer {
+    sample_rate: u32,
+    pub(super) voices: Vec<Voice>,
+    next_id: u64,
+    pub(super) buses: Vec<Bus>,
+    pub(super) ducks: Vec<Duck>,
+    pub(super) listener: Listener,
+    /// In world units per second. `None` turns off Doppler.
+    pub(super) speed_of_sound: Option<f32>,
+}
+
+impl Mixer {
+    pub(super) fn new(sample_rate: u32) -> Mixer {
+        Mixer {
+            sample_rate,
+            voices: Vec::new(),
+            next_id: 0,
+            buses: DEFAULT_BUSES.iter().map(|name| Bus::new(name)).collect(),
+            ducks: Vec::new(),
+            listener: Listener {
+                position: Vector2 { x: 0.0, y: 0.0 },
+                velocity: Vector2 { x: 0.0, y: 0.0 },
+            },
+            speed_of_sound: None,
+        }
+    }
+
+    pub fn get_sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Overwrites `output` with the next `output.len() / 2` frames of
+    /// interleaved stereo.
+    pub fn mix(&mut self, output: &mut [f32]) {
+        let sample_rate = self.sample_rate;
+
+        for bus in &mut self.buses {
+            bus.buffer.clear();
+            bus.buffer.resize(output.len(), 0.0);
+        }
+
+        let buses = &mut self.buses;
+        let listener = &self.listener;
+        let speed_of_sound = self.speed_of_sound;
+
+        self.voices.retain_mut(|voice| {
+            voice.mix(
+                &mut buses[voice.bus].buffer,
+                sample_rate,
+                listener,
+                speed_of_sound,
+            )
+        });
+
+        let seconds = (output.len() / 2) as f64 / sample_rate as f64;
+
+        for duck in &mut self.ducks {
+            let triggered = self
+                .voices
+                .iter()
+                .any(|voice| voice.bus == duck.trigger && !voice.paused);
+
+            duck.update(triggered, seconds);
+        }
+
+        let ducks = &self.ducks;
+        let ducking = |bus: usize| -> f32 {
+            ducks
+                .iter()
+                .filter(|duck| duck.target == bus)
+                .map(|duck| duck.gain)
+                .product()
+        };
+
+        let (master, others) = self.buses.split_first_mut().unwrap();
+
+        for (i, bus) in others.iter_mut().enumerate() {
+            bus.finish(sample_rate, ducking(i + 1));
+
+            for (mixed, sample) in master.buffer.iter_mut().zip(&bus.buffer) {
+                *mixed += sample;
+            }
+        }
+
+        master.finish(sample_rate, ducking(0));
+
+        for (sample, mixed) in output.iter_mut().zip(&master.buffer) {
+            *sample = mixed.clamp(-1.0, 1.0);
+        }
+    }
+
+    pub(super) fn add_voice(&mut self, source: Source, bus: usize) -> &mut Voice {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.voices.push(Voice::new(id, source, bus));
+        return self.voices.last_mut().unwrap();
+    }
+
+    pub(super) fn bus_index(&self, name: &str) -> Option<usize> {
+        self.buses.iter().position(|bus| bus.name == name)
+    }
+
+    pub(super) fn voice_mut(&mut self, id: u64) -> Option<&mut Voice> {
+        self.voices.iter_mut().find(|voice| voice.id == id)
+    }
+
+    pub(super) fn remove_voice(&mut self, id: u64) {
+        self.voices.retain(|voice| voice.id != id);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::Error;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BusVolume {
+    pub name: String,
+    pub volume: f32,
+    pub muted: bool,
+}
+
+/// The volume of every bus, for options menus. It's saved as a small text
+/// file, with a line like `music = 0.8` or `sfx = 0.5 muted` for each bus.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VolumeSettings {
+    pub buses: Vec<BusVolume>,
+}
+
+impl VolumeSettings {
+    pub fn load(path: &str) -> Result<VolumeSettings, Error> {
+        let text = std::fs::read_to_string(path).map_err(|error| Error::Io {
+            path: path.to_string(),
+            error,
+        })?;
+
+        return VolumeSettings::parse(&text).map_err(|(line, message)| Error::Parse {
+            path: path.to_string(),
+            line,
+            message,
+        });
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), Error> {
+        return std::fs::write(path, self.to_string()).map_err(|error| Error::Io {
+            path: path.to_string(),
+            error,
+        });
+    }
+
+    // Returns the line number and what's wrong with it on failure.
+    fn parse(text: &str) -> Result<VolumeSettings, (usize, String)> {
+        let mut settings = VolumeSettings::default();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (name, value) = match line.rsplit_once('=') {
+                Some(split) => split,
+                None => return Err((i + 1, "expected `name = volume`".to_string())),
+            };
+
+            let mut words = value.split_whitespace();
+
+            let volume = match words.next().map(str::parse::<f32>) {
+                Some(Ok(volume)) => volume,
+                _ => return Err((i + 1, "expected a volume after `=`".to_string())),
+            };
+
+            let muted = match words.next() {
+                None => false,
+                Some("muted") => true,
+                Some(word) => return Err((i + 1, format!("expected `muted`, found `{}`", word))),
+            };
+
+            settings.buses.push(BusVolume {
+                name: name.trim().to_string(),
+                volume,
+                muted,
+            });
+        }
+
+        return Ok(settings);
+    }
+}
+
+impl std::fmt::Display for VolumeSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for bus in &self.buses {
+            write!(f, "{} = {}", bus.name, bus.volume)?;
+
+            if bus.muted {
+                write!(f, " muted")?;
+            }
+
+            writeln!(f)?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl std::str::FromStr for VolumeSettings {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<VolumeSettings, Error> {
+        return VolumeSettings::parse(text).map_err(|(line, message)| Error::Parse {
+            path: "<string>".to_string(),
+            line,
+            message,
+        });
+    }
+}
@@ -0,0 +1,112 @@
+use crate::math::Vector2;
+
+/// How sounds get quieter with distance. These are the same as OpenAL's
+/// clamped distance models.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rolloff {
+    /// Goes down in a straight line, and is silent at `max_distance`.
+    Linear,
+    /// Halves every time the distance doubles, with a `factor` of 1. This is
+    /// the closest to real life.
+    Inverse,
+    Exponential,
+}
+
+/// How a positional sound is heard at different distances. Closer than
+/// `reference_distance`, sounds are at full volume. Past `max_distance` they
+/// stop getting quieter, and with `Rolloff::Linear` that means silent.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Attenuation {
+    pub rolloff: Rolloff,
+    /// How fast the volume drops. 0 turns the rolloff off.
+    pub factor: f32,
+    pub reference_distance: f32,
+    pub max_distance: f32,
+}
+
+impl Default for Attenuation {
+    fn default() -> Attenuation {
+        Attenuation {
+            rolloff: Rolloff::Inverse,
+            factor: 1.0,
+            reference_distance: 100.0,
+            max_distance: 2000.0,
+        }
+    }
+}
+
+impl Attenuation {
+    pub fn get_gain(&self, distance: f32) -> f32 {
+        let reference = self.reference_distance.max(f32::EPSILON);
+        let max = self.max_distance.max(reference);
+        let distance = distance.clamp(reference, max);
+
+        let gain = match self.rolloff {
+            Rolloff::Linear if max > reference => {
+                1.0 - self.factor * (distance - reference) / (max - reference)
+            }
+            Rolloff::Linear => 1.0,
+            Rolloff::Inverse => reference / (reference + self.factor * (distance - reference)),
+            Rolloff::Exponential => (distance / reference).powf(-self.factor),
+        };
+
+        return gain.clamp(0.0, 1.0);
+    }
+}
+
+pub(super) struct Listener {
+    pub(super) position: Vector2<f32>,
+    pub(super) velocity: Vector2<f32>,
+}
+
+pub(super) struct Spatial {
+    pub(super) position: Vector2<f32>,
+    pub(super) velocity: Vector2<f32>,
+    pub(super) attenuation: Attenuation,
+}
+
+impl Spatial {
+    /// Returns the gain, pan and pitch multiplier for where the sound is
+    /// compared to `listener`. Doppler is left out if there's no speed of
+    /// sound.
+    pub(super) fn compute(
+        &self,
+        listener: &Listener,
+        speed_of_sound: Option<f32>,
+    ) -> (f32, f32, f32) {
+        let dx = self.position.x - listener.position.x;
+        let dy = self.position.y - listener.position.y;
+        let distance = (dx * dx + dy * dy).sqrt();
+
+        let gain = self.attenuation.get_gain(distance);
+
+        // Right in the middle within the reference distance, and turning
+        // smoothly to the side further out, so passing by the listener
+        // doesn't flip the sound from one ear to the other.
+        let pan = dx
+            / distance
+                .max(self.attenuation.reference_distance)
+                .max(f32::EPSILON);
+
+        let pitch = match speed_of_sound {
+            Some(speed) if speed > 0.0 && distance > 0.0 => {
+                // The speeds towards each other, along the line between them.
+                let (to_listener_x, to_listener_y) = (-dx / distance, -dy / distance);
+                let source_speed =
+                    self.velocity.x * to_listener_x + self.velocity.y * to_listener_y;
+                let listener_speed =
+                    -(listener.velocity.x * to_listener_x + listener.velocity.y * to_listener_y);
+
+                // Nothing's allowed to go as fast as sound, or the pitch
+                // would go to infinity.
+                let source_speed = source_speed.min(speed * 0.9);
+                let listener_speed = listener_speed.max(-speed * 0.9);
+
+                (speed + listener_speed) / (speed - source_speed)
+            }
+            _ => 1.0,
+        };
+
+        return (gain, pan.clamp(-1.0, 1.0), pitch);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::graphics::preprocessor::PreprocessError;
+
+/// Everything that can go wrong in `bgf`.
+#[derive(Debug)]
+pub enum Error {
+    /// GLFW couldn't start, or the window couldn't be created.
+    Window(String),
+    /// The OpenGL context doesn't support what `bgf` needs, or something
+    /// made with it is unusable.
+    GlContext(String),
+    Io {
+        path: String,
+        error: std::io::Error,
+    },
+    /// The file was read, but stb_image doesn't understand it.
+    ImageDecode {
+        path: String,
+    },
+    /// `log` is the driver's info log, with the line numbers mapped back to
+    /// the files they came from. Built-in shaders are named like
+    /// `<built-in 2d_renderer_basic.fs>`.
+    ShaderCompile {
+        source_name: String,
+        log: String,
+    },
+    ShaderLink {
+        vertex_source_name: String,
+        fragment_source_name: String,
+        log: String,
+    },
+    ShaderPreprocess(PreprocessError),
+    /// The audio file is unsupported or broken. `message` comes from the
+    /// decoder.
+    AudioDecode {
+        path: String,
+        message: String,
+    },
+    /// The audio backend couldn't open or write to its output.
+    AudioBackend(String),
+    /// A text file, like saved volume settings, is written wrong. `line`
+    /// starts at 1.
+    Parse {
+        path: String,
+        line: usize,
+        message: String,
+    },
+    /// Something with a fixed size, like the renderer's texture slots, is
+    /// full.
+    Capacity {
+        what: &'static str,
+        capacity: usize,
+    },
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Window(message) => write!(f, "window error: {}", message),
+            Error::GlContext(message) => write!(f, "OpenGL error: {}", message),
+            Error::Io { path, error } => write!(f, "failed to read {}: {}", path, error),
+            Error::ImageDecode { path } => write!(f, "failed to decode the image {}", path),
+            Error::ShaderCompile { source_name, log } => {
+                write!(f, "failed to compile {}:\n{}", source_name, log)
+            }
+            Error::ShaderLink {
+                vertex_source_name,
+                fragment_source_name,
+                log,
+            } => write!(
+                f,
+                "failed to link {} with {}:\n{}",
+                vertex_source_name, fragment_source_name, log
+            ),
+            Error::ShaderPreprocess(error) => write!(f, "failed to preprocess {}", error),
+            Error::AudioDecode { path, message } => {
+                write!(f, "failed to decode the audio file {}: {}", path, message)
+            }
+            Error::AudioBackend(message) => write!(f, "audio error: {}", message),
+            Error::Parse {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path, line, message),
+            Error::Capacity { what, capacity } => {
+                write!(f, "there's no room for more than {} {}", capacity, what)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io { error, .. } => Some(error),
+            Error::ShaderPreprocess(error) => Some(error),
+            _ => None,
+        }
+    }
+}
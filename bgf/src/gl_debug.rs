@@ -0,0 +1,141 @@
+//! Routes OpenGL's debug messages into the `log` crate, under the `bgf::gl`
+//! target. High severity messages and errors are logged as errors, medium
+//! ones as warnings, low ones as info and notifications as debug messages.
+
+use glad_gl::gl;
+use std::cell::RefCell;
+use std::sync::Mutex;
+
+const LOG_TARGET: &str = "bgf::gl";
+
+struct Settings {
+    suppressed_ids: Vec<u32>,
+    panic_on_high_severity: bool,
+}
+
+static SETTINGS: Mutex<Settings> = Mutex::new(Settings {
+    suppressed_ids: Vec::new(),
+    panic_on_high_severity: false,
+});
+
+thread_local! {
+    // Panicking inside the callback would unwind into the driver, so a high
+    // severity message is kept here until bgf is back in its own code.
+    static PENDING_PANIC: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Stops messages with this id from being logged. Ids are specific to each
+/// driver, and they're shown at the start of every logged message.
+pub fn suppress_message(id: u32) {
+    let mut settings = SETTINGS.lock().unwrap();
+
+    if !settings.suppressed_ids.contains(&id) {
+        settings.suppressed_ids.push(id);
+    }
+}
+
+pub fn unsuppress_message(id: u32) {
+    SETTINGS
+        .lock()
+        .unwrap()
+        .suppressed_ids
+        .retain(|suppressed| *suppressed != id);
+}
+
+/// Makes high severity messages panic, the next time the window is updated
+/// or a batch is drawn. This only does anything in debug builds.
+pub fn set_panic_on_high_severity(panic: bool) {
+    SETTINGS.lock().unwrap().panic_on_high_severity = panic;
+}
+
+/// Panics if a high severity message came in since the last check, and
+/// `set_panic_on_high_severity` is on.
+pub(crate) fn check_pending_panic() {
+    if let Some(message) = PENDING_PANIC.with(|pending| pending.borrow_mut().take()) {
+        panic!("OpenGL error: {}", message);
+    }
+}
+
+fn source_name(source: u32) -> &'static str {
+    match source {
+        gl::DEBUG_SOURCE_API => "api",
+        gl::DEBUG_SOURCE_WINDOW_SYSTEM => "window system",
+        gl::DEBUG_SOURCE_SHADER_COMPILER => "shader compiler",
+        gl::DEBUG_SOURCE_THIRD_PARTY => "third party",
+        gl::DEBUG_SOURCE_APPLICATION => "application",
+        _ => "other",
+    }
+}
+
+fn type_name(message_type: u32) -> &'static str {
+    match message_type {
+        gl::DEBUG_TYPE_ERROR => "error",
+        gl::DEBUG_TYPE_DEPRECATED_BEHAVIOR => "deprecated behavior",
+        gl::DEBUG_TYPE_UNDEFINED_BEHAVIOR => "undefined behavior",
+        gl::DEBUG_TYPE_PORTABILITY => "portability",
+        gl::DEBUG_TYPE_PERFORMANCE => "performance",
+        gl::DEBUG_TYPE_MARKER => "marker",
+        gl::DEBUG_TYPE_PUSH_GROUP => "push group",
+        gl::DEBUG_TYPE_POP_GROUP => "pop group",
+        _ => "other",
+    }
+}
+
+pub(crate) extern "system" fn callback(
+    source: u32,
+    message_type: u32,
+    id: u32,
+    severity: u32,
+    length: i32,
+    message: *const i8,
+    _: *mut std::ffi::c_void,
+) {
+    let settings = match SETTINGS.lock() {
+        Ok(settings) => settings,
+        Err(_) => return,
+    };
+
+    if settings.suppressed_ids.contains(&id) {
+        return;
+    }
+
+    let message = if message.is_null() {
+        String::new()
+    } else if length >= 0 {
+        let bytes = unsafe { std::slice::from_raw_parts(message as *const u8, length as usize) };
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        unsafe { std::ffi::CStr::from_ptr(message) }
+            .to_string_lossy()
+            .into_owned()
+    };
+
+    let level = match severity {
+        _ if message_type == gl::DEBUG_TYPE_ERROR => log::Level::Error,
+        gl::DEBUG_SEVERITY_HIGH => log::Level::Error,
+        gl::DEBUG_SEVERITY_MEDIUM => log::Level::Warn,
+        gl::DEBUG_SEVERITY_LOW => log::Level::Info,
+        _ => log::Level::Debug,
+    };
+
+    log::log!(
+        target: LOG_TARGET,
+        level,
+        "[{} {}, {}] {}",
+        id,
+        source_name(source),
+        type_name(message_type),
+        message
+    );
+
+    if cfg!(debug_assertions)
+        && settings.panic_on_high_severity
+        && severity == gl::DEBUG_SEVERITY_HIGH
+    {
+        PENDING_PANIC.with(|pending| {
+            pending
+                .borrow_mut()
+                .get_or_insert_with(|| format!("[{}] {}", id, message));
+        });
+    }
+}
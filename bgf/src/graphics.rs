@@ -1,37 +1,75 @@
-use crate::math;
+mod builtin_shaders;
+mod color;
+mod debug_draw;
+mod hot_reload;
+mod material;
+mod nine_slice;
+mod post_process;
+pub mod preprocessor;
+mod render_target;
+pub mod shapes;
+pub mod sorting;
+
+pub use builtin_shaders::{register_shader_include, set_shader_override_directory};
+pub use color::{colors, Color};
+pub use debug_draw::{DebugDraw, DebugShape};
+pub use hot_reload::ShaderWatcher;
+pub use material::{Material, MaterialId, Shader};
+pub use nine_slice::{NineSlice, SliceMode, SlicePiece};
+pub use post_process::{PostProcessEffect, PostProcessStack};
+pub use render_target::RenderTarget;
+pub use shapes::{LineCap, LineJoin};
+pub use sorting::SortMode;
+
+use crate::{math, Error};
 use glad_gl::gl;
 use stb::image::*;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::rc::Rc;
 
 const MAX_TEXTURES: u32 = 32;
 
-pub struct Color {
-    red: f32,
-    green: f32,
-    blue: f32,
-    alpha: f32,
+// The indices of the two triangles that make up a quad.
+const QUAD_INDICES: [u32; 6] = [0, 1, 2, 0, 3, 2];
+
+// The built-in font is about this many pixels tall at a scale of 1.
+const FONT_HEIGHT: f32 = 12.0;
+
+/// A value for a shader uniform that's kept around and set every time the
+/// shader is used.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum UniformValue {
+    Int(i32),
+    Float(f32),
+    Vec2(f32, f32),
+    Vec3(f32, f32, f32),
+    Vec4(f32, f32, f32, f32),
+    Matrix4(math::Matrix4<f32>),
+    /// A texture id from `Renderer2D::load_texture`, for a `sampler2D`.
+    /// Post-processing effects bind these to units of their own, since units
+    /// 0 and 1 are their inputs.
+    Texture(f32),
 }
 
-pub mod colors {
-    use super::Color;
+/// Returns the width and height that `Renderer2D::draw_text` would take up.
+pub fn measure_text(text: &str, scale: f32) -> (f32, f32) {
+    use stb::easy_font::*;
 
-    pub const WHITE: Color = Color { red: 1.0, green: 1.0, blue: 1.0, alpha: 1.0 };
-    pub const BLACK: Color = Color { red: 0.0, green: 0.0, blue: 0.0, alpha: 1.0 };
-    pub const RED: Color = Color { red: 1.0, green: 0.0, blue: 0.0, alpha: 1.0 };
-    pub const GREEN: Color = Color { red: 0.0, green: 1.0, blue: 0.0, alpha: 1.0 };
-    pub const BLUE: Color = Color { red: 0.0, green: 0.0, blue: 1.0, alpha: 1.0 };
-    pub const CYAN: Color = Color { red: 0.0, green: 1.0, blue: 1.0, alpha: 1.0 };
-    pub const YELLOW: Color = Color { red: 1.0, green: 1.0, blue: 0.0, alpha: 1.0 };
-}
+    let text = match std::ffi::CString::new(text.replace('\0', "")) {
+        Ok(text) => text,
+        Err(_) => return (0.0, 0.0),
+    };
 
-impl Color {
-    fn to_vec(&self) -> math::Vector4<f32> {
-        math::Vector4 {
-            x: self.red,
-            y: self.green,
-            z: self.blue,
-            w: self.alpha,
-        }
+    if text.as_bytes().is_empty() {
+        return (0.0, FONT_HEIGHT * scale);
     }
+
+    return (
+        stb_easy_font_width(&text) as f32 * scale,
+        stb_easy_font_height(&text) as f32 * scale,
+    );
 }
 
 pub struct Renderer2D {
@@ -39,39 +77,65 @@ pub struct Renderer2D {
     vertex_buffer: Buffer,
     index_buffer: Buffer,
 
-    textures: [Option<InternalTexture>; 32],
+    textures: [Option<Rc<InternalTexture>>; 32],
 
     vertices: Vec<Vertex2D>,
-    quads_to_draw: u32,
-    max_quads: u32,
-    next_texture_slot: u32,
-
-    shader_program: ShaderProgram,
+    indices: Vec<u32>,
+    max_vertices: usize,
+    max_indices: usize,
+
+    draw_items: Vec<sorting::DrawItem>,
+    // Draws from `DebugDraw`, which go after everything else in the order
+    // they were made, whatever the sort mode.
+    overlay_items: Vec<sorting::DrawItem>,
+    overlay: bool,
+    // How many draws didn't fit in the batch, to warn about in `end`.
+    dropped_draws: usize,
+    layer: i32,
+    sort_mode: SortMode,
+
+    materials: Vec<Material>,
+    material: Option<MaterialId>,
+
+    window_width: f32,
+    window_height: f32,
+    // The framebuffer and size of the render target that's being drawn to, if
+    // it's not the window.
+    target: Option<(u32, u32, u32)>,
+    clear_color: Option<Color>,
+
+    shader_program: Rc<ShaderProgram>,
 }
 
 impl Renderer2D {
-    pub fn new(max_quads: usize, window_width: f32, window_height: f32) -> Renderer2D {
-        let shader_program = ShaderProgram::new(
-            "assets/shaders/2d_renderer_basic.vs",
-            "assets/shaders/2d_renderer_basic.fs",
-        );
+    /// `max_quads` sets the size of the batch. Other shapes take up as much of
+    /// it as the amount of vertices and indices they need. The shaders are
+    /// the ones compiled into the crate, unless they've been overridden with
+    /// `set_shader_override_directory`.
+    pub fn new(
+        max_quads: usize,
+        window_width: f32,
+        window_height: f32,
+    ) -> Result<Renderer2D, Error> {
+        let shader_program =
+            ShaderProgram::builtin("2d_renderer_basic.vs", "2d_renderer_basic.fs")?;
         shader_program.use_program();
 
-        for i in 0..32 {
-            shader_program.set_uniform_1i(format!("texture_samplers[{}]", i).as_str(), i);
-        }
-
         let projection = math::orthographic(0.0, window_width, 0.0, window_height, 1.0, 0.0);
 
-        shader_program.set_unifrom_matrix_4f("projection", &projection);
+        shader_program.set_uniform_matrix_4f("projection", &projection);
 
         let vertex_array = VertexArray::new();
         vertex_array.bind();
 
-        let vertices = Vec::with_capacity(max_quads * 4);
+        let max_vertices = max_quads * 4;
+        let max_indices = max_quads * 6;
+
+        let vertices = Vec::with_capacity(max_vertices);
+        let indices = Vec::with_capacity(max_indices);
 
         let vertex_buffer = Buffer::new_empty(
-            (max_quads * std::mem::size_of::<Vertex2D>() * 4)
+            (max_vertices * std::mem::size_of::<Vertex2D>())
                 .try_into()
                 .unwrap(),
             BufferType::Vertex,
@@ -79,18 +143,13 @@ impl Renderer2D {
         );
         vertex_buffer.bind();
 
-        let mut indices: Vec<u32> = Vec::with_capacity(max_quads * 6);
-
-        for i in 0..max_quads {
-            indices.push((i * 4 + 0).try_into().unwrap());
-            indices.push((i * 4 + 1).try_into().unwrap());
-            indices.push((i * 4 + 2).try_into().unwrap());
-            indices.push((i * 4 + 0).try_into().unwrap());
-            indices.push((i * 4 + 3).try_into().unwrap());
-            indices.push((i * 4 + 2).try_into().unwrap());
-        }
-
-        let index_buffer = Buffer::new(indices, BufferType::Index, BufferUsage::Static);
+        let index_buffer = Buffer::new_empty(
+            (max_indices * std::mem::size_of::<u32>())
+                .try_into()
+                .unwrap(),
+            BufferType::Index,
+            BufferUsage::Dynamic,
+        );
         index_buffer.bind();
 
         vertex_array.create_vertex_attribute(
@@ -127,98 +186,288 @@ impl Renderer2D {
 
         // Too lazy to implement the copy trait for Texture
         let textures = [
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
-            Option::<InternalTexture>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
+            Option::<Rc<InternalTexture>>::None,
         ];
 
-        return Renderer2D {
+        return Ok(Renderer2D {
             vertex_array,
             vertex_buffer,
             index_buffer,
             textures,
             vertices,
-            quads_to_draw: 0,
-            max_quads: max_quads.try_into().unwrap(),
+            indices,
+            max_vertices,
+            max_indices,
             shader_program,
-            next_texture_slot: 0,
+            draw_items: Vec::new(),
+            overlay_items: Vec::new(),
+            overlay: false,
+            dropped_draws: 0,
+            layer: 0,
+            sort_mode: SortMode::Layer,
+            materials: Vec::new(),
+            material: None,
+            window_width,
+            window_height,
+            target: None,
+            clear_color: None,
+        });
+    }
+
+    /// Returns the id to draw the texture with. There are 32 texture slots,
+    /// shared with render targets, and loading more than that is an error.
+    pub fn load_texture(&mut self, image_file_path: &str) -> Result<f32, Error> {
+        let texture = Rc::new(InternalTexture::new(image_file_path)?);
+        return self.add_texture(texture);
+    }
+
+    // Puts a texture in the first free slot and returns its id. Slots are
+    // freed again by `destroy_render_target`.
+    fn add_texture(&mut self, texture: Rc<InternalTexture>) -> Result<f32, Error> {
+        let slot = match self.textures.iter().position(Option::is_none) {
+            Some(slot) => slot,
+            None => {
+                return Err(Error::Capacity {
+                    what: "textures",
+                    capacity: MAX_TEXTURES as usize,
+                })
+            }
         };
+
+        self.textures[slot] = Some(texture);
+
+        return Ok(slot as f32);
     }
 
-    pub fn load_texture(&mut self, image_file_path: &str) -> f32 {
-        if self.next_texture_slot < MAX_TEXTURES {
-            self.textures[self.next_texture_slot as usize] =
-                Some(InternalTexture::new(image_file_path));
-            self.next_texture_slot += 1;
+    /// How big the texture is in pixels, or `None` if there's no texture with
+    /// that id.
+    pub fn get_texture_size(&self, texture_id: f32) -> Option<math::Vector2<f32>> {
+        if texture_id < 0.0 {
+            return None;
+        }
 
-            return (self.next_texture_slot - 1) as f32;
-        } else {
-            return 0.0;
+        let texture = self.textures.get(texture_id as usize)?.as_ref()?;
+        return Some(math::Vector2::new(
+            texture.width as f32,
+            texture.height as f32,
+        ));
+    }
+
+    /// Creates an offscreen target to draw into. Its texture takes up a
+    /// texture slot, just like `load_texture`, until it's given back with
+    /// `destroy_render_target`.
+    pub fn create_render_target(
+        &mut self,
+        width: u32,
+        height: u32,
+        filter: TextureFilter,
+        with_depth_stencil: bool,
+    ) -> Result<RenderTarget, Error> {
+        let texture = Rc::new(InternalTexture::new_empty(width, height, filter));
+
+        // The framebuffer is made first, so that one that can't be used
+        // doesn't take up a slot.
+        let mut target =
+            RenderTarget::new(texture.clone(), -1.0, width, height, with_depth_stencil)?;
+        target.texture_id = self.add_texture(texture)?;
+
+        return Ok(target);
+    }
+
+    /// Deletes a render target and frees up its texture slot for the next
+    /// texture or target. Just dropping a target keeps its slot taken. It
+    /// shouldn't be destroyed between `begin` and `end` if its texture has
+    /// been drawn with.
+    pub fn destroy_render_target(&mut self, target: RenderTarget) {
+        let slot = target.texture_id() as usize;
+
+        // Only if it's really this target's texture, in case it came from
+        // another renderer.
+        if let Some(Some(texture)) = self.textures.get(slot) {
+            if std::ptr::eq(texture.as_ref(), target.color_texture()) {
+                self.textures[slot] = None;
+            }
         }
     }
 
     pub fn begin(&mut self) {
-        self.quads_to_draw = 0;
         self.vertices.clear();
+        self.indices.clear();
+        self.draw_items.clear();
+        self.overlay_items.clear();
+        self.overlay = false;
+        self.dropped_draws = 0;
+        self.layer = 0;
+        self.material = None;
+        self.target = None;
+        self.clear_color = None;
     }
 
-    pub fn draw_quad_v(
-        &mut self,
-        position: &math::Vector2<f32>,
-        size: &math::Vector2<f32>,
-        color: &math::Vector4<f32>,
-        texture_id: f32,
-    ) {
-        self.quads_to_draw += 1;
+    /// Hands a material over to the renderer, so that it can be drawn with.
+    pub fn add_material(&mut self, material: Material) -> MaterialId {
+        self.materials.push(material);
+        return MaterialId(self.materials.len() - 1);
+    }
+
+    pub fn get_material(&self, material: MaterialId) -> &Material {
+        &self.materials[material.0]
+    }
+
+    /// For changing the material's uniforms between frames.
+    pub fn get_material_mut(&mut self, material: MaterialId) -> &mut Material {
+        &mut self.materials[material.0]
+    }
+
+    /// Everything drawn after this uses the given material, or the default
+    /// shader for `None`, until the next call or the next `begin`. Switching
+    /// materials splits the batch into more draw calls.
+    pub fn set_material(&mut self, material: Option<MaterialId>) {
+        self.material = material;
+    }
+
+    /// Like `begin`, but everything until `end` is drawn into `target`
+    /// instead of the window.
+    pub fn begin_target(&mut self, target: &RenderTarget) {
+        self.begin();
+
+        let (width, height) = target.get_size();
+        self.target = Some((target.framebuffer(), width, height));
+    }
+
+    /// Clears whatever is being drawn to at `end`, before anything is drawn.
+    pub fn clear(&mut self, color: &Color) {
+        self.clear_color = Some(*color);
+    }
+
+    /// Everything drawn after this goes on the given layer, until the next
+    /// call or the next `begin`. Lower layers are drawn first.
+    pub fn set_layer(&mut self, layer: i32) {
+        self.layer = layer;
+    }
+
+    pub fn get_layer(&self) -> i32 {
+        self.layer
+    }
+
+    /// Decides how the draws are ordered at `end`. `SortMode::Layer` by
+    /// default.
+    pub fn set_sort_mode(&mut self, sort_mode: SortMode) {
+        self.sort_mode = sort_mode;
+    }
+
+    fn push_geometry(&mut self, vertices: Vec<Vertex2D>, indices: &[u32]) {
+        if vertices.is_empty() {
+            return;
+        }
 
         // If the client made more draw calls than what was allocated, then do
         // nothing.
-        if self.quads_to_draw > self.max_quads {
-            self.quads_to_draw -= 1;
+        if self.vertices.len() + vertices.len() > self.max_vertices
+            || self.indices.len() + indices.len() > self.max_indices
+        {
+            self.dropped_draws += 1;
             return;
         }
 
+        let mut item = sorting::DrawItem {
+            layer: self.layer,
+            material: self.material,
+            min_x: f32::MAX,
+            min_y: f32::MAX,
+            max_x: f32::MIN,
+            max_y: f32::MIN,
+            first_index: self.indices.len(),
+            index_count: indices.len(),
+        };
+
+        for vertex in &vertices {
+            item.min_x = item.min_x.min(vertex.position.x);
+            item.min_y = item.min_y.min(vertex.position.y);
+            item.max_x = item.max_x.max(vertex.position.x);
+            item.max_y = item.max_y.max(vertex.position.y);
+        }
+
+        if self.overlay {
+            self.overlay_items.push(item);
+        } else {
+            self.draw_items.push(item);
+        }
+
+        let first_index: u32 = self.vertices.len().try_into().unwrap();
+
+        self.indices
+            .extend(indices.iter().map(|index| first_index + index));
+        self.vertices.extend(vertices);
+    }
+
+    fn push_shape(&mut self, geometry: shapes::Geometry, color: &Color) {
+        let vertices = geometry
+            .positions
+            .iter()
+            .map(|position| Vertex2D {
+                position: *position,
+                uv: math::Vector2 { x: 0.0, y: 0.0 },
+                color: color.to_vec(),
+                texture: -1.0,
+            })
+            .collect();
+
+        self.push_geometry(vertices, &geometry.indices);
+    }
+
+    pub fn draw_quad_v(
+        &mut self,
+        position: &math::Vector2<f32>,
+        size: &math::Vector2<f32>,
+        color: &Color,
+        texture_id: f32,
+    ) {
         use math::Vector2;
 
+        let color = color.to_vec();
+        let mut vertices = Vec::with_capacity(4);
+
         let vertex = Vertex2D {
             position: Vector2::<f32> {
                 x: position.x + size.x,
                 y: position.y,
             },
             uv: Vector2::<f32> { x: 1.0, y: 0.0 },
-            color: color.clone(),
+            color,
             texture: texture_id,
         };
-        self.vertices.push(vertex);
+        vertices.push(vertex);
 
         let vertex = Vertex2D {
             position: Vector2::<f32> {
@@ -226,10 +475,10 @@ impl Renderer2D {
                 y: position.y + size.y,
             },
             uv: Vector2::<f32> { x: 1.0, y: 1.0 },
-            color: color.clone(),
+            color,
             texture: texture_id,
         };
-        self.vertices.push(vertex);
+        vertices.push(vertex);
 
         let vertex = Vertex2D {
             position: Vector2::<f32> {
@@ -237,10 +486,10 @@ impl Renderer2D {
                 y: position.y + size.y,
             },
             uv: Vector2::<f32> { x: 0.0, y: 1.0 },
-            color: color.clone(),
+            color,
             texture: texture_id,
         };
-        self.vertices.push(vertex);
+        vertices.push(vertex);
 
         let vertex = Vertex2D {
             position: Vector2::<f32> {
@@ -248,10 +497,12 @@ impl Renderer2D {
                 y: position.y,
             },
             uv: Vector2::<f32> { x: 0.0, y: 0.0 },
-            color: color.clone(),
+            color,
             texture: texture_id,
         };
-        self.vertices.push(vertex);
+        vertices.push(vertex);
+
+        self.push_geometry(vertices, &QUAD_INDICES);
     }
 
     pub fn draw_quad(
@@ -263,24 +514,17 @@ impl Renderer2D {
         color: &Color,
         texture_id: f32,
     ) {
-        self.quads_to_draw += 1;
-
-        // If the client made more draw calls than what was allocated, then do
-        // nothing.
-        if self.quads_to_draw > self.max_quads {
-            self.quads_to_draw -= 1;
-            return;
-        }
-
         use math::Vector2;
 
+        let mut vertices = Vec::with_capacity(4);
+
         let vertex = Vertex2D {
             position: Vector2::<f32> { x: x + width, y: y },
             uv: Vector2::<f32> { x: 1.0, y: 0.0 },
             color: color.to_vec(),
             texture: texture_id,
         };
-        self.vertices.push(vertex);
+        vertices.push(vertex);
 
         let vertex = Vertex2D {
             position: Vector2::<f32> {
@@ -291,7 +535,7 @@ impl Renderer2D {
             color: color.to_vec(),
             texture: texture_id,
         };
-        self.vertices.push(vertex);
+        vertices.push(vertex);
 
         let vertex = Vertex2D {
             position: Vector2::<f32> {
@@ -302,7 +546,7 @@ impl Renderer2D {
             color: color.to_vec(),
             texture: texture_id,
         };
-        self.vertices.push(vertex);
+        vertices.push(vertex);
 
         let vertex = Vertex2D {
             position: Vector2::<f32> { x: x, y: y },
@@ -310,16 +554,335 @@ impl Renderer2D {
             color: color.to_vec(),
             texture: texture_id,
         };
-        self.vertices.push(vertex);
+        vertices.push(vertex);
+
+        self.push_geometry(vertices, &QUAD_INDICES);
+    }
+
+    /// Draws a `size` quad with its top left corner at the transform's
+    /// origin, for sprites that are rotated, scaled or attached to something
+    /// else.
+    pub fn draw_quad_transformed(
+        &mut self,
+        transform: &math::Transform2D,
+        size: &math::Vector2<f32>,
+        color: &Color,
+        texture_id: f32,
+    ) {
+        use math::Vector2;
+
+        // In the same order as draw_quad, so QUAD_INDICES works.
+        let corners = [(1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)];
+
+        let vertices = corners
+            .iter()
+            .map(|(u, v)| Vertex2D {
+                position: transform.transform_point(Vector2::new(size.x * u, size.y * v)),
+                uv: Vector2::new(*u, *v),
+                color: color.to_vec(),
+                texture: texture_id,
+            })
+            .collect();
+
+        self.push_geometry(vertices, &QUAD_INDICES);
+    }
+
+    pub fn draw_triangle(
+        &mut self,
+        a: &math::Vector2<f32>,
+        b: &math::Vector2<f32>,
+        c: &math::Vector2<f32>,
+        color: &Color,
+    ) {
+        let geometry = shapes::triangle(*a, *b, *c);
+        self.push_shape(geometry, color);
+    }
+
+    /// The points have to form a convex polygon, in either winding order.
+    pub fn draw_polygon(&mut self, points: &[math::Vector2<f32>], color: &Color) {
+        self.push_shape(shapes::convex_polygon(points), color);
+    }
+
+    pub fn draw_polygon_outline(
+        &mut self,
+        points: &[math::Vector2<f32>],
+        thickness: f32,
+        join: LineJoin,
+        color: &Color,
+    ) {
+        let geometry = shapes::polyline(points, thickness, LineCap::Butt, join, true);
+        self.push_shape(geometry, color);
+    }
+
+    pub fn draw_line(
+        &mut self,
+        start: &math::Vector2<f32>,
+        end: &math::Vector2<f32>,
+        thickness: f32,
+        cap: LineCap,
+        color: &Color,
+    ) {
+        let points = [*start, *end];
+        let geometry = shapes::polyline(&points, thickness, cap, LineJoin::Bevel, false);
+        self.push_shape(geometry, color);
+    }
+
+    pub fn draw_polyline(
+        &mut self,
+        points: &[math::Vector2<f32>],
+        thickness: f32,
+        cap: LineCap,
+        join: LineJoin,
+        color: &Color,
+    ) {
+        let geometry = shapes::polyline(points, thickness, cap, join, false);
+        self.push_shape(geometry, color);
+    }
+
+    pub fn draw_circle(
+        &mut self,
+        center: &math::Vector2<f32>,
+        radius: f32,
+        segments: u32,
+        color: &Color,
+    ) {
+        let geometry = shapes::ellipse(*center, math::Vector2::from_scalar(radius), segments);
+        self.push_shape(geometry, color);
+    }
+
+    /// The outline is drawn on the inside of the circle's edge.
+    pub fn draw_circle_outline(
+        &mut self,
+        center: &math::Vector2<f32>,
+        radius: f32,
+        thickness: f32,
+        segments: u32,
+        color: &Color,
+    ) {
+        let geometry = shapes::ellipse_outline(
+            *center,
+            math::Vector2::from_scalar(radius),
+            thickness,
+            segments,
+        );
+        self.push_shape(geometry, color);
+    }
+
+    pub fn draw_ellipse(
+        &mut self,
+        center: &math::Vector2<f32>,
+        radii: &math::Vector2<f32>,
+        segments: u32,
+        color: &Color,
+    ) {
+        let geometry = shapes::ellipse(*center, *radii, segments);
+        self.push_shape(geometry, color);
+    }
+
+    /// The outline is drawn on the inside of the ellipse's edge.
+    pub fn draw_ellipse_outline(
+        &mut self,
+        center: &math::Vector2<f32>,
+        radii: &math::Vector2<f32>,
+        thickness: f32,
+        segments: u32,
+        color: &Color,
+    ) {
+        let geometry = shapes::ellipse_outline(*center, *radii, thickness, segments);
+        self.push_shape(geometry, color);
+    }
+
+    /// Angles are in radians and go clockwise on screen, starting from the
+    /// positive x axis.
+    pub fn draw_arc(
+        &mut self,
+        center: &math::Vector2<f32>,
+        radius: f32,
+        start_angle: f32,
+        end_angle: f32,
+        thickness: f32,
+        segments: u32,
+        color: &Color,
+    ) {
+        let geometry = shapes::arc(*center, radius, start_angle, end_angle, thickness, segments);
+        self.push_shape(geometry, color);
+    }
+
+    /// `segments` is the amount of segments per corner.
+    pub fn draw_rounded_rect(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        radius: f32,
+        segments: u32,
+        color: &Color,
+    ) {
+        let geometry = shapes::rounded_rect(x, y, width, height, radius, segments);
+        self.push_shape(geometry, color);
+    }
+
+    /// The outline is drawn on the inside of the rectangle's edge.
+    pub fn draw_rounded_rect_outline(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        radius: f32,
+        thickness: f32,
+        segments: u32,
+        color: &Color,
+    ) {
+        let geometry =
+            shapes::rounded_rect_outline(x, y, width, height, radius, thickness, segments);
+        self.push_shape(geometry, color);
+    }
+
+    /// Draws a nine-slice over `rect`, tinted by `color`. Nothing is drawn if
+    /// its texture hasn't been loaded.
+    pub fn draw_nine_slice(&mut self, rect: &math::Rect<f32>, slice: &NineSlice, color: &Color) {
+        if let Some(size) = self.get_texture_size(slice.texture) {
+            for piece in slice.get_pieces(rect, size) {
+                self.draw_slice_piece(&piece, slice.texture, color);
+            }
+        }
+    }
+
+    /// Like `draw_nine_slice`, but leaves out anything outside of `clip`, like
+    /// for panels that scroll.
+    pub fn draw_nine_slice_clipped(
+        &mut self,
+        rect: &math::Rect<f32>,
+        slice: &NineSlice,
+        color: &Color,
+        clip: &math::Rect<f32>,
+    ) {
+        if let Some(size) = self.get_texture_size(slice.texture) {
+            for piece in slice.get_pieces(rect, size) {
+                if let Some(piece) = piece.clip(clip) {
+                    self.draw_slice_piece(&piece, slice.texture, color);
+                }
+            }
+        }
+    }
+
+    fn draw_slice_piece(&mut self, piece: &SlicePiece, texture_id: f32, color: &Color) {
+        let (rect, uv) = (&piece.rect, &piece.uv);
+        let color = color.to_vec();
+
+        let corners = [
+            (rect.x + rect.width, rect.y, uv.x + uv.width, uv.y),
+            (
+                rect.x + rect.width,
+                rect.y + rect.height,
+                uv.x + uv.width,
+                uv.y + uv.height,
+            ),
+            (rect.x, rect.y + rect.height, uv.x, uv.y + uv.height),
+            (rect.x, rect.y, uv.x, uv.y),
+        ];
+
+        let vertices = corners
+            .iter()
+            .map(|(x, y, u, v)| Vertex2D {
+                position: math::Vector2::new(*x, *y),
+                uv: math::Vector2::new(*u, *v),
+                color,
+                texture: texture_id,
+            })
+            .collect();
+
+        self.push_geometry(vertices, &QUAD_INDICES);
+    }
+
+    /// Draws text with the built-in font, with `(x, y)` being the top left
+    /// corner. At a scale of 1, a line of text is about 12 pixels tall.
+    pub fn draw_text(&mut self, x: f32, y: f32, scale: f32, text: &str, color: &Color) {
+        use stb::easy_font::*;
+
+        let text = match std::ffi::CString::new(text.replace('\0', "")) {
+            Ok(text) => text,
+            Err(_) => return,
+        };
+
+        // stb_easy_font uses about 270 bytes per character on average, so this
+        // should be plenty.
+        let mut buffer = vec![Vertex::default(); text.as_bytes().len() * 64 + 64];
+        let quad_count = stb_easy_font_print(0.0, 0.0, &text, None, &mut buffer);
+
+        let vertices = buffer[..quad_count * 4]
+            .iter()
+            .map(|vertex| Vertex2D {
+                position: math::Vector2 {
+                    x: x + vertex.xyz[0] * scale,
+                    y: y + vertex.xyz[1] * scale,
+                },
+                uv: math::Vector2 { x: 0.0, y: 0.0 },
+                color: color.to_vec(),
+                texture: -1.0,
+            })
+            .collect();
+
+        let indices: Vec<u32> = (0..quad_count as u32)
+            .flat_map(|quad| QUAD_INDICES.iter().map(move |index| quad * 4 + index))
+            .collect();
+
+        self.push_geometry(vertices, &indices);
     }
 
     pub fn end(&self) {
+        let mut draw_items = self.draw_items.clone();
+        sorting::sort_draw_items(&mut draw_items, self.sort_mode);
+        draw_items.extend_from_slice(&self.overlay_items);
+
+        // The vertices stay where they are, only the indices get reordered.
+        // Every run of draws with the same material becomes one draw call.
+        let mut indices = Vec::with_capacity(self.indices.len());
+        let mut draw_calls: Vec<(Option<MaterialId>, usize, usize)> = Vec::new();
+
+        for item in &draw_items {
+            match draw_calls.last_mut() {
+                Some((material, _, count)) if *material == item.material => {
+                    *count += item.index_count
+                }
+                _ => draw_calls.push((item.material, indices.len(), item.index_count)),
+            }
+
+            indices.extend_from_slice(
+                &self.indices[item.first_index..item.first_index + item.index_count],
+            );
+        }
+
+        let (framebuffer, width, height) = match self.target {
+            Some((framebuffer, width, height)) => (framebuffer, width as f32, height as f32),
+            None => (0, self.window_width, self.window_height),
+        };
+
+        // Render targets get flipped vertically, so that their textures end up
+        // the right way up when they're drawn like any other texture.
+        let projection = if self.target.is_some() {
+            math::orthographic(0.0, width, height, 0.0, 1.0, 0.0)
+        } else {
+            math::orthographic(0.0, width, 0.0, height, 1.0, 0.0)
+        };
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+            gl::Viewport(0, 0, width as i32, height as i32);
+
+            if let Some(color) = &self.clear_color {
+                gl::ClearColor(color.red, color.green, color.blue, color.alpha);
+                gl::Clear(gl::COLOR_BUFFER_BIT | gl::DEPTH_BUFFER_BIT | gl::STENCIL_BUFFER_BIT);
+            }
+        }
+
         self.vertex_array.bind();
         self.vertex_buffer.set_sub_data(0, &self.vertices);
+        self.index_buffer.set_sub_data(0, &indices);
         self.index_buffer.bind();
 
-        let quad_count: i32 = self.quads_to_draw.try_into().unwrap();
-
         for i in 0..32 {
             if let Some(texture) = &self.textures[i] {
                 InternalTexture::set_active_texture(i.try_into().unwrap());
@@ -327,16 +890,42 @@ impl Renderer2D {
             }
         }
 
-        self.shader_program.use_program();
+        for (material, first_index, index_count) in draw_calls {
+            match material {
+                Some(material) => self.materials[material.0].apply(&projection),
+                None => {
+                    self.shader_program
+                        .set_uniform_matrix_4f("projection", &projection);
+                    self.shader_program.use_program();
+                }
+            }
 
-        unsafe {
-            gl::DrawElements(
-                gl::TRIANGLES,
-                quad_count * 6,
-                gl::UNSIGNED_INT,
-                std::ptr::null(),
+            unsafe {
+                gl::DrawElements(
+                    gl::TRIANGLES,
+                    index_count.try_into().unwrap(),
+                    gl::UNSIGNED_INT,
+                    (first_index * std::mem::size_of::<u32>()) as *const std::ffi::c_void,
+                );
+            }
+        }
+
+        if self.target.is_some() {
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+                gl::Viewport(0, 0, self.window_width as i32, self.window_height as i32);
+            }
+        }
+        if self.dropped_draws > 0 {
+            log::warn!(
+                "{} draws didn't fit in the batch and were skipped, it has room for {} vertices and {} indices",
+                self.dropped_draws,
+                self.max_vertices,
+                self.max_indices
             );
         }
+
+        crate::gl_debug::check_pending_panic();
     }
 }
 
@@ -414,7 +1003,6 @@ enum BufferType {
 
 // Once again, only included the ones that I will use.
 enum BufferUsage {
-    Static,
     Dynamic,
 }
 
@@ -424,35 +1012,6 @@ struct Buffer {
 }
 
 impl Buffer {
-    fn new<T>(data: Vec<T>, buffer_type: BufferType, buffer_usage: BufferUsage) -> Buffer {
-        let mut handle = 0;
-        let buffer_type = match buffer_type {
-            BufferType::Vertex => gl::ARRAY_BUFFER,
-            BufferType::Index => gl::ELEMENT_ARRAY_BUFFER,
-        };
-
-        unsafe {
-            gl::GenBuffers(1, &mut handle);
-            gl::BindBuffer(buffer_type, handle);
-            gl::BufferData(
-                buffer_type,
-                (data.len() * std::mem::size_of::<T>()).try_into().unwrap(),
-                data.as_ptr() as *const std::ffi::c_void,
-                match buffer_usage {
-                    BufferUsage::Static => gl::STATIC_DRAW,
-                    BufferUsage::Dynamic => gl::DYNAMIC_DRAW,
-                },
-            );
-
-            gl::BindBuffer(buffer_type, 0);
-        }
-
-        return Buffer {
-            handle,
-            buffer_type,
-        };
-    }
-
     /// Creates a new empty buffer
     fn new_empty(size: isize, buffer_type: BufferType, buffer_usage: BufferUsage) -> Buffer {
         let mut handle = 0;
@@ -469,7 +1028,6 @@ impl Buffer {
                 size,
                 std::ptr::null(),
                 match buffer_usage {
-                    BufferUsage::Static => gl::STATIC_DRAW,
                     BufferUsage::Dynamic => gl::DYNAMIC_DRAW,
                 },
             );
@@ -521,17 +1079,25 @@ impl Drop for Buffer {
     }
 }
 
-fn create_shader(source_path: &str, shader_type: u32) -> u32 {
-    let source = std::fs::read_to_string(source_path);
-    let source = match source {
-        Ok(source) => source,
-        Err(e) => {
-            eprintln!(
-                "[ERROR]: Failed to load file {}: {}",
-                source_path,
-                e.to_string()
-            );
-            "".to_string()
+fn read_shader_source(path: &str) -> Result<String, Error> {
+    return std::fs::read_to_string(path).map_err(|error| Error::Io {
+        path: path.to_string(),
+        error,
+    });
+}
+
+fn compile_shader(
+    source: &preprocessor::ProcessedSource,
+    source_name: &str,
+    shader_type: u32,
+) -> Result<u32, Error> {
+    let source_string = match std::ffi::CString::new(source.get_source()) {
+        Ok(source_string) => source_string,
+        Err(_) => {
+            return Err(Error::ShaderCompile {
+                source_name: source_name.to_string(),
+                log: "the source has a NUL character in it".to_string(),
+            })
         }
     };
 
@@ -539,8 +1105,7 @@ fn create_shader(source_path: &str, shader_type: u32) -> u32 {
 
     unsafe {
         shader = gl::CreateShader(shader_type);
-        let source = std::ffi::CString::new(source.as_bytes()).unwrap();
-        gl::ShaderSource(shader, 1, &source.as_ptr(), std::ptr::null());
+        gl::ShaderSource(shader, 1, &source_string.as_ptr(), std::ptr::null());
         gl::CompileShader(shader);
     }
 
@@ -551,121 +1116,450 @@ fn create_shader(source_path: &str, shader_type: u32) -> u32 {
     }
 
     if success != gl::TRUE.into() {
+        let mut log_length = 0;
+
+        unsafe {
+            gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut log_length);
+        }
+
+        let mut log = vec![0u8; log_length.max(1) as usize];
+
         unsafe {
-            let mut error_log_length = 0;
-            gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut error_log_length);
-            let mut error_log = Vec::with_capacity(error_log_length as usize);
-            error_log.set_len((error_log_length as usize) - 1);
             gl::GetShaderInfoLog(
                 shader,
-                error_log_length,
-                &mut error_log_length,
-                error_log.as_mut_ptr() as *mut gl::types::GLchar,
+                log.len() as i32,
+                &mut log_length,
+                log.as_mut_ptr() as *mut gl::types::GLchar,
             );
-            eprintln!(
-                "[ERROR]: Failed to link a shader program:\n{}\n\n",
-                std::str::from_utf8(&error_log).unwrap()
+            gl::DeleteShader(shader);
+        }
+
+        log.truncate(log_length.max(0) as usize);
+
+        // The line numbers in the log are for the preprocessed source, so
+        // they're mapped back to the files that the lines came from.
+        return Err(Error::ShaderCompile {
+            source_name: source_name.to_string(),
+            log: source.remap_log(&String::from_utf8_lossy(&log)),
+        });
+    }
+
+    return Ok(shader);
+}
+
+/// Where one of a program's shaders came from, so that it can be compiled
+/// again when it changes.
+#[derive(Clone)]
+enum ShaderSource {
+    File(String),
+    /// One of the shaders compiled into the crate, by file name.
+    Builtin(&'static str),
+    Inline {
+        name: &'static str,
+        source: String,
+    },
+}
+
+impl ShaderSource {
+    /// Returns the name to report errors with, and the preprocessed source.
+    fn load(
+        &self,
+        defines: &[(String, String)],
+    ) -> Result<(String, preprocessor::ProcessedSource), Error> {
+        let (name, source) = match self {
+            ShaderSource::File(path) => (path.clone(), read_shader_source(path)?),
+            ShaderSource::Builtin(name) => builtin_shaders::load(name)?,
+            ShaderSource::Inline { name, source } => (name.to_string(), source.clone()),
+        };
+
+        let processed = builtin_shaders::preprocessor(defines)
+            .process(&name, &source)
+            .map_err(Error::ShaderPreprocess)?;
+
+        return Ok((name, processed));
+    }
+
+    /// The file that the source is read from, if there is one.
+    fn path(&self) -> Option<PathBuf> {
+        match self {
+            ShaderSource::File(path) => Some(PathBuf::from(path)),
+            ShaderSource::Builtin(name) => builtin_shaders::override_path(name),
+            ShaderSource::Inline { .. } => None,
+        }
+    }
+}
+
+/// Returns the program along with the files that the sources included.
+fn link_program(
+    vertex: &ShaderSource,
+    fragment: &ShaderSource,
+    defines: &[(String, String)],
+) -> Result<(u32, Vec<PathBuf>), Error> {
+    let (vertex_source_name, vertex_source) = vertex.load(defines)?;
+    let (fragment_source_name, fragment_source) = fragment.load(defines)?;
+
+    let mut dependencies = vertex_source.get_dependencies().to_vec();
+    dependencies.extend_from_slice(fragment_source.get_dependencies());
+
+    let vertex = compile_shader(&vertex_source, &vertex_source_name, gl::VERTEX_SHADER)?;
+    let fragment =
+        match compile_shader(&fragment_source, &fragment_source_name, gl::FRAGMENT_SHADER) {
+            Ok(fragment) => fragment,
+            Err(error) => {
+                unsafe {
+                    gl::DeleteShader(vertex);
+                }
+                return Err(error);
+            }
+        };
+
+    let handle;
+
+    unsafe {
+        handle = gl::CreateProgram();
+        gl::AttachShader(handle, vertex);
+        gl::AttachShader(handle, fragment);
+        gl::LinkProgram(handle);
+
+        // We can delete the shaders once they're linked to the program.
+        gl::DeleteShader(vertex);
+        gl::DeleteShader(fragment);
+    }
+
+    let mut success = 0;
+
+    unsafe {
+        gl::GetProgramiv(handle, gl::LINK_STATUS, &mut success);
+    }
+
+    if success != gl::TRUE.into() {
+        let mut log_length = 0;
+
+        unsafe {
+            gl::GetProgramiv(handle, gl::INFO_LOG_LENGTH, &mut log_length);
+        }
+
+        let mut log = vec![0u8; log_length.max(1) as usize];
+
+        unsafe {
+            gl::GetProgramInfoLog(
+                handle,
+                log.len() as i32,
+                &mut log_length,
+                log.as_mut_ptr() as *mut gl::types::GLchar,
             );
+            gl::DeleteProgram(handle);
         }
+
+        log.truncate(log_length.max(0) as usize);
+
+        return Err(Error::ShaderLink {
+            vertex_source_name,
+            fragment_source_name,
+            log: String::from_utf8_lossy(&log).into_owned(),
+        });
     }
 
-    return shader;
+    return Ok((handle, dependencies));
 }
 
 struct ShaderProgram {
-    handle: u32,
+    // This changes when the program is hot reloaded.
+    handle: Cell<u32>,
+    uniform_locations: RefCell<HashMap<String, i32>>,
+    vertex_source: ShaderSource,
+    fragment_source: ShaderSource,
+    defines: Vec<(String, String)>,
+    // The files that the sources included the last time they were compiled.
+    dependencies: RefCell<Vec<PathBuf>>,
 }
 
 impl ShaderProgram {
-    fn new(vertex_source_path: &str, fragment_source_path: &str) -> ShaderProgram {
-        let vertex = create_shader(vertex_source_path, gl::VERTEX_SHADER);
-        let fragment = create_shader(fragment_source_path, gl::FRAGMENT_SHADER);
+    fn new(
+        vertex_source_path: &str,
+        fragment_source_path: &str,
+    ) -> Result<Rc<ShaderProgram>, Error> {
+        return ShaderProgram::load(
+            ShaderSource::File(vertex_source_path.to_string()),
+            ShaderSource::File(fragment_source_path.to_string()),
+            Vec::new(),
+        );
+    }
 
-        let handle;
+    /// Uses the shaders compiled into the crate, or the overrides from
+    /// `set_shader_override_directory`.
+    fn builtin(
+        vertex_name: &'static str,
+        fragment_name: &'static str,
+    ) -> Result<Rc<ShaderProgram>, Error> {
+        return ShaderProgram::load(
+            ShaderSource::Builtin(vertex_name),
+            ShaderSource::Builtin(fragment_name),
+            Vec::new(),
+        );
+    }
 
-        unsafe {
-            handle = gl::CreateProgram();
-            gl::AttachShader(handle, vertex);
-            gl::AttachShader(handle, fragment);
-            gl::LinkProgram(handle);
+    fn from_sources(
+        vertex_source: &str,
+        fragment_source: &str,
+    ) -> Result<Rc<ShaderProgram>, Error> {
+        return ShaderProgram::load(
+            ShaderSource::Inline {
+                name: "<vertex source>",
+                source: vertex_source.to_string(),
+            },
+            ShaderSource::Inline {
+                name: "<fragment source>",
+                source: fragment_source.to_string(),
+            },
+            Vec::new(),
+        );
+    }
 
-            // We can delete the shaders once they're linked to the program.
-            gl::DeleteShader(vertex);
-            gl::DeleteShader(fragment);
-        }
+    /// `defines` are injected into both shaders by the preprocessor.
+    fn load(
+        vertex_source: ShaderSource,
+        fragment_source: ShaderSource,
+        defines: Vec<(String, String)>,
+    ) -> Result<Rc<ShaderProgram>, Error> {
+        let (handle, dependencies) = link_program(&vertex_source, &fragment_source, &defines)?;
+
+        let program = Rc::new(ShaderProgram {
+            handle: Cell::new(handle),
+            uniform_locations: RefCell::new(HashMap::new()),
+            vertex_source,
+            fragment_source,
+            defines,
+            dependencies: RefCell::new(dependencies),
+        });
+
+        program.set_texture_samplers();
+        hot_reload::track(&program);
+
+        return Ok(program);
+    }
 
-        let mut success = 0;
+    /// Compiles the program again from its sources and swaps the new one in.
+    /// If that fails, the old one is kept. Uniforms other than the texture
+    /// samplers have to be set again afterwards.
+    fn reload(&self) -> Result<(), Error> {
+        let (handle, dependencies) =
+            link_program(&self.vertex_source, &self.fragment_source, &self.defines)?;
+        let old_handle = self.handle.replace(handle);
+        *self.dependencies.borrow_mut() = dependencies;
 
         unsafe {
-            gl::GetProgramiv(handle, gl::LINK_STATUS, &mut success);
+            gl::DeleteProgram(old_handle);
         }
 
-        if success != gl::TRUE.into() {
-            unsafe {
-                let mut error_log_length = 0;
-                gl::GetProgramiv(handle, gl::INFO_LOG_LENGTH, &mut error_log_length);
-                let mut error_log = Vec::with_capacity(error_log_length as usize);
-                error_log.set_len((error_log_length as usize) - 1);
-                gl::GetProgramInfoLog(
-                    handle,
-                    error_log_length,
-                    &mut error_log_length,
-                    error_log.as_mut_ptr() as *mut gl::types::GLchar,
-                );
-                eprintln!(
-                    "[ERROR]: Failed to link a shader program:\n{}\n\n",
-                    std::str::from_utf8(&error_log).unwrap()
-                );
-            }
-        }
+        self.uniform_locations.borrow_mut().clear();
+        self.set_texture_samplers();
+
+        return Ok(());
+    }
+
+    /// The files that the program's sources are read from, including the
+    /// ones they include.
+    fn source_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = [&self.vertex_source, &self.fragment_source]
+            .iter()
+            .filter_map(|source| source.path())
+            .collect();
+        paths.extend(self.dependencies.borrow().iter().cloned());
 
-        return ShaderProgram { handle: handle };
+        return paths;
     }
 
     fn use_program(&self) {
         unsafe {
-            gl::UseProgram(self.handle);
+            gl::UseProgram(self.handle.get());
+        }
+    }
+
+    /// Looks the location up the first time, and remembers it after that.
+    /// Uniforms that don't exist get -1, which OpenGL silently ignores.
+    fn get_uniform_location(&self, name: &str) -> i32 {
+        if let Some(location) = self.uniform_locations.borrow().get(name) {
+            return *location;
         }
+
+        let mut name_bytes = name.as_bytes().to_vec();
+        name_bytes.push(0);
+
+        let location =
+            unsafe { gl::GetUniformLocation(self.handle.get(), name_bytes.as_ptr() as *const i8) };
+
+        self.uniform_locations
+            .borrow_mut()
+            .insert(name.to_string(), location);
+
+        return location;
     }
 
+    // These use the ProgramUniform functions so that they work without the
+    // program being in use.
+
     fn set_uniform_1i(&self, name: &str, value: i32) {
-        let mut name = name.as_bytes().to_vec();
-        name.push(0);
+        unsafe {
+            gl::ProgramUniform1i(self.handle.get(), self.get_uniform_location(name), value);
+        }
+    }
+
+    fn set_uniform_matrix_4f(&self, name: &str, value: &math::Matrix4<f32>) {
+        // Matrix4 is row-major, and OpenGL wants column-major.
+        unsafe {
+            gl::ProgramUniformMatrix4fv(
+                self.handle.get(),
+                self.get_uniform_location(name),
+                1,
+                gl::TRUE,
+                value.as_ptr(),
+            );
+        }
+    }
+
+    fn set_uniform_1f(&self, name: &str, value: f32) {
+        unsafe {
+            gl::ProgramUniform1f(self.handle.get(), self.get_uniform_location(name), value);
+        }
+    }
 
+    fn set_uniform_2f(&self, name: &str, x: f32, y: f32) {
         unsafe {
-            let location = gl::GetUniformLocation(self.handle, name.as_ptr() as *const i8);
-            gl::Uniform1i(location, value);
+            gl::ProgramUniform2f(self.handle.get(), self.get_uniform_location(name), x, y);
         }
     }
 
-    fn set_unifrom_matrix_4f(&self, name: &str, value: &math::Matrix4<f32>) {
-        let mut name = name.as_bytes().to_vec();
-        name.push(0);
+    fn set_uniform_3f(&self, name: &str, x: f32, y: f32, z: f32) {
+        unsafe {
+            gl::ProgramUniform3f(self.handle.get(), self.get_uniform_location(name), x, y, z);
+        }
+    }
 
+    fn set_uniform_4f(&self, name: &str, x: f32, y: f32, z: f32, w: f32) {
         unsafe {
-            let location = gl::GetUniformLocation(self.handle, name.as_ptr() as *const i8);
-            gl::UniformMatrix4fv(location, 1, gl::TRUE, value.as_ptr());
+            gl::ProgramUniform4f(
+                self.handle.get(),
+                self.get_uniform_location(name),
+                x,
+                y,
+                z,
+                w,
+            );
         }
     }
 
-    // I'll add the uniform functions when I need them.
+    fn set_uniform(&self, name: &str, value: &UniformValue) {
+        match value {
+            UniformValue::Int(value) => self.set_uniform_1i(name, *value),
+            UniformValue::Float(value) => self.set_uniform_1f(name, *value),
+            UniformValue::Vec2(x, y) => self.set_uniform_2f(name, *x, *y),
+            UniformValue::Vec3(x, y, z) => self.set_uniform_3f(name, *x, *y, *z),
+            UniformValue::Vec4(x, y, z, w) => self.set_uniform_4f(name, *x, *y, *z, *w),
+            UniformValue::Matrix4(matrix) => self.set_uniform_matrix_4f(name, matrix),
+            // Every one of the renderer's textures is bound to the texture
+            // unit with the same number as its id.
+            UniformValue::Texture(texture_id) => self.set_uniform_1i(name, *texture_id as i32),
+        }
+    }
+
+    /// Points `texture_samplers` at the renderer's texture units. They never
+    /// change, so this only happens when the program is linked.
+    fn set_texture_samplers(&self) {
+        for i in 0..32 {
+            self.set_uniform_1i(format!("texture_samplers[{}]", i).as_str(), i);
+        }
+    }
 }
 
 impl Drop for ShaderProgram {
     fn drop(&mut self) {
         unsafe {
             gl::UseProgram(0);
-            gl::DeleteProgram(self.handle);
+            gl::DeleteProgram(self.handle.get());
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TextureFilter {
+    Linear,
+    /// Keeps pixels sharp when scaled up, for pixel art.
+    Nearest,
+}
+
+impl TextureFilter {
+    fn to_gl(self) -> i32 {
+        match self {
+            TextureFilter::Linear => gl::LINEAR.try_into().unwrap(),
+            TextureFilter::Nearest => gl::NEAREST.try_into().unwrap(),
         }
     }
 }
 
 struct InternalTexture {
     handle: u32,
+    width: u32,
+    height: u32,
 }
 
 impl InternalTexture {
-    fn new(image_path: &str) -> InternalTexture {
+    /// Creates a texture with no contents, for drawing into.
+    fn new_empty(width: u32, height: u32, filter: TextureFilter) -> InternalTexture {
+        let mut handle: u32 = 0;
+
+        unsafe {
+            gl::GenTextures(1, &mut handle);
+            gl::BindTexture(gl::TEXTURE_2D, handle);
+
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, filter.to_gl());
+            gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, filter.to_gl());
+
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_S,
+                gl::CLAMP_TO_EDGE.try_into().unwrap(),
+            );
+            gl::TexParameteri(
+                gl::TEXTURE_2D,
+                gl::TEXTURE_WRAP_T,
+                gl::CLAMP_TO_EDGE.try_into().unwrap(),
+            );
+
+            gl::TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA8.try_into().unwrap(),
+                width.try_into().unwrap(),
+                height.try_into().unwrap(),
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                std::ptr::null(),
+            );
+
+            gl::BindTexture(gl::TEXTURE_2D, 0);
+        }
+
+        return InternalTexture {
+            handle,
+            width,
+            height,
+        };
+    }
+
+    fn new(image_path: &str) -> Result<InternalTexture, Error> {
+        // The image is loaded first so that there's no texture to clean up if
+        // it fails.
+        let mut image_file = std::fs::File::open(image_path).map_err(|error| Error::Io {
+            path: image_path.to_string(),
+            error,
+        })?;
+        let (image_info, image_data) = stbi_load_from_reader(&mut image_file, Channels::Default)
+            .ok_or_else(|| Error::ImageDecode {
+                path: image_path.to_string(),
+            })?;
+
         let mut handle: u32 = 0;
 
         unsafe {
@@ -695,10 +1589,6 @@ impl InternalTexture {
             );
         }
 
-        let mut image_file = std::fs::File::open(image_path).unwrap();
-        let (image_info, image_data) =
-            stbi_load_from_reader(&mut image_file, Channels::Default).unwrap();
-
         let image_format = match image_info.components {
             1 => gl::RED,
             2 => gl::RG,
@@ -724,7 +1614,11 @@ impl InternalTexture {
             gl::BindTexture(gl::TEXTURE_2D, 0);
         }
 
-        return InternalTexture { handle };
+        return Ok(InternalTexture {
+            handle,
+            width: image_info.width as u32,
+            height: image_info.height as u32,
+        });
     }
 
     fn bind(&self) {
@@ -0,0 +1,112 @@
+use super::preprocessor::Preprocessor;
+use crate::Error;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+// The shaders that the renderer and the post-processing effects use, compiled
+// into the crate so that games don't need to ship them.
+const BUILTIN_SHADERS: &[(&str, &str)] = &[
+    (
+        "2d_renderer_basic.vs",
+        include_str!("../../shaders/2d_renderer_basic.vs"),
+    ),
+    (
+        "2d_renderer_basic.fs",
+        include_str!("../../shaders/2d_renderer_basic.fs"),
+    ),
+    (
+        "post_process.vs",
+        include_str!("../../shaders/post_process.vs"),
+    ),
+    ("post_copy.fs", include_str!("../../shaders/post_copy.fs")),
+    ("post_blur.fs", include_str!("../../shaders/post_blur.fs")),
+    (
+        "post_bloom_extract.fs",
+        include_str!("../../shaders/post_bloom_extract.fs"),
+    ),
+    (
+        "post_bloom_combine.fs",
+        include_str!("../../shaders/post_bloom_combine.fs"),
+    ),
+    (
+        "post_vignette.fs",
+        include_str!("../../shaders/post_vignette.fs"),
+    ),
+    (
+        "post_chromatic_aberration.fs",
+        include_str!("../../shaders/post_chromatic_aberration.fs"),
+    ),
+    ("post_crt.fs", include_str!("../../shaders/post_crt.fs")),
+    (
+        "post_color_grading.fs",
+        include_str!("../../shaders/post_color_grading.fs"),
+    ),
+];
+
+static OVERRIDE_DIRECTORY: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+static INCLUDE_FILES: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Makes the built-in shaders load from files in `directory` when it has a
+/// file with the same name, like `2d_renderer_basic.fs`, instead of using the
+/// copies compiled into the crate. Only shaders created after this is called
+/// are affected. Pass `None` to go back to the built-in ones.
+pub fn set_shader_override_directory(directory: Option<&Path>) {
+    *OVERRIDE_DIRECTORY.lock().unwrap() = directory.map(Path::to_path_buf);
+}
+
+/// Makes `#include "name"` in any shader use `source`, so shared code
+/// doesn't have to be on the disk. These are looked up before files relative
+/// to the shader.
+pub fn register_shader_include(name: &str, source: &str) {
+    let mut files = INCLUDE_FILES.lock().unwrap();
+
+    match files.iter_mut().find(|(n, _)| n == name) {
+        Some(file) => file.1 = source.to_string(),
+        None => files.push((name.to_string(), source.to_string())),
+    }
+}
+
+/// A preprocessor with the registered includes and the given defines.
+pub(super) fn preprocessor(defines: &[(String, String)]) -> Preprocessor {
+    let mut preprocessor = Preprocessor::new();
+
+    for (name, source) in INCLUDE_FILES.lock().unwrap().iter() {
+        preprocessor.add_virtual_file(name, source);
+    }
+
+    for (name, value) in defines {
+        preprocessor.define(name, value);
+    }
+
+    return preprocessor;
+}
+
+/// The file in the override directory that replaces a built-in shader, if
+/// there is one.
+pub(super) fn override_path(name: &str) -> Option<PathBuf> {
+    let path = OVERRIDE_DIRECTORY.lock().unwrap().as_ref()?.join(name);
+
+    if path.is_file() {
+        return Some(path);
+    }
+
+    return None;
+}
+
+/// Returns the name to report errors with and the source of a built-in
+/// shader, from the override directory if there is one.
+pub(super) fn load(name: &str) -> Result<(String, String), Error> {
+    if let Some(path) = override_path(name) {
+        let path = path.to_string_lossy().into_owned();
+        let source = super::read_shader_source(&path)?;
+        return Ok((path, source));
+    }
+
+    let (_, source) = BUILTIN_SHADERS
+        .iter()
+        .find(|(builtin_name, _)| *builtin_name == name)
+        .expect("There's no built-in shader with that name.");
+
+    return Ok((format!("<built-in {}>", name), source.to_string()));
+}
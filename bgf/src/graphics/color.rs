@@ -0,0 +1,333 @@
+use crate::math::Vector4;
+
+/// An RGBA color, with each channel going from 0 to 1.
+///
+/// The renderer expects sRGB, the same as hex codes and color pickers use.
+/// It writes colors to the screen as they are, without converting them, so
+/// everything that takes a `Color` should be given sRGB. `to_linear` is for
+/// maths that needs linear light, like lighting or blending colors smoothly,
+/// and the result should go through `to_srgb` before it's drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Color {
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32,
+    pub alpha: f32,
+}
+
+pub mod colors {
+    use super::Color;
+
+    pub const TRANSPARENT: Color = Color::new(0.0, 0.0, 0.0, 0.0);
+    pub const WHITE: Color = Color::rgb(1.0, 1.0, 1.0);
+    pub const BLACK: Color = Color::rgb(0.0, 0.0, 0.0);
+    pub const GRAY: Color = Color::rgb(0.5, 0.5, 0.5);
+    pub const LIGHT_GRAY: Color = Color::rgb(0.75, 0.75, 0.75);
+    pub const DARK_GRAY: Color = Color::rgb(0.25, 0.25, 0.25);
+    pub const RED: Color = Color::rgb(1.0, 0.0, 0.0);
+    pub const GREEN: Color = Color::rgb(0.0, 1.0, 0.0);
+    pub const BLUE: Color = Color::rgb(0.0, 0.0, 1.0);
+    pub const CYAN: Color = Color::rgb(0.0, 1.0, 1.0);
+    pub const MAGENTA: Color = Color::rgb(1.0, 0.0, 1.0);
+    pub const YELLOW: Color = Color::rgb(1.0, 1.0, 0.0);
+    pub const ORANGE: Color = Color::rgb(1.0, 0.5, 0.0);
+    pub const PURPLE: Color = Color::rgb(0.5, 0.0, 0.5);
+    pub const PINK: Color = Color::rgb(1.0, 0.75, 0.8);
+    pub const BROWN: Color = Color::rgb(0.6, 0.3, 0.1);
+    pub const SKY_BLUE: Color = Color::rgb(0.53, 0.81, 0.92);
+    pub const CORNFLOWER_BLUE: Color = Color::rgb(0.39, 0.58, 0.93);
+
+    /// Every color above, by its name in lowercase, for `Color::from_name`.
+    pub const ALL: [(&str, Color); 18] = [
+        ("transparent", TRANSPARENT),
+        ("white", WHITE),
+        ("black", BLACK),
+        ("gray", GRAY),
+        ("light_gray", LIGHT_GRAY),
+        ("dark_gray", DARK_GRAY),
+        ("red", RED),
+        ("green", GREEN),
+        ("blue", BLUE),
+        ("cyan", CYAN),
+        ("magenta", MAGENTA),
+        ("yellow", YELLOW),
+        ("orange", ORANGE),
+        ("purple", PURPLE),
+        ("pink", PINK),
+        ("brown", BROWN),
+        ("sky_blue", SKY_BLUE),
+        ("cornflower_blue", CORNFLOWER_BLUE),
+    ];
+}
+
+fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+impl Color {
+    pub const fn new(red: f32, green: f32, blue: f32, alpha: f32) -> Color {
+        Color {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+
+    /// A fully opaque color.
+    pub const fn rgb(red: f32, green: f32, blue: f32) -> Color {
+        Color::new(red, green, blue, 1.0)
+    }
+
+    pub fn from_rgba8(red: u8, green: u8, blue: u8, alpha: u8) -> Color {
+        Color::new(
+            red as f32 / 255.0,
+            green as f32 / 255.0,
+            blue as f32 / 255.0,
+            alpha as f32 / 255.0,
+        )
+    }
+
+    pub fn from_rgb8(red: u8, green: u8, blue: u8) -> Color {
+        Color::from_rgba8(red, green, blue, 255)
+    }
+
+    /// Clamps each channel to 0 to 1 first.
+    pub fn to_rgba8(&self) -> [u8; 4] {
+        [
+            to_u8(self.red),
+            to_u8(self.green),
+            to_u8(self.blue),
+            to_u8(self.alpha),
+        ]
+    }
+
+    /// Takes `#RGB`, `#RGBA`, `#RRGGBB` or `#RRGGBBAA`, with or without the
+    /// `#`. Returns `None` for anything else.
+    pub fn from_hex(hex: &str) -> Option<Color> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+
+        if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let digit = |index: usize| u8::from_str_radix(&hex[index..index + 1], 16).unwrap();
+        let pair = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).unwrap();
+
+        let [red, green, blue, alpha] = match hex.len() {
+            3 | 4 => {
+                let alpha = if hex.len() == 4 { digit(3) } else { 15 };
+                [digit(0), digit(1), digit(2), alpha].map(|value| value * 17)
+            }
+            6 | 8 => {
+                let alpha = if hex.len() == 8 { pair(6) } else { 255 };
+                [pair(0), pair(2), pair(4), alpha]
+            }
+            _ => return None,
+        };
+
+        return Some(Color::from_rgba8(red, green, blue, alpha));
+    }
+
+    /// `#RRGGBB`, or `#RRGGBBAA` if the color isn't fully opaque.
+    pub fn to_hex(&self) -> String {
+        let [red, green, blue, alpha] = self.to_rgba8();
+
+        if alpha == 255 {
+            return format!("#{:02x}{:02x}{:02x}", red, green, blue);
+        }
+
+        return format!("#{:02x}{:02x}{:02x}{:02x}", red, green, blue, alpha);
+    }
+
+    /// Looks the name up in `colors::ALL`, ignoring case. Spaces and dashes
+    /// work in place of underscores, so `"Sky Blue"` works too.
+    pub fn from_name(name: &str) -> Option<Color> {
+        let name = name.to_lowercase().replace([' ', '-'], "_");
+
+        return colors::ALL
+            .iter()
+            .find(|(other, _)| *other == name)
+            .map(|(_, color)| *color);
+    }
+
+    /// `hue` is in degrees and wraps around, and the rest go from 0 to 1.
+    pub fn from_hsv(hue: f32, saturation: f32, value: f32, alpha: f32) -> Color {
+        let chroma = value * saturation;
+        return Color::from_hue(hue, chroma, value - chroma, alpha);
+    }
+
+    /// The hue (in degrees, from 0 to 360), saturation and value.
+    pub fn to_hsv(&self) -> (f32, f32, f32) {
+        let (hue, chroma, max, _) = self.get_hue();
+        let saturation = if max == 0.0 { 0.0 } else { chroma / max };
+
+        return (hue, saturation, max);
+    }
+
+    /// `hue` is in degrees and wraps around, and the rest go from 0 to 1.
+    pub fn from_hsl(hue: f32, saturation: f32, lightness: f32, alpha: f32) -> Color {
+        let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+        return Color::from_hue(hue, chroma, lightness - chroma / 2.0, alpha);
+    }
+
+    /// The hue (in degrees, from 0 to 360), saturation and lightness.
+    pub fn to_hsl(&self) -> (f32, f32, f32) {
+        let (hue, chroma, max, min) = self.get_hue();
+        let lightness = (max + min) / 2.0;
+
+        let saturation = if lightness == 0.0 || lightness == 1.0 {
+            0.0
+        } else {
+            chroma / (1.0 - (2.0 * lightness - 1.0).abs())
+        };
+
+        return (hue, saturation, lightness);
+    }
+
+    // Both HSV and HSL come down to a hue, how much color there is (the
+    // chroma), and how much gets added to every channel.
+    fn from_hue(hue: f32, chroma: f32, offset: f32, alpha: f32) -> Color {
+        let sector = hue.rem_euclid(360.0) / 60.0;
+        let second = chroma * (1.0 - (sector % 2.0 - 1.0).abs());
+
+        let (red, green, blue) = match sector as u32 {
+            0 => (chroma, second, 0.0),
+            1 => (second, chroma, 0.0),
+            2 => (0.0, chroma, second),
+            3 => (0.0, second, chroma),
+            4 => (second, 0.0, chroma),
+            _ => (chroma, 0.0, second),
+        };
+
+        return Color::new(red + offset, green + offset, blue + offset, alpha);
+    }
+
+    // The hue in degrees, the chroma, and the biggest and smallest channels.
+    fn get_hue(&self) -> (f32, f32, f32, f32) {
+        let max = self.red.max(self.green).max(self.blue);
+        let min = self.red.min(self.green).min(self.blue);
+        let chroma = max - min;
+
+        let sector = if chroma == 0.0 {
+            0.0
+        } else if max == self.red {
+            ((self.green - self.blue) / chroma).rem_euclid(6.0)
+        } else if max == self.green {
+            (self.blue - self.red) / chroma + 2.0
+        } else {
+            (self.red - self.green) / chroma + 4.0
+        };
+
+        return (sector * 60.0, chroma, max, min);
+    }
+
+    /// Blends every channel, alpha included. `t` of 0 gives `self`, and 1
+    /// gives `other`.
+    pub fn lerp(&self, other: &Color, t: f32) -> Color {
+        Color::from(self.to_vec().lerp(other.to_vec(), t))
+    }
+
+    /// Multiplies the color channels by alpha, for blending with
+    /// `(ONE, ONE_MINUS_SRC_ALPHA)`.
+    pub fn premultiply(&self) -> Color {
+        Color::new(
+            self.red * self.alpha,
+            self.green * self.alpha,
+            self.blue * self.alpha,
+            self.alpha,
+        )
+    }
+
+    /// From sRGB to linear, for doing lighting or blending maths. Alpha is
+    /// already linear, so it stays the same.
+    pub fn to_linear(&self) -> Color {
+        Color::new(
+            srgb_to_linear(self.red),
+            srgb_to_linear(self.green),
+            srgb_to_linear(self.blue),
+            self.alpha,
+        )
+    }
+
+    /// Undoes `to_linear`.
+    pub fn to_srgb(&self) -> Color {
+        Color::new(
+            linear_to_srgb(self.red),
+            linear_to_srgb(self.green),
+            linear_to_srgb(self.blue),
+            self.alpha,
+        )
+    }
+
+    /// Moves towards white by `amount`, from 0 to 1.
+    pub fn lighten(&self, amount: f32) -> Color {
+        self.lerp(&colors::WHITE.with_alpha(self.alpha), amount)
+    }
+
+    /// Moves towards black by `amount`, from 0 to 1.
+    pub fn darken(&self, amount: f32) -> Color {
+        self.lerp(&colors::BLACK.with_alpha(self.alpha), amount)
+    }
+
+    /// How bright the color looks, from 0 to 1, going by the Rec. 709
+    /// weights.
+    pub fn get_luminance(&self) -> f32 {
+        let linear = self.to_linear();
+        return 0.2126 * linear.red + 0.7152 * linear.green + 0.0722 * linear.blue;
+    }
+
+    pub fn with_alpha(&self, alpha: f32) -> Color {
+        Color { alpha, ..*self }
+    }
+
+    /// Multiplies the alpha, so `fade(0.5)` makes it half as opaque.
+    pub fn fade(&self, amount: f32) -> Color {
+        self.with_alpha(self.alpha * amount)
+    }
+
+    pub(super) fn to_vec(self) -> Vector4<f32> {
+        Vector4::from(self)
+    }
+}
+
+impl From<Color> for Vector4<f32> {
+    fn from(color: Color) -> Vector4<f32> {
+        Vector4::new(color.red, color.green, color.blue, color.alpha)
+    }
+}
+
+impl From<Vector4<f32>> for Color {
+    fn from(vector: Vector4<f32>) -> Color {
+        Color::new(vector.x, vector.y, vector.z, vector.w)
+    }
+}
+
+impl From<[f32; 4]> for Color {
+    fn from(array: [f32; 4]) -> Color {
+        Color::new(array[0], array[1], array[2], array[3])
+    }
+}
+
+impl From<Color> for [f32; 4] {
+    fn from(color: Color) -> [f32; 4] {
+        [color.red, color.green, color.blue, color.alpha]
+    }
+}
@@ -0,0 +1,242 @@
+use super::{Color, LineCap, LineJoin, Renderer2D};
+use crate::math::Vector2;
+use crate::Window;
+
+const LINE_THICKNESS: f32 = 1.0;
+const POINT_RADIUS: f32 = 3.0;
+const CIRCLE_SEGMENTS: u32 = 24;
+const ARROW_HEAD_SIZE: f32 = 8.0;
+
+/// Something queued with `DebugDraw`, in the order it was queued.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DebugShape {
+    Rect {
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+    },
+    Circle {
+        center: Vector2<f32>,
+        radius: f32,
+    },
+    Line {
+        start: Vector2<f32>,
+        end: Vector2<f32>,
+    },
+    Arrow {
+        start: Vector2<f32>,
+        end: Vector2<f32>,
+    },
+    Point {
+        position: Vector2<f32>,
+    },
+    Text {
+        x: f32,
+        y: f32,
+        text: String,
+    },
+}
+
+/// A queue of wireframe shapes and labels for visualising things like
+/// collision boxes and AI paths. Keep one next to the renderer and hand it to
+/// whatever needs to draw something, then `render` it once a frame. The
+/// shapes go on top of everything else in the batch, without changing the
+/// order of anything that was drawn normally.
+#[derive(Clone, Debug)]
+pub struct DebugDraw {
+    shapes: Vec<(DebugShape, Color)>,
+    enabled: bool,
+    toggle_key: glfw::Key,
+    toggle_key_was_down: bool,
+}
+
+impl Default for DebugDraw {
+    fn default() -> DebugDraw {
+        DebugDraw::new()
+    }
+}
+
+impl DebugDraw {
+    pub fn new() -> DebugDraw {
+        DebugDraw {
+            shapes: Vec::new(),
+            enabled: true,
+            toggle_key: glfw::Key::F3,
+            toggle_key_was_down: false,
+        }
+    }
+
+    fn push(&mut self, shape: DebugShape, color: &Color) {
+        // No point in queueing things that will never be drawn.
+        if self.enabled {
+            self.shapes.push((shape, *color));
+        }
+    }
+
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, color: &Color) {
+        self.push(
+            DebugShape::Rect {
+                x,
+                y,
+                width,
+                height,
+            },
+            color,
+        );
+    }
+
+    pub fn circle(&mut self, center: &Vector2<f32>, radius: f32, color: &Color) {
+        self.push(
+            DebugShape::Circle {
+                center: *center,
+                radius,
+            },
+            color,
+        );
+    }
+
+    pub fn line(&mut self, start: &Vector2<f32>, end: &Vector2<f32>, color: &Color) {
+        self.push(
+            DebugShape::Line {
+                start: *start,
+                end: *end,
+            },
+            color,
+        );
+    }
+
+    pub fn arrow(&mut self, start: &Vector2<f32>, end: &Vector2<f32>, color: &Color) {
+        self.push(
+            DebugShape::Arrow {
+                start: *start,
+                end: *end,
+            },
+            color,
+        );
+    }
+
+    pub fn point(&mut self, position: &Vector2<f32>, color: &Color) {
+        self.push(
+            DebugShape::Point {
+                position: *position,
+            },
+            color,
+        );
+    }
+
+    pub fn text(&mut self, x: f32, y: f32, text: &str, color: &Color) {
+        self.push(
+            DebugShape::Text {
+                x,
+                y,
+                text: text.to_string(),
+            },
+            color,
+        );
+    }
+
+    /// Everything queued since the last `render` or `clear`.
+    pub fn get_shapes(&self) -> &[(DebugShape, Color)] {
+        &self.shapes
+    }
+
+    pub fn clear(&mut self) {
+        self.shapes.clear();
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Turning it off also throws away anything that's queued.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+
+        if !enabled {
+            self.shapes.clear();
+        }
+    }
+
+    /// The key that `DebugDraw::update` toggles the overlay with. F3 by
+    /// default.
+    pub fn set_toggle_key(&mut self, key: glfw::Key) {
+        self.toggle_key = key;
+    }
+
+    /// Checks whether the toggle key was just pressed. Call this once per
+    /// frame.
+    pub fn update(&mut self, window: &Window) {
+        let is_down = window.is_key_down(self.toggle_key);
+
+        if is_down && !self.toggle_key_was_down {
+            self.set_enabled(!self.enabled);
+        }
+
+        self.toggle_key_was_down = is_down;
+    }
+
+    /// Adds everything that was queued to the renderer's batch and clears the
+    /// queue. It can be called anywhere between `Renderer2D::begin` and
+    /// `Renderer2D::end`, and the shapes still end up on top, in the order
+    /// they were queued.
+    pub fn render(&mut self, renderer: &mut Renderer2D) {
+        let shapes = std::mem::take(&mut self.shapes);
+        let material = renderer.material.take();
+        renderer.overlay = true;
+
+        for (shape, color) in shapes {
+            match shape {
+                DebugShape::Rect {
+                    x,
+                    y,
+                    width,
+                    height,
+                } => {
+                    let points = [
+                        Vector2::new(x, y),
+                        Vector2::new(x + width, y),
+                        Vector2::new(x + width, y + height),
+                        Vector2::new(x, y + height),
+                    ];
+                    renderer.draw_polygon_outline(&points, LINE_THICKNESS, LineJoin::Miter, &color);
+                }
+                DebugShape::Circle { center, radius } => {
+                    renderer.draw_circle_outline(
+                        &center,
+                        radius,
+                        LINE_THICKNESS,
+                        CIRCLE_SEGMENTS,
+                        &color,
+                    );
+                }
+                DebugShape::Line { start, end } => {
+                    renderer.draw_line(&start, &end, LINE_THICKNESS, LineCap::Butt, &color);
+                }
+                DebugShape::Arrow { start, end } => {
+                    renderer.draw_line(&start, &end, LINE_THICKNESS, LineCap::Butt, &color);
+
+                    let length = start.distance(end);
+
+                    if length > 0.0 {
+                        let direction = (end - start) / length;
+                        let size = ARROW_HEAD_SIZE.min(length);
+                        let base = end - direction * size;
+                        let side = direction.perpendicular() * (size / 2.0);
+
+                        renderer.draw_triangle(&end, &(base + side), &(base - side), &color);
+                    }
+                }
+                DebugShape::Point { position } => {
+                    renderer.draw_circle(&position, POINT_RADIUS, 8, &color);
+                }
+                DebugShape::Text { x, y, text } => {
+                    renderer.draw_text(x, y, 1.0, &text, &color);
+                }
+            }
+        }
+
+        renderer.overlay = false;
+        renderer.material = material;
+    }
+}
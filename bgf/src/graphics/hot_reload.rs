@@ -0,0 +1,170 @@
+use super::{measure_text, Color, Renderer2D, ShaderProgram};
+use crate::Error;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::rc::{Rc, Weak};
+use std::time::{Duration, Instant, SystemTime};
+
+const ERROR_TEXT_SCALE: f32 = 1.0;
+const ERROR_PADDING: f32 = 8.0;
+
+thread_local! {
+    // Every shader program that's been created, so that the watcher can find
+    // them without the renderer or the post-processing stack being involved.
+    static PROGRAMS: RefCell<Vec<Weak<ShaderProgram>>> = const { RefCell::new(Vec::new()) };
+}
+
+pub(super) fn track(program: &Rc<ShaderProgram>) {
+    PROGRAMS.with(|programs| programs.borrow_mut().push(Rc::downgrade(program)));
+}
+
+fn live_programs() -> Vec<Rc<ShaderProgram>> {
+    PROGRAMS.with(|programs| {
+        let mut programs = programs.borrow_mut();
+        programs.retain(|program| program.strong_count() > 0);
+        programs.iter().filter_map(Weak::upgrade).collect()
+    })
+}
+
+/// Watches the source files of every shader for changes, and recompiles the
+/// programs that use them. This is meant for development, while iterating on
+/// shaders.
+///
+/// Programs are only swapped out in `update`, so call it between frames. When
+/// a new version fails to compile, the old program keeps being used and the
+/// error is logged and kept until it's fixed. `render_errors` puts those on
+/// the screen.
+///
+/// Built-in shaders are only watched when they've been overridden with
+/// `set_shader_override_directory`.
+pub struct ShaderWatcher {
+    check_interval: Duration,
+    last_check: Option<Instant>,
+    modified_times: HashMap<PathBuf, SystemTime>,
+    errors: Vec<(Weak<ShaderProgram>, Error)>,
+}
+
+impl ShaderWatcher {
+    /// `check_interval` is how often the files are looked at, in seconds.
+    pub fn new(check_interval: f64) -> ShaderWatcher {
+        ShaderWatcher {
+            check_interval: Duration::from_secs_f64(check_interval.max(0.0)),
+            last_check: None,
+            modified_times: HashMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Reloads the programs whose files changed since the last check. Returns
+    /// how many of them were reloaded successfully.
+    pub fn update(&mut self) -> usize {
+        let now = Instant::now();
+
+        if let Some(last_check) = self.last_check {
+            if now.duration_since(last_check) < self.check_interval {
+                return 0;
+            }
+        }
+
+        self.last_check = Some(now);
+
+        let programs = live_programs();
+        self.errors
+            .retain(|(program, _)| program.strong_count() > 0);
+
+        // Several programs can share a file, so every file is only compared
+        // once and the programs are checked against the ones that changed.
+        let mut changed = HashSet::new();
+
+        for path in programs.iter().flat_map(|program| program.source_paths()) {
+            if changed.contains(&path) {
+                continue;
+            }
+
+            let modified = match std::fs::metadata(&path).and_then(|data| data.modified()) {
+                Ok(modified) => modified,
+                // It's probably in the middle of being saved.
+                Err(_) => continue,
+            };
+
+            match self.modified_times.insert(path.clone(), modified) {
+                Some(previous) if previous != modified => {
+                    changed.insert(path);
+                }
+                _ => {}
+            }
+        }
+
+        let mut reloaded = 0;
+
+        for program in programs {
+            if !program
+                .source_paths()
+                .iter()
+                .any(|path| changed.contains(path))
+            {
+                continue;
+            }
+
+            let weak = Rc::downgrade(&program);
+            self.errors
+                .retain(|(failed, _)| !Weak::ptr_eq(failed, &weak));
+
+            match program.reload() {
+                Ok(()) => reloaded += 1,
+                Err(error) => {
+                    log::error!("Failed to reload a shader: {}", error);
+                    self.errors.push((weak, error));
+                }
+            }
+        }
+
+        if reloaded > 0 {
+            log::info!("Reloaded {} shader program(s)", reloaded);
+        }
+
+        return reloaded;
+    }
+
+    /// The errors from programs that failed to reload and haven't been fixed
+    /// yet.
+    pub fn errors(&self) -> impl Iterator<Item = &Error> {
+        self.errors.iter().map(|(_, error)| error)
+    }
+
+    /// Draws the errors in the top left corner of the screen, in its own
+    /// batch. Call it after `Renderer2D::end`.
+    pub fn render_errors(&self, renderer: &mut Renderer2D) {
+        if self.errors.is_empty() {
+            return;
+        }
+
+        let text = self
+            .errors()
+            .map(|error| error.to_string())
+            .collect::<Vec<String>>()
+            .join("\n");
+        let (width, height) = measure_text(&text, ERROR_TEXT_SCALE);
+
+        renderer.begin();
+
+        renderer.draw_quad(
+            0.0,
+            0.0,
+            width + ERROR_PADDING * 2.0,
+            height + ERROR_PADDING * 2.0,
+            &Color::new(0.0, 0.0, 0.0, 0.8),
+            -1.0,
+        );
+        renderer.draw_text(
+            ERROR_PADDING,
+            ERROR_PADDING,
+            ERROR_TEXT_SCALE,
+            &text,
+            &Color::rgb(1.0, 0.4, 0.4),
+        );
+
+        renderer.end();
+    }
+}
@@ -0,0 +1,180 @@
+use super::{ShaderProgram, ShaderSource, UniformValue};
+use crate::{math, Error};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// A shader program that works with `Renderer2D`'s batches.
+///
+/// Vertex shaders get the batch's vertex layout:
+/// - `layout (location = 0) in vec2 a_position;`
+/// - `layout (location = 1) in vec2 a_uv;`
+/// - `layout (location = 2) in vec4 a_color;`
+/// - `layout (location = 3) in float a_texture;`
+///
+/// along with `uniform mat4 projection`. Fragment shaders that go with the
+/// default vertex shader get `in vec2 uv`, `in vec4 color` and
+/// `flat in float texture_unit`, which is negative for untextured shapes and
+/// otherwise indexes `uniform sampler2D texture_samplers[32]`.
+///
+/// Cloning a shader is cheap, the clones share the same program.
+///
+/// Values set directly on a shader are lost when a `ShaderWatcher` reloads
+/// it, while a `Material`'s are set again every time it's drawn with.
+#[derive(Clone)]
+pub struct Shader {
+    pub(super) program: Rc<ShaderProgram>,
+}
+
+impl Shader {
+    pub fn from_files(
+        vertex_source_path: &str,
+        fragment_source_path: &str,
+    ) -> Result<Shader, Error> {
+        let program = ShaderProgram::new(vertex_source_path, fragment_source_path)?;
+        return Ok(Shader { program });
+    }
+
+    /// Uses the renderer's own vertex shader with a custom fragment shader.
+    pub fn from_fragment_file(fragment_source_path: &str) -> Result<Shader, Error> {
+        let program = ShaderProgram::load(
+            ShaderSource::Builtin("2d_renderer_basic.vs"),
+            ShaderSource::File(fragment_source_path.to_string()),
+            Vec::new(),
+        )?;
+
+        return Ok(Shader { program });
+    }
+
+    /// Errors name the shaders `<vertex source>` and `<fragment source>`.
+    pub fn from_sources(vertex_source: &str, fragment_source: &str) -> Result<Shader, Error> {
+        let program = ShaderProgram::from_sources(vertex_source, fragment_source)?;
+        return Ok(Shader { program });
+    }
+
+    /// Compiles the same sources again with some `#define`s added after the
+    /// `#version` line, on top of the ones this shader already has.
+    pub fn variant(&self, defines: &[(&str, &str)]) -> Result<Shader, Error> {
+        let mut all_defines = self.program.defines.clone();
+
+        for (name, value) in defines {
+            match all_defines.iter_mut().find(|(n, _)| n == name) {
+                Some(define) => define.1 = value.to_string(),
+                None => all_defines.push((name.to_string(), value.to_string())),
+            }
+        }
+
+        let program = ShaderProgram::load(
+            self.program.vertex_source.clone(),
+            self.program.fragment_source.clone(),
+            all_defines,
+        )?;
+
+        return Ok(Shader { program });
+    }
+
+    pub fn set_int(&self, name: &str, value: i32) {
+        self.program.set_uniform_1i(name, value);
+    }
+
+    pub fn set_float(&self, name: &str, value: f32) {
+        self.program.set_uniform_1f(name, value);
+    }
+
+    pub fn set_vec2(&self, name: &str, value: &math::Vector2<f32>) {
+        self.program.set_uniform_2f(name, value.x, value.y);
+    }
+
+    pub fn set_vec3(&self, name: &str, value: &math::Vector3<f32>) {
+        self.program.set_uniform_3f(name, value.x, value.y, value.z);
+    }
+
+    pub fn set_vec4(&self, name: &str, value: &math::Vector4<f32>) {
+        self.program
+            .set_uniform_4f(name, value.x, value.y, value.z, value.w);
+    }
+
+    pub fn set_matrix4(&self, name: &str, value: &math::Matrix4<f32>) {
+        self.program.set_uniform_matrix_4f(name, value);
+    }
+
+    /// Points a `sampler2D` at a texture from `Renderer2D::load_texture`.
+    pub fn set_texture(&self, name: &str, texture_id: f32) {
+        self.program
+            .set_uniform(name, &UniformValue::Texture(texture_id));
+    }
+
+    pub fn set_uniform(&self, name: &str, value: &UniformValue) {
+        self.program.set_uniform(name, value);
+    }
+}
+
+/// Refers to a material that was added to a renderer with
+/// `Renderer2D::add_material`. The number is where it is in the order they
+/// were added, starting at 0.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct MaterialId(pub usize);
+
+/// A shader along with values for its uniforms. The values are set on the
+/// shader every time something is drawn with the material, so several
+/// materials can share a shader.
+pub struct Material {
+    pub(super) shader: Shader,
+    pub(super) uniforms: HashMap<String, UniformValue>,
+}
+
+impl Material {
+    pub fn new(shader: Shader) -> Material {
+        Material {
+            shader,
+            uniforms: HashMap::new(),
+        }
+    }
+
+    pub fn get_shader(&self) -> &Shader {
+        &self.shader
+    }
+
+    pub fn set_uniform(&mut self, name: &str, value: UniformValue) {
+        self.uniforms.insert(name.to_string(), value);
+    }
+
+    pub fn set_int(&mut self, name: &str, value: i32) {
+        self.set_uniform(name, UniformValue::Int(value));
+    }
+
+    pub fn set_float(&mut self, name: &str, value: f32) {
+        self.set_uniform(name, UniformValue::Float(value));
+    }
+
+    pub fn set_vec2(&mut self, name: &str, value: &math::Vector2<f32>) {
+        self.set_uniform(name, UniformValue::Vec2(value.x, value.y));
+    }
+
+    pub fn set_vec3(&mut self, name: &str, value: &math::Vector3<f32>) {
+        self.set_uniform(name, UniformValue::Vec3(value.x, value.y, value.z));
+    }
+
+    pub fn set_vec4(&mut self, name: &str, value: &math::Vector4<f32>) {
+        self.set_uniform(name, UniformValue::Vec4(value.x, value.y, value.z, value.w));
+    }
+
+    pub fn set_matrix4(&mut self, name: &str, value: &math::Matrix4<f32>) {
+        self.set_uniform(name, UniformValue::Matrix4(*value));
+    }
+
+    pub fn set_texture(&mut self, name: &str, texture_id: f32) {
+        self.set_uniform(name, UniformValue::Texture(texture_id));
+    }
+
+    pub(super) fn apply(&self, projection: &math::Matrix4<f32>) {
+        let program = &self.shader.program;
+
+        program.set_uniform_matrix_4f("projection", projection);
+
+        for (name, value) in &self.uniforms {
+            program.set_uniform(name, value);
+        }
+
+        program.use_program();
+    }
+}
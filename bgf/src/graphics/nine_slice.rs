@@ -0,0 +1,197 @@
+use crate::math::{Rect, Vector2};
+
+/// How the edges or the middle of a nine-slice fill the space they're given.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SliceMode {
+    #[default]
+    Stretch,
+    /// Repeats that part of the texture at its own size, cutting the last
+    /// one short.
+    Tile,
+}
+
+/// A texture cut into a 3 by 3 grid, so it can be drawn at any size
+/// without stretching its corners, like for panels and buttons. The corners
+/// are drawn as they are, the edges fill the space between them, and the
+/// middle fills the rest.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NineSlice {
+    pub texture: f32,
+    /// How far in from each edge of the texture the cuts are, in pixels.
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub edge_mode: SliceMode,
+    pub center_mode: SliceMode,
+}
+
+/// One quad of a nine-slice: where it goes, and which part of the texture
+/// goes there. The UVs go from 0 to 1 across the whole texture.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SlicePiece {
+    pub rect: Rect<f32>,
+    pub uv: Rect<f32>,
+}
+
+impl SlicePiece {
+    /// The part of the piece inside `clip`, with its UVs cut down to match.
+    pub fn clip(&self, clip: &Rect<f32>) -> Option<SlicePiece> {
+        let rect = self.rect.intersection(clip)?;
+
+        let u = |x: f32| self.uv.x + (x - self.rect.x) / self.rect.width * self.uv.width;
+        let v = |y: f32| self.uv.y + (y - self.rect.y) / self.rect.height * self.uv.height;
+
+        let (u1, v1) = (u(rect.x), v(rect.y));
+        let (u2, v2) = (u(rect.x + rect.width), v(rect.y + rect.height));
+
+        return Some(SlicePiece {
+            rect,
+            uv: Rect::new(u1, v1, u2 - u1, v2 - v1),
+        });
+    }
+}
+
+// Where each of the three columns or rows goes, and where it comes from in
+// the texture. If there isn't room for both corners, they get squashed
+// together and the middle disappears.
+fn split(
+    start: f32,
+    length: f32,
+    first: f32,
+    last: f32,
+    texture_length: f32,
+) -> [(f32, f32, f32, f32); 3] {
+    let squash = if first + last > length && first + last > 0.0 {
+        length / (first + last)
+    } else {
+        1.0
+    };
+
+    let (first_length, last_length) = (first * squash, last * squash);
+    let middle_length = (length - first_length - last_length).max(0.0);
+
+    return [
+        (start, first_length, 0.0, first),
+        (
+            start + first_length,
+            middle_length,
+            first,
+            texture_length - first - last,
+        ),
+        (
+            start + first_length + middle_length,
+            last_length,
+            texture_length - last,
+            last,
+        ),
+    ];
+}
+
+// Cuts `length` up into `tile` long pieces, with how much of the tile each
+// one uses.
+fn tile(start: f32, length: f32, tile: f32) -> Vec<(f32, f32, f32)> {
+    if tile <= 0.0 {
+        return vec![(start, length, 1.0)];
+    }
+
+    let mut tiles = Vec::new();
+    let mut offset = 0.0;
+
+    while offset < length {
+        let size = tile.min(length - offset);
+        tiles.push((start + offset, size, size / tile));
+        offset += tile;
+    }
+
+    return tiles;
+}
+
+impl NineSlice {
+    /// The same cut on every side, with everything stretched.
+    pub fn new(texture: f32, border: f32) -> NineSlice {
+        NineSlice {
+            texture,
+            left: border,
+            top: border,
+            right: border,
+            bottom: border,
+            edge_mode: SliceMode::Stretch,
+            center_mode: SliceMode::Stretch,
+        }
+    }
+
+    pub fn with_borders(mut self, left: f32, top: f32, right: f32, bottom: f32) -> NineSlice {
+        self.left = left;
+        self.top = top;
+        self.right = right;
+        self.bottom = bottom;
+        self
+    }
+
+    pub fn with_edge_mode(mut self, edge_mode: SliceMode) -> NineSlice {
+        self.edge_mode = edge_mode;
+        self
+    }
+
+    pub fn with_center_mode(mut self, center_mode: SliceMode) -> NineSlice {
+        self.center_mode = center_mode;
+        self
+    }
+
+    /// The quads that draw the nine-slice over `rect`, for a texture that's
+    /// `texture_size` pixels big. Empty parts are left out.
+    pub fn get_pieces(&self, rect: &Rect<f32>, texture_size: Vector2<f32>) -> Vec<SlicePiece> {
+        let mut pieces = Vec::new();
+
+        if texture_size.x <= 0.0 || texture_size.y <= 0.0 {
+            return pieces;
+        }
+
+        let columns = split(rect.x, rect.width, self.left, self.right, texture_size.x);
+        let rows = split(rect.y, rect.height, self.top, self.bottom, texture_size.y);
+
+        for (row, &(y, height, source_y, source_height)) in rows.iter().enumerate() {
+            for (column, &(x, width, source_x, source_width)) in columns.iter().enumerate() {
+                if width <= 0.0 || height <= 0.0 {
+                    continue;
+                }
+
+                let mode = match (column == 1, row == 1) {
+                    (true, true) => self.center_mode,
+                    (false, false) => SliceMode::Stretch,
+                    _ => self.edge_mode,
+                };
+
+                // Corners never tile, and edges only tile along their length.
+                let tiled = mode == SliceMode::Tile;
+                let tile_width = if tiled && column == 1 {
+                    source_width
+                } else {
+                    0.0
+                };
+                let tile_height = if tiled && row == 1 {
+                    source_height
+                } else {
+                    0.0
+                };
+
+                for (y, height, used_y) in tile(y, height, tile_height) {
+                    for (x, width, used_x) in tile(x, width, tile_width) {
+                        pieces.push(SlicePiece {
+                            rect: Rect::new(x, y, width, height),
+                            uv: Rect::new(
+                                source_x / texture_size.x,
+                                source_y / texture_size.y,
+                                source_width * used_x / texture_size.x,
+                                source_height * used_y / texture_size.y,
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+
+        return pieces;
+    }
+}
@@ -0,0 +1,360 @@
+use super::{
+    InternalTexture, RenderTarget, Renderer2D, ShaderProgram, ShaderSource, TextureFilter,
+    UniformValue, VertexArray,
+};
+use crate::Error;
+use glad_gl::gl;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+const VERTEX_SHADER: &str = "post_process.vs";
+
+// Texture units 0 and 1 always hold the pass's input and the effect's input,
+// so textures that effects add themselves start after those.
+const FIRST_EXTRA_TEXTURE_UNIT: u32 = 2;
+
+struct PostProcessPass {
+    shader: Rc<ShaderProgram>,
+    // Uniforms that belong to this pass only, like the direction of a blur.
+    uniforms: Vec<(String, UniformValue)>,
+}
+
+impl PostProcessPass {
+    fn builtin(fragment_name: &'static str) -> Result<PostProcessPass, Error> {
+        return Ok(PostProcessPass {
+            shader: ShaderProgram::builtin(VERTEX_SHADER, fragment_name)?,
+            uniforms: Vec::new(),
+        });
+    }
+
+    fn from_file(fragment_source_path: &str) -> Result<PostProcessPass, Error> {
+        let shader = ShaderProgram::load(
+            ShaderSource::Builtin(VERTEX_SHADER),
+            ShaderSource::File(fragment_source_path.to_string()),
+            Vec::new(),
+        )?;
+
+        return Ok(PostProcessPass {
+            shader,
+            uniforms: Vec::new(),
+        });
+    }
+
+    fn with_uniform(mut self, name: &str, value: UniformValue) -> PostProcessPass {
+        self.uniforms.push((name.to_string(), value));
+        self
+    }
+}
+
+/// A full-screen effect made out of one or more shader passes.
+///
+/// Every pass gets these uniforms on top of the effect's own parameters:
+/// - `input_texture`: the output of the previous pass
+/// - `effect_input`: what the first pass of this effect got as its input
+/// - `resolution`: the size of the output, in pixels
+/// - `texel_size`: the size of one pixel of the input, in texture coordinates
+/// - `time`: the time passed to `PostProcessStack::update`, in seconds
+///
+/// The passes' vertex shader passes the texture coordinates on as `in vec2 uv`.
+pub struct PostProcessEffect {
+    name: String,
+    passes: Vec<PostProcessPass>,
+    uniforms: HashMap<String, UniformValue>,
+    // Textures from the renderer's slots, by sampler name.
+    textures: Vec<(String, f32)>,
+    enabled: bool,
+}
+
+impl PostProcessEffect {
+    /// Creates an effect with no passes. Add some with `add_pass`.
+    pub fn new(name: &str) -> PostProcessEffect {
+        PostProcessEffect {
+            name: name.to_string(),
+            passes: Vec::new(),
+            uniforms: HashMap::new(),
+            textures: Vec::new(),
+            enabled: true,
+        }
+    }
+
+    /// Creates an effect with a single pass.
+    pub fn from_fragment_file(
+        name: &str,
+        fragment_source_path: &str,
+    ) -> Result<PostProcessEffect, Error> {
+        let mut effect = PostProcessEffect::new(name);
+        effect.add_pass(fragment_source_path)?;
+        return Ok(effect);
+    }
+
+    pub fn add_pass(&mut self, fragment_source_path: &str) -> Result<(), Error> {
+        self.passes
+            .push(PostProcessPass::from_file(fragment_source_path)?);
+        return Ok(());
+    }
+
+    fn builtin(name: &str, fragment_name: &'static str) -> Result<PostProcessEffect, Error> {
+        let mut effect = PostProcessEffect::new(name);
+        effect.passes.push(PostProcessPass::builtin(fragment_name)?);
+        return Ok(effect);
+    }
+
+    /// Old CRT screens. Parameters: `curvature`, `scanline_intensity` and
+    /// `flicker`.
+    pub fn crt() -> Result<PostProcessEffect, Error> {
+        PostProcessEffect::builtin("crt", "post_crt.fs")
+    }
+
+    /// Darkens the edges of the screen. Parameters: `intensity`, `radius` and
+    /// `softness`.
+    pub fn vignette() -> Result<PostProcessEffect, Error> {
+        PostProcessEffect::builtin("vignette", "post_vignette.fs")
+    }
+
+    /// Splits the color channels towards the edges of the screen. Parameters:
+    /// `amount`, in pixels.
+    pub fn chromatic_aberration() -> Result<PostProcessEffect, Error> {
+        PostProcessEffect::builtin("chromatic_aberration", "post_chromatic_aberration.fs")
+    }
+
+    /// Remaps colors through a lookup table texture loaded with
+    /// `Renderer2D::load_texture`. Parameters: `lut_size` and `intensity`.
+    pub fn color_grading(lut_texture_id: f32) -> Result<PostProcessEffect, Error> {
+        let mut effect = PostProcessEffect::builtin("color_grading", "post_color_grading.fs")?;
+        effect.set_texture("lut", lut_texture_id);
+        return Ok(effect);
+    }
+
+    /// A separable gaussian blur. Parameters: `radius`, which spreads the
+    /// samples further apart.
+    pub fn gaussian_blur() -> Result<PostProcessEffect, Error> {
+        let mut effect = PostProcessEffect::new("gaussian_blur");
+        effect.passes = blur_passes()?;
+        return Ok(effect);
+    }
+
+    /// Makes bright areas glow. Parameters: `threshold`, `radius` and
+    /// `intensity`.
+    pub fn bloom() -> Result<PostProcessEffect, Error> {
+        let mut effect = PostProcessEffect::new("bloom");
+
+        effect
+            .passes
+            .push(PostProcessPass::builtin("post_bloom_extract.fs")?);
+        effect.passes.extend(blur_passes()?);
+        effect
+            .passes
+            .push(PostProcessPass::builtin("post_bloom_combine.fs")?);
+
+        return Ok(effect);
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Sets a uniform for every pass of the effect. It keeps its value until
+    /// it's set again, so it can be animated from the game's update.
+    /// `UniformValue::Texture` goes through `set_texture`, because the
+    /// texture units that renderer textures are usually on hold the passes'
+    /// inputs here.
+    pub fn set_uniform(&mut self, name: &str, value: UniformValue) {
+        if let UniformValue::Texture(texture_id) = value {
+            self.uniforms.remove(name);
+            self.set_texture(name, texture_id);
+            return;
+        }
+
+        self.textures.retain(|(n, _)| n != name);
+        self.uniforms.insert(name.to_string(), value);
+    }
+
+    pub fn set_float(&mut self, name: &str, value: f32) {
+        self.set_uniform(name, UniformValue::Float(value));
+    }
+
+    /// Binds one of the renderer's textures to a sampler of the effect.
+    pub fn set_texture(&mut self, name: &str, texture_id: f32) {
+        match self.textures.iter_mut().find(|(n, _)| n == name) {
+            Some(texture) => texture.1 = texture_id,
+            None => self.textures.push((name.to_string(), texture_id)),
+        }
+    }
+}
+
+fn blur_passes() -> Result<Vec<PostProcessPass>, Error> {
+    return Ok(vec![
+        PostProcessPass::builtin("post_blur.fs")?
+            .with_uniform("direction", UniformValue::Vec2(1.0, 0.0)),
+        PostProcessPass::builtin("post_blur.fs")?
+            .with_uniform("direction", UniformValue::Vec2(0.0, 1.0)),
+    ]);
+}
+
+/// Runs a chain of full-screen effects over a scene.
+///
+/// Draw the scene between `begin` and `Renderer2D::end`, then call `apply` to
+/// run every enabled effect in order and put the result on the window.
+pub struct PostProcessStack {
+    // The scene gets drawn into the first one, and the passes ping-pong
+    // between all three.
+    targets: [RenderTarget; 3],
+    effects: Vec<PostProcessEffect>,
+    copy_pass: PostProcessPass,
+    vertex_array: VertexArray,
+    time: f64,
+}
+
+impl PostProcessStack {
+    /// `width` and `height` are the resolution the scene is drawn at. It
+    /// doesn't have to match the window's, which is handy for pixel art.
+    pub fn new(width: u32, height: u32, filter: TextureFilter) -> Result<PostProcessStack, Error> {
+        let new_target = || {
+            let texture = Rc::new(InternalTexture::new_empty(width, height, filter));
+            RenderTarget::new(texture, -1.0, width, height, false)
+        };
+
+        return Ok(PostProcessStack {
+            targets: [new_target()?, new_target()?, new_target()?],
+            effects: Vec::new(),
+            copy_pass: PostProcessPass::builtin("post_copy.fs")?,
+            vertex_array: VertexArray::new(),
+            time: 0.0,
+        });
+    }
+
+    pub fn push(&mut self, effect: PostProcessEffect) {
+        self.effects.push(effect);
+    }
+
+    pub fn effect(&self, name: &str) -> Option<&PostProcessEffect> {
+        self.effects.iter().find(|effect| effect.name == name)
+    }
+
+    pub fn effect_mut(&mut self, name: &str) -> Option<&mut PostProcessEffect> {
+        self.effects.iter_mut().find(|effect| effect.name == name)
+    }
+
+    /// Advances the `time` uniform.
+    pub fn update(&mut self, delta_time: f64) {
+        self.time += delta_time;
+    }
+
+    /// Starts drawing the scene that the effects will be applied to.
+    pub fn begin(&self, renderer: &mut Renderer2D) {
+        renderer.begin_target(&self.targets[0]);
+    }
+
+    pub fn apply(&self, renderer: &Renderer2D) {
+        let mut passes: Vec<(Option<&PostProcessEffect>, &PostProcessPass, bool)> = Vec::new();
+
+        for effect in self.effects.iter().filter(|effect| effect.enabled) {
+            for (i, pass) in effect.passes.iter().enumerate() {
+                passes.push((Some(effect), pass, i == 0));
+            }
+        }
+
+        if passes.is_empty() {
+            passes.push((None, &self.copy_pass, true));
+        }
+
+        let mut input = 0;
+        let mut effect_input = 0;
+
+        unsafe {
+            gl::Disable(gl::BLEND);
+        }
+
+        self.vertex_array.bind();
+
+        for (i, (effect, pass, starts_effect)) in passes.iter().enumerate() {
+            if *starts_effect {
+                effect_input = input;
+            }
+
+            let is_last = i == passes.len() - 1;
+            let output = (0..self.targets.len())
+                .find(|target| *target != input && *target != effect_input)
+                .unwrap();
+
+            let (input_width, input_height) = self.targets[input].get_size();
+            let (framebuffer, width, height) = if is_last {
+                (
+                    0,
+                    renderer.window_width as u32,
+                    renderer.window_height as u32,
+                )
+            } else {
+                let (width, height) = self.targets[output].get_size();
+                (self.targets[output].framebuffer(), width, height)
+            };
+
+            unsafe {
+                gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+                gl::Viewport(0, 0, width as i32, height as i32);
+            }
+
+            let shader = &pass.shader;
+            shader.use_program();
+
+            // The targets are drawn upside down, so only the window needs
+            // flipping.
+            shader.set_uniform_1i("flip_y", is_last as i32);
+            shader.set_uniform_1i("input_texture", 0);
+            shader.set_uniform_1i("effect_input", 1);
+            shader.set_uniform_2f("resolution", width as f32, height as f32);
+            shader.set_uniform_2f(
+                "texel_size",
+                1.0 / input_width as f32,
+                1.0 / input_height as f32,
+            );
+            shader.set_uniform_1f("time", self.time as f32);
+
+            InternalTexture::set_active_texture(0);
+            self.targets[input].color_texture().bind();
+            InternalTexture::set_active_texture(1);
+            self.targets[effect_input].color_texture().bind();
+
+            if let Some(effect) = effect {
+                for (name, value) in &effect.uniforms {
+                    shader.set_uniform(name, value);
+                }
+
+                for (unit, (name, texture_id)) in effect.textures.iter().enumerate() {
+                    let unit = FIRST_EXTRA_TEXTURE_UNIT + unit as u32;
+
+                    if let Some(Some(texture)) = renderer.textures.get(*texture_id as usize) {
+                        InternalTexture::set_active_texture(unit);
+                        texture.bind();
+                        shader.set_uniform_1i(name, unit as i32);
+                    }
+                }
+            }
+
+            for (name, value) in &pass.uniforms {
+                shader.set_uniform(name, value);
+            }
+
+            unsafe {
+                gl::DrawArrays(gl::TRIANGLES, 0, 3);
+            }
+
+            input = output;
+        }
+
+        unsafe {
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+            gl::Enable(gl::BLEND);
+        }
+
+        InternalTexture::set_active_texture(0);
+    }
+}
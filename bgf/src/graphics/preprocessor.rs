@@ -0,0 +1,344 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+
+// What the injected defines are called in error messages.
+const DEFINES_NAME: &str = "<defines>";
+
+/// Runs over shader sources before they're compiled. It handles:
+/// - `#include "file"`, which is looked up in the virtual files first, then
+///   relative to the file that includes it, in the virtual files and then on
+///   disk
+/// - `#pragma once`, for files that get included from several places
+/// - defines, which are put right after the `#version` line
+///
+/// Everything else is left for the driver's preprocessor.
+#[derive(Default)]
+pub struct Preprocessor {
+    virtual_files: HashMap<String, String>,
+    defines: Vec<(String, String)>,
+}
+
+/// An error along with the file and line it's on.
+#[derive(Clone, PartialEq, Debug)]
+pub struct PreprocessError {
+    pub file: String,
+    pub line: u32,
+    pub message: String,
+}
+
+impl std::fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}: {}", self.file, self.line, self.message)
+    }
+}
+
+impl std::error::Error for PreprocessError {}
+
+/// The output of the preprocessor, with a map from its lines back to the
+/// files they came from.
+pub struct ProcessedSource {
+    source: String,
+    files: Vec<String>,
+    // The index into `files` and the line number of every line of `source`.
+    lines: Vec<(usize, u32)>,
+    dependencies: Vec<PathBuf>,
+}
+
+impl Preprocessor {
+    pub fn new() -> Preprocessor {
+        Preprocessor::default()
+    }
+
+    /// Makes `#include "name"` use `source` without going to the disk.
+    pub fn add_virtual_file(&mut self, name: &str, source: &str) {
+        self.virtual_files
+            .insert(normalize(Path::new(name)), source.to_string());
+    }
+
+    /// Adds `#define name value`. Defining the same name again replaces its
+    /// value.
+    pub fn define(&mut self, name: &str, value: &str) {
+        match self.defines.iter_mut().find(|(n, _)| n == name) {
+            Some(define) => define.1 = value.to_string(),
+            None => self.defines.push((name.to_string(), value.to_string())),
+        }
+    }
+
+    /// `source_name` is the path of the source, which includes are relative
+    /// to, or any name to report errors with.
+    pub fn process(
+        &self,
+        source_name: &str,
+        source: &str,
+    ) -> Result<ProcessedSource, PreprocessError> {
+        let mut output = ProcessedSource {
+            source: String::new(),
+            files: Vec::new(),
+            lines: Vec::new(),
+            dependencies: Vec::new(),
+        };
+
+        let mut state = State {
+            stack: Vec::new(),
+            included_once: HashSet::new(),
+        };
+
+        self.process_file(source_name, source, true, &mut output, &mut state)?;
+
+        return Ok(output);
+    }
+
+    fn process_file(
+        &self,
+        name: &str,
+        source: &str,
+        is_root: bool,
+        output: &mut ProcessedSource,
+        state: &mut State,
+    ) -> Result<(), PreprocessError> {
+        let file = output.add_file(name);
+        state.stack.push(name.to_string());
+
+        // Nothing but comments can come before `#version`, so the defines go
+        // right after it, or at the very top if there isn't one.
+        let version_line = if is_root {
+            let version_line = source
+                .lines()
+                .position(|line| matches!(directive(line), Some(("version", _))));
+
+            if version_line.is_none() {
+                self.inject_defines(output);
+            }
+
+            version_line
+        } else {
+            None
+        };
+
+        for (i, line) in source.lines().enumerate() {
+            let line_number = i as u32 + 1;
+            let error = |message: String| PreprocessError {
+                file: name.to_string(),
+                line: line_number,
+                message,
+            };
+
+            match directive(line) {
+                Some(("include", rest)) => {
+                    let include_name = parse_include_name(rest).ok_or_else(|| {
+                        error("expected a file name in quotes after #include".to_string())
+                    })?;
+                    let (resolved_name, contents, path) =
+                        self.resolve(name, include_name).map_err(error)?;
+
+                    if state.stack.contains(&resolved_name) {
+                        return Err(error(format!("{} includes itself", resolved_name)));
+                    }
+
+                    if let Some(path) = path {
+                        if !output.dependencies.contains(&path) {
+                            output.dependencies.push(path);
+                        }
+                    }
+
+                    if !state.included_once.contains(&resolved_name) {
+                        self.process_file(&resolved_name, &contents, false, output, state)?;
+                    }
+                }
+                Some(("pragma", "once")) => {
+                    state.included_once.insert(name.to_string());
+                }
+                _ => output.push_line(line, file, line_number),
+            }
+
+            if version_line == Some(i) {
+                self.inject_defines(output);
+            }
+        }
+
+        state.stack.pop();
+
+        return Ok(());
+    }
+
+    fn inject_defines(&self, output: &mut ProcessedSource) {
+        let file = output.add_file(DEFINES_NAME);
+
+        for (i, (name, value)) in self.defines.iter().enumerate() {
+            output.push_line(&format!("#define {} {}", name, value), file, i as u32 + 1);
+        }
+    }
+
+    /// Finds an included file, and returns its name, its contents and its
+    /// path on disk if it's not a virtual file.
+    fn resolve(
+        &self,
+        including_name: &str,
+        include_name: &str,
+    ) -> Result<(String, String, Option<PathBuf>), String> {
+        let name = normalize(Path::new(include_name));
+
+        if let Some(source) = self.virtual_files.get(&name) {
+            return Ok((name, source.clone(), None));
+        }
+
+        let directory = Path::new(including_name).parent().unwrap_or(Path::new(""));
+        let path = directory.join(include_name);
+        let name = normalize(&path);
+
+        if let Some(source) = self.virtual_files.get(&name) {
+            return Ok((name, source.clone(), None));
+        }
+
+        match std::fs::read_to_string(&path) {
+            Ok(source) => Ok((name, source, Some(path))),
+            Err(error) => Err(format!("couldn't include {}: {}", include_name, error)),
+        }
+    }
+}
+
+struct State {
+    // The files that are being processed, to catch files that include
+    // themselves.
+    stack: Vec<String>,
+    included_once: HashSet<String>,
+}
+
+impl ProcessedSource {
+    pub fn get_source(&self) -> &str {
+        &self.source
+    }
+
+    /// The files on disk that were included, for watching them.
+    pub fn get_dependencies(&self) -> &[PathBuf] {
+        &self.dependencies
+    }
+
+    /// Maps a line of the output, counting from 1 like compilers do, to the
+    /// file and line it came from.
+    pub fn original_line(&self, line: u32) -> Option<(&str, u32)> {
+        let (file, line) = self.lines.get((line as usize).checked_sub(1)?)?;
+        return Some((&self.files[*file], *line));
+    }
+
+    /// Replaces the line numbers in a compiler log with the files and lines
+    /// they came from. This understands the `0(12)` and `0:12` forms that
+    /// drivers use.
+    pub fn remap_log(&self, log: &str) -> String {
+        log.lines()
+            .map(|line| self.remap_log_line(line))
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn remap_log_line(&self, line: &str) -> String {
+        let bytes = line.as_bytes();
+
+        for start in 0..bytes.len() {
+            // There's only ever one source string, so its number is always 0.
+            if bytes[start] != b'0' || (start > 0 && bytes[start - 1].is_ascii_alphanumeric()) {
+                continue;
+            }
+
+            let separator = match bytes.get(start + 1) {
+                Some(b'(') => b'(',
+                Some(b':') => b':',
+                _ => continue,
+            };
+
+            let digits_start = start + 2;
+            let digits_end = bytes[digits_start..]
+                .iter()
+                .position(|byte| !byte.is_ascii_digit())
+                .map_or(bytes.len(), |end| digits_start + end);
+
+            if digits_end == digits_start {
+                continue;
+            }
+
+            if separator == b'(' && bytes.get(digits_end) != Some(&b')') {
+                continue;
+            }
+
+            let original = line[digits_start..digits_end]
+                .parse()
+                .ok()
+                .and_then(|number| self.original_line(number));
+
+            if let Some((file, original_line)) = original {
+                let (replacement, rest) = if separator == b'(' {
+                    (format!("{}({})", file, original_line), digits_end + 1)
+                } else {
+                    (format!("{}:{}", file, original_line), digits_end)
+                };
+
+                return format!("{}{}{}", &line[..start], replacement, &line[rest..]);
+            }
+        }
+
+        return line.to_string();
+    }
+
+    fn add_file(&mut self, name: &str) -> usize {
+        match self.files.iter().position(|file| file == name) {
+            Some(index) => index,
+            None => {
+                self.files.push(name.to_string());
+                self.files.len() - 1
+            }
+        }
+    }
+
+    fn push_line(&mut self, line: &str, file: usize, line_number: u32) {
+        self.source.push_str(line);
+        self.source.push('\n');
+        self.lines.push((file, line_number));
+    }
+}
+
+/// Splits a line like `  #  include "noise.glsl"` into `include` and the rest.
+fn directive(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim().strip_prefix('#')?.trim_start();
+    let end = line
+        .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+        .unwrap_or(line.len());
+
+    return Some((&line[..end], line[end..].trim()));
+}
+
+fn parse_include_name(rest: &str) -> Option<&str> {
+    let name = rest.strip_prefix('"')?;
+    let end = name.find('"')?;
+
+    if !name[end + 1..].trim().is_empty() {
+        return None;
+    }
+
+    return Some(&name[..end]);
+}
+
+/// Takes out the `.` and `..` in a path without going to the disk, so that the
+/// same file always has the same name.
+fn normalize(path: &Path) -> String {
+    let mut parts: Vec<String> = Vec::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match parts.last().map(String::as_str) {
+                Some(part) if part != ".." => {
+                    parts.pop();
+                }
+                _ => parts.push("..".to_string()),
+            },
+            Component::Normal(part) => parts.push(part.to_string_lossy().into_owned()),
+            // This makes the join below start the path with a slash.
+            Component::RootDir => parts.push(String::new()),
+            Component::Prefix(prefix) => {
+                parts.push(prefix.as_os_str().to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    return parts.join("/");
+}
@@ -0,0 +1,124 @@
+use super::InternalTexture;
+use crate::Error;
+use glad_gl::gl;
+use std::rc::Rc;
+
+/// An offscreen framebuffer with a color texture, and optionally a combined
+/// depth and stencil buffer. Create one with
+/// `Renderer2D::create_render_target`, draw into it with
+/// `Renderer2D::begin_target`, and draw its texture with `texture_id`.
+pub struct RenderTarget {
+    framebuffer: u32,
+    depth_stencil: Option<u32>,
+    // Keeps the texture alive for as long as the framebuffer uses it, even if
+    // the renderer lets go of it first.
+    color: Rc<InternalTexture>,
+    pub(super) texture_id: f32,
+    width: u32,
+    height: u32,
+}
+
+impl RenderTarget {
+    pub(super) fn new(
+        color: Rc<InternalTexture>,
+        texture_id: f32,
+        width: u32,
+        height: u32,
+        with_depth_stencil: bool,
+    ) -> Result<RenderTarget, Error> {
+        let mut framebuffer = 0;
+        let mut depth_stencil = None;
+        let status;
+
+        unsafe {
+            gl::GenFramebuffers(1, &mut framebuffer);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, framebuffer);
+
+            gl::FramebufferTexture2D(
+                gl::FRAMEBUFFER,
+                gl::COLOR_ATTACHMENT0,
+                gl::TEXTURE_2D,
+                color.handle,
+                0,
+            );
+
+            if with_depth_stencil {
+                let mut renderbuffer = 0;
+                gl::GenRenderbuffers(1, &mut renderbuffer);
+                gl::BindRenderbuffer(gl::RENDERBUFFER, renderbuffer);
+                gl::RenderbufferStorage(
+                    gl::RENDERBUFFER,
+                    gl::DEPTH24_STENCIL8,
+                    width.try_into().unwrap(),
+                    height.try_into().unwrap(),
+                );
+                gl::BindRenderbuffer(gl::RENDERBUFFER, 0);
+
+                gl::FramebufferRenderbuffer(
+                    gl::FRAMEBUFFER,
+                    gl::DEPTH_STENCIL_ATTACHMENT,
+                    gl::RENDERBUFFER,
+                    renderbuffer,
+                );
+
+                depth_stencil = Some(renderbuffer);
+            }
+
+            status = gl::CheckFramebufferStatus(gl::FRAMEBUFFER);
+            gl::BindFramebuffer(gl::FRAMEBUFFER, 0);
+        }
+
+        // This deletes the framebuffer if it's returned as an error.
+        let target = RenderTarget {
+            framebuffer,
+            depth_stencil,
+            color,
+            texture_id,
+            width,
+            height,
+        };
+
+        if status != gl::FRAMEBUFFER_COMPLETE {
+            return Err(Error::GlContext(format!(
+                "a {}x{} render target is incomplete (status {:#x})",
+                width, height, status
+            )));
+        }
+
+        return Ok(target);
+    }
+
+    pub(super) fn framebuffer(&self) -> u32 {
+        self.framebuffer
+    }
+
+    pub(super) fn color_texture(&self) -> &InternalTexture {
+        &self.color
+    }
+
+    /// The id to draw the target's contents with, like the ones returned by
+    /// `Renderer2D::load_texture`.
+    pub fn texture_id(&self) -> f32 {
+        self.texture_id
+    }
+
+    pub fn get_size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn has_depth_stencil(&self) -> bool {
+        self.depth_stencil.is_some()
+    }
+}
+
+impl Drop for RenderTarget {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteFramebuffers(1, &self.framebuffer);
+
+            if let Some(renderbuffer) = &self.depth_stencil {
+                gl::DeleteRenderbuffers(1, renderbuffer);
+            }
+        }
+    }
+}
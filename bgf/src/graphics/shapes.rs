@@ -0,0 +1,452 @@
+use crate::math::Vector2;
+use std::f32::consts::PI;
+
+// The segment count used for round caps and round joins. They are usually
+// small enough on screen that this doesn't need to be configurable.
+const ROUND_SEGMENTS: u32 = 8;
+
+// Beyond this ratio between the miter length and the half thickness, a miter
+// join falls back to a bevel join so that sharp corners don't spike out.
+const MITER_LIMIT: f32 = 4.0;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LineCap {
+    Butt,
+    Square,
+    Round,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LineJoin {
+    Miter,
+    Bevel,
+    Round,
+}
+
+/// Positions and indices of a shape's triangles, with indices starting at
+/// zero. The renderer offsets them when the shape gets appended to the batch.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Geometry {
+    pub positions: Vec<Vector2<f32>>,
+    pub indices: Vec<u32>,
+}
+
+impl Geometry {
+    fn new() -> Geometry {
+        Geometry {
+            positions: Vec::new(),
+            indices: Vec::new(),
+        }
+    }
+
+    fn push_vertex(&mut self, position: Vector2<f32>) -> u32 {
+        self.positions.push(position);
+        return (self.positions.len() - 1) as u32;
+    }
+
+    fn push_triangle(&mut self, a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>) {
+        let a = self.push_vertex(a);
+        let b = self.push_vertex(b);
+        let c = self.push_vertex(c);
+        self.indices.extend_from_slice(&[a, b, c]);
+    }
+
+    fn push_quad(&mut self, a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>, d: Vector2<f32>) {
+        let a = self.push_vertex(a);
+        let b = self.push_vertex(b);
+        let c = self.push_vertex(c);
+        let d = self.push_vertex(d);
+        self.indices.extend_from_slice(&[a, b, c, a, c, d]);
+    }
+
+    /// A triangle fan around `center`, going from `start_angle` to `end_angle`.
+    fn push_fan(
+        &mut self,
+        center: Vector2<f32>,
+        radii: Vector2<f32>,
+        start_angle: f32,
+        end_angle: f32,
+        segments: u32,
+    ) {
+        let segments = segments.max(1);
+        let center_index = self.push_vertex(center);
+        let step = (end_angle - start_angle) / segments as f32;
+
+        for i in 0..=segments {
+            let angle = start_angle + step * i as f32;
+            let index = self.push_vertex(Vector2::new(
+                center.x + angle.cos() * radii.x,
+                center.y + angle.sin() * radii.y,
+            ));
+
+            if i > 0 {
+                self.indices
+                    .extend_from_slice(&[center_index, index - 1, index]);
+            }
+        }
+    }
+
+    /// Connects two rings of points of the same length with quads.
+    fn push_strip(&mut self, outer: &[Vector2<f32>], inner: &[Vector2<f32>], closed: bool) {
+        let first = self.positions.len() as u32;
+
+        for (outer, inner) in outer.iter().zip(inner.iter()) {
+            self.push_vertex(*outer);
+            self.push_vertex(*inner);
+        }
+
+        let count = outer.len().min(inner.len()) as u32;
+        let pairs = if closed {
+            count
+        } else {
+            count.saturating_sub(1)
+        };
+
+        for i in 0..pairs {
+            let a = first + i * 2;
+            let b = first + ((i + 1) % count) * 2;
+            self.indices
+                .extend_from_slice(&[a, a + 1, b + 1, a, b + 1, b]);
+        }
+    }
+
+    fn push_round_cap(
+        &mut self,
+        center: Vector2<f32>,
+        direction: Vector2<f32>,
+        half_thickness: f32,
+    ) {
+        let angle = direction.y.atan2(direction.x);
+        self.push_fan(
+            center,
+            Vector2::from_scalar(half_thickness),
+            angle - PI / 2.0,
+            angle + PI / 2.0,
+            ROUND_SEGMENTS,
+        );
+    }
+
+    /// Fills the gap on the outer side of the corner between two segments
+    /// meeting at `corner`.
+    fn push_join(
+        &mut self,
+        corner: Vector2<f32>,
+        direction_in: Vector2<f32>,
+        direction_out: Vector2<f32>,
+        half_thickness: f32,
+        join: LineJoin,
+    ) {
+        let cross = direction_in.cross(direction_out);
+
+        // Straight (or fully folded back) corners don't leave a gap.
+        if cross.abs() < f32::EPSILON {
+            return;
+        }
+
+        // The outer side is the opposite of the side we're turning towards.
+        let side = if cross > 0.0 { -1.0 } else { 1.0 };
+        let normal_in = direction_in.perpendicular() * side;
+        let normal_out = direction_out.perpendicular() * side;
+
+        let outer_in = corner + normal_in * half_thickness;
+        let outer_out = corner + normal_out * half_thickness;
+
+        match join {
+            LineJoin::Bevel => self.push_triangle(corner, outer_in, outer_out),
+            LineJoin::Miter => {
+                let miter = (normal_in + normal_out).normalize();
+                let cos_half_angle = miter.dot(normal_in);
+
+                if cos_half_angle <= 0.0 || 1.0 / cos_half_angle > MITER_LIMIT {
+                    self.push_triangle(corner, outer_in, outer_out);
+                    return;
+                }
+
+                let tip = corner + miter * (half_thickness / cos_half_angle);
+                self.push_triangle(corner, outer_in, tip);
+                self.push_triangle(corner, tip, outer_out);
+            }
+            LineJoin::Round => {
+                let start_angle = normal_in.y.atan2(normal_in.x);
+                let mut end_angle = normal_out.y.atan2(normal_out.x);
+
+                // Always go around the short way.
+                while end_angle - start_angle > PI {
+                    end_angle -= 2.0 * PI;
+                }
+                while start_angle - end_angle > PI {
+                    end_angle += 2.0 * PI;
+                }
+
+                self.push_fan(
+                    corner,
+                    Vector2::from_scalar(half_thickness),
+                    start_angle,
+                    end_angle,
+                    ROUND_SEGMENTS,
+                );
+            }
+        }
+    }
+}
+
+fn ellipse_points(
+    center: Vector2<f32>,
+    radii: Vector2<f32>,
+    start_angle: f32,
+    end_angle: f32,
+    segments: u32,
+    closed: bool,
+) -> Vec<Vector2<f32>> {
+    let segments = segments.max(1);
+    let step = (end_angle - start_angle) / segments as f32;
+    let count = if closed { segments } else { segments + 1 };
+
+    return (0..count)
+        .map(|i| {
+            let angle = start_angle + step * i as f32;
+            Vector2::new(
+                center.x + angle.cos() * radii.x,
+                center.y + angle.sin() * radii.y,
+            )
+        })
+        .collect();
+}
+
+fn rounded_rect_points(
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    radius: f32,
+    segments: u32,
+) -> Vec<Vector2<f32>> {
+    let radius = radius.max(0.0).min(width / 2.0).min(height / 2.0);
+    let segments = segments.max(1);
+    let mut points = Vec::with_capacity(((segments + 1) * 4) as usize);
+
+    // Corners in clockwise order on screen, starting at the top left.
+    let corners = [
+        (Vector2::new(x + radius, y + radius), PI),
+        (Vector2::new(x + width - radius, y + radius), PI * 1.5),
+        (Vector2::new(x + width - radius, y + height - radius), 0.0),
+        (Vector2::new(x + radius, y + height - radius), PI * 0.5),
+    ];
+
+    for (center, start_angle) in corners {
+        for i in 0..=segments {
+            let angle = start_angle + (PI / 2.0) * (i as f32 / segments as f32);
+            points.push(Vector2::new(
+                center.x + angle.cos() * radius,
+                center.y + angle.sin() * radius,
+            ));
+        }
+    }
+
+    return points;
+}
+
+pub fn triangle(a: Vector2<f32>, b: Vector2<f32>, c: Vector2<f32>) -> Geometry {
+    let mut geometry = Geometry::new();
+    geometry.push_triangle(a, b, c);
+    return geometry;
+}
+
+/// Fills a convex polygon with a triangle fan from the first point.
+pub fn convex_polygon(points: &[Vector2<f32>]) -> Geometry {
+    let mut geometry = Geometry::new();
+
+    if points.len() < 3 {
+        return geometry;
+    }
+
+    geometry.positions.extend_from_slice(points);
+
+    for i in 1..(points.len() as u32 - 1) {
+        geometry.indices.extend_from_slice(&[0, i, i + 1]);
+    }
+
+    return geometry;
+}
+
+pub fn ellipse(center: Vector2<f32>, radii: Vector2<f32>, segments: u32) -> Geometry {
+    let mut geometry = Geometry::new();
+    geometry.push_fan(center, radii, 0.0, 2.0 * PI, segments);
+    return geometry;
+}
+
+/// A ring along the inside of the ellipse's edge.
+pub fn ellipse_outline(
+    center: Vector2<f32>,
+    radii: Vector2<f32>,
+    thickness: f32,
+    segments: u32,
+) -> Geometry {
+    let inner_radii = Vector2::new(
+        (radii.x - thickness).max(0.0),
+        (radii.y - thickness).max(0.0),
+    );
+
+    let outer = ellipse_points(center, radii, 0.0, 2.0 * PI, segments, true);
+    let inner = ellipse_points(center, inner_radii, 0.0, 2.0 * PI, segments, true);
+
+    let mut geometry = Geometry::new();
+    geometry.push_strip(&outer, &inner, true);
+    return geometry;
+}
+
+/// A thick arc centered on the circle with the given radius.
+pub fn arc(
+    center: Vector2<f32>,
+    radius: f32,
+    start_angle: f32,
+    end_angle: f32,
+    thickness: f32,
+    segments: u32,
+) -> Geometry {
+    let half_thickness = thickness / 2.0;
+    let outer_radius = radius + half_thickness;
+    let inner_radius = (radius - half_thickness).max(0.0);
+
+    let outer = ellipse_points(
+        center,
+        Vector2::from_scalar(outer_radius),
+        start_angle,
+        end_angle,
+        segments,
+        false,
+    );
+    let inner = ellipse_points(
+        center,
+        Vector2::from_scalar(inner_radius),
+        start_angle,
+        end_angle,
+        segments,
+        false,
+    );
+
+    let mut geometry = Geometry::new();
+    geometry.push_strip(&outer, &inner, false);
+    return geometry;
+}
+
+pub fn rounded_rect(
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    radius: f32,
+    segments: u32,
+) -> Geometry {
+    return convex_polygon(&rounded_rect_points(x, y, width, height, radius, segments));
+}
+
+/// A ring along the inside of the rounded rectangle's edge.
+pub fn rounded_rect_outline(
+    x: f32,
+    y: f32,
+    width: f32,
+    height: f32,
+    radius: f32,
+    thickness: f32,
+    segments: u32,
+) -> Geometry {
+    let radius = radius.max(0.0).min(width / 2.0).min(height / 2.0);
+    let thickness = thickness.min(width / 2.0).min(height / 2.0);
+
+    let outer = rounded_rect_points(x, y, width, height, radius, segments);
+    let inner = rounded_rect_points(
+        x + thickness,
+        y + thickness,
+        width - thickness * 2.0,
+        height - thickness * 2.0,
+        (radius - thickness).max(0.0),
+        segments,
+    );
+
+    let mut geometry = Geometry::new();
+    geometry.push_strip(&outer, &inner, true);
+    return geometry;
+}
+
+/// Strokes a line through all the points, centered on them. Closed
+/// polylines get a join at the first point instead of caps.
+pub fn polyline(
+    points: &[Vector2<f32>],
+    thickness: f32,
+    cap: LineCap,
+    join: LineJoin,
+    closed: bool,
+) -> Geometry {
+    let mut geometry = Geometry::new();
+
+    // Consecutive duplicate points have no direction, so they're skipped.
+    let mut points: Vec<Vector2<f32>> = points.iter().copied().fold(Vec::new(), |mut acc, p| {
+        if acc.last() != Some(&p) {
+            acc.push(p);
+        }
+        acc
+    });
+
+    if closed && points.len() > 2 && points.first() == points.last() {
+        points.pop();
+    }
+
+    if points.len() < 2 {
+        return geometry;
+    }
+
+    let half_thickness = thickness / 2.0;
+    let segment_count = if closed {
+        points.len()
+    } else {
+        points.len() - 1
+    };
+
+    let directions: Vec<Vector2<f32>> = (0..segment_count)
+        .map(|i| (points[(i + 1) % points.len()] - points[i]).normalize())
+        .collect();
+
+    for i in 0..segment_count {
+        let direction = directions[i];
+        let mut start = points[i];
+        let mut end = points[(i + 1) % points.len()];
+
+        if !closed && cap == LineCap::Square {
+            if i == 0 {
+                start -= direction * half_thickness;
+            }
+            if i == segment_count - 1 {
+                end += direction * half_thickness;
+            }
+        }
+
+        let offset = direction.perpendicular() * half_thickness;
+        geometry.push_quad(start + offset, end + offset, end - offset, start - offset);
+    }
+
+    let join_count = if closed {
+        segment_count
+    } else {
+        segment_count - 1
+    };
+
+    for i in 0..join_count {
+        let direction_in = directions[i];
+        let direction_out = directions[(i + 1) % segment_count];
+        let corner = points[(i + 1) % points.len()];
+
+        geometry.push_join(corner, direction_in, direction_out, half_thickness, join);
+    }
+
+    if !closed && cap == LineCap::Round {
+        geometry.push_round_cap(points[0], -directions[0], half_thickness);
+        geometry.push_round_cap(
+            points[points.len() - 1],
+            directions[segment_count - 1],
+            half_thickness,
+        );
+    }
+
+    return geometry;
+}
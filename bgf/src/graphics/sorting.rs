@@ -0,0 +1,95 @@
+use super::MaterialId;
+
+// How far back an item may look for an earlier draw with the same material
+// to join. This keeps the sort close to linear when there are lots of
+// materials.
+const MAX_GROUPING_DISTANCE: usize = 64;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SortMode {
+    /// Everything is drawn in the order it was submitted, ignoring layers.
+    Submission,
+    /// Lower layers are drawn first. Draws on the same layer keep their
+    /// submission order, unless they don't overlap.
+    Layer,
+    /// Like `Layer`, but draws on the same layer are also sorted by the
+    /// bottom edge of their bounds, for top-down games.
+    YSort,
+}
+
+/// One draw call's worth of indices in the renderer's batch, along with what
+/// it needs to be sorted.
+#[derive(Clone, Debug)]
+pub struct DrawItem {
+    pub layer: i32,
+    pub material: Option<MaterialId>,
+    pub min_x: f32,
+    pub min_y: f32,
+    pub max_x: f32,
+    pub max_y: f32,
+    pub first_index: usize,
+    pub index_count: usize,
+}
+
+impl DrawItem {
+    fn overlaps(&self, other: &DrawItem) -> bool {
+        self.min_x < other.max_x
+            && other.min_x < self.max_x
+            && self.min_y < other.max_y
+            && other.min_y < self.max_y
+    }
+}
+
+/// Sorts the items into the order they should be drawn in. The sort is stable,
+/// and within a layer, items are only moved next to an earlier item with the
+/// same material when that doesn't make them jump over anything they overlap,
+/// so the result always looks the same as drawing in submission order.
+///
+/// Grouping by material is what saves work: every run of items with the same
+/// material is one draw call and one shader switch at `end`. Textures don't
+/// matter, since all of them are bound for the whole batch.
+pub fn sort_draw_items(items: &mut Vec<DrawItem>, mode: SortMode) {
+    match mode {
+        SortMode::Submission => return,
+        SortMode::Layer => items.sort_by_key(|item| item.layer),
+        SortMode::YSort => items.sort_by(|a, b| {
+            a.layer
+                .cmp(&b.layer)
+                .then_with(|| a.max_y.total_cmp(&b.max_y))
+        }),
+    }
+
+    let same_group = |a: &DrawItem, b: &DrawItem| match mode {
+        SortMode::YSort => a.layer == b.layer && a.max_y == b.max_y,
+        _ => a.layer == b.layer,
+    };
+
+    let mut sorted: Vec<DrawItem> = Vec::with_capacity(items.len());
+    let mut group_start = 0;
+
+    for item in items.drain(..) {
+        if let Some(last) = sorted.last() {
+            if !same_group(last, &item) {
+                group_start = sorted.len();
+            }
+        }
+
+        let mut insert_at = sorted.len();
+        let lowest = group_start.max(sorted.len().saturating_sub(MAX_GROUPING_DISTANCE));
+
+        for i in (lowest..sorted.len()).rev() {
+            if sorted[i].material == item.material {
+                insert_at = i + 1;
+                break;
+            }
+
+            if sorted[i].overlaps(&item) {
+                break;
+            }
+        }
+
+        sorted.insert(insert_at, item);
+    }
+
+    *items = sorted;
+}
@@ -1,37 +1,59 @@
+pub mod audio;
+mod error;
+pub mod gl_debug;
 pub mod graphics;
 pub mod math;
+pub mod random;
+pub mod tween;
 pub mod ui;
 
+pub use error::Error;
+
 use glad_gl::gl;
 use glfw::Context;
 use graphics::Renderer2D;
+use std::cell::RefCell;
 use std::sync::mpsc::Receiver;
 
+thread_local! {
+    // GLFW reports errors through a callback, so the last one is kept here
+    // for when a function that failed only returns `None`.
+    static LAST_GLFW_ERROR: RefCell<Option<(glfw::Error, String)>> = const { RefCell::new(None) };
+}
+
+fn record_glfw_error(error: glfw::Error, description: String, _: &()) {
+    LAST_GLFW_ERROR.with(|last| *last.borrow_mut() = Some((error, description)));
+}
+
+fn take_glfw_error() -> Error {
+    match LAST_GLFW_ERROR.with(|last| last.borrow_mut().take()) {
+        // These mean that there's no OpenGL 4.3 core context to be had.
+        Some((glfw::Error::VersionUnavailable, description))
+        | Some((glfw::Error::ApiUnavailable, description)) => Error::GlContext(description),
+        Some((_, description)) => Error::Window(description),
+        None => Error::Window("failed to create the window".to_string()),
+    }
+}
+
 pub struct Window {
     glfw: glfw::Glfw,
     window: glfw::Window,
-    _events: Receiver<(f64, glfw::WindowEvent)>,
-}
-
-extern "system" fn opengl_debug_callback(
-    _source: u32,
-    _type: u32,
-    _id: u32,
-    _severity: u32,
-    _length: i32,
-    message: *const i8,
-    _: *mut std::ffi::c_void,
-) {
-    let message = unsafe { std::ffi::CStr::from_ptr(message) }
-        .to_bytes()
-        .to_vec();
-
-    println!("[OPENGL]: {}", String::from_utf8(message).unwrap());
+    events: Receiver<(f64, glfw::WindowEvent)>,
+    // What was typed, pressed and scrolled between the last two updates.
+    typed_text: String,
+    key_presses: Vec<(glfw::Key, glfw::Modifiers)>,
+    scroll: f32,
+    // Every connected gamepad's buttons and sticks, as of the last update.
+    gamepads: Vec<glfw::GamepadState>,
 }
 
 impl Window {
-    pub fn new(width: u32, height: u32, title: &str) -> Window {
-        let mut glfw = glfw::init(glfw::FAIL_ON_ERRORS).expect("Failed to initialize GLFW!");
+    pub fn new(width: u32, height: u32, title: &str) -> Result<Window, Error> {
+        let mut glfw = glfw::init(Some(glfw::Callback {
+            f: record_glfw_error as fn(glfw::Error, String, &()),
+            data: (),
+        }))
+        .map_err(|error| Error::Window(format!("failed to initialize GLFW: {:?}", error)))?;
 
         glfw.window_hint(glfw::WindowHint::ContextVersion(4, 3));
         glfw.window_hint(glfw::WindowHint::OpenGlProfile(
@@ -42,42 +64,67 @@ impl Window {
 
         glfw.window_hint(glfw::WindowHint::OpenGlDebugContext(true));
 
-        let (mut window, events) = glfw
-            .create_window(width, height, title, glfw::WindowMode::Windowed)
-            .expect("Failed to create the Window!");
+        let (mut window, events) =
+            match glfw.create_window(width, height, title, glfw::WindowMode::Windowed) {
+                Some(window) => window,
+                None => return Err(take_glfw_error()),
+            };
 
         window.make_current();
         window.set_key_polling(true);
+        window.set_char_polling(true);
+        window.set_scroll_polling(true);
 
         gl::load(|procname| glfw.get_proc_address_raw(procname));
 
+        let version = unsafe { gl::GetString(gl::VERSION) };
+
+        if version.is_null() {
+            return Err(Error::GlContext(
+                "failed to load the OpenGL functions".to_string(),
+            ));
+        }
+
+        log::info!(
+            "Created a {}x{} window with OpenGL {}",
+            width,
+            height,
+            unsafe { std::ffi::CStr::from_ptr(version as *const std::ffi::c_char) }
+                .to_string_lossy()
+        );
+
         unsafe {
             gl::Enable(gl::DEBUG_OUTPUT);
             gl::Enable(gl::DEBUG_OUTPUT_SYNCHRONOUS);
 
-            gl::DebugMessageCallback(opengl_debug_callback, std::ptr::null());
+            gl::DebugMessageCallback(gl_debug::callback, std::ptr::null());
 
             gl::Enable(gl::BLEND);
             gl::BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
         }
 
+        // Centers the window, if there's a monitor to center it on.
         glfw.with_primary_monitor(|_, m| {
-            let video_mode = m.unwrap().get_video_mode().unwrap();
+            if let Some(video_mode) = m.and_then(|m| m.get_video_mode()) {
+                let new_x = (video_mode.width as i32 - width as i32) / 2;
+                let new_y = (video_mode.height as i32 - height as i32) / 2;
 
-            let new_x: u32 = (video_mode.width - width) / 2;
-            let new_y: u32 = (video_mode.height - height) / 2;
-
-            window.set_pos(new_x.try_into().unwrap(), new_y.try_into().unwrap());
+                window.set_pos(new_x.max(0), new_y.max(0));
+            }
         });
 
-        return Window {
+        return Ok(Window {
             glfw,
             window,
-            _events: events,
-        };
+            events,
+            typed_text: String::new(),
+            key_presses: Vec::new(),
+            scroll: 0.0,
+            gamepads: Vec::new(),
+        });
     }
     
-    pub fn build_renderer(&self, max_quads: u32) -> Renderer2D {
+    pub fn build_renderer(&self, max_quads: u32) -> Result<Renderer2D, Error> {
         let (width, height) = self.window.get_size();
         Renderer2D::new(max_quads.try_into().unwrap(), width as f32, height as f32)
     }
@@ -93,6 +140,34 @@ impl Window {
     pub fn update(&mut self) {
         self.window.swap_buffers();
         self.glfw.poll_events();
+
+        self.typed_text.clear();
+        self.key_presses.clear();
+        self.scroll = 0.0;
+
+        for (_, event) in glfw::flush_messages(&self.events) {
+            match event {
+                glfw::WindowEvent::Char(character) => self.typed_text.push(character),
+                glfw::WindowEvent::Key(
+                    key,
+                    _,
+                    glfw::Action::Press | glfw::Action::Repeat,
+                    modifiers,
+                ) => self.key_presses.push((key, modifiers)),
+                glfw::WindowEvent::Scroll(_, y) => self.scroll += y as f32,
+                _ => {}
+            }
+        }
+
+        // Looking at all the joystick slots isn't free, so it's done once
+        // here rather than every time a button is checked.
+        let glfw = &self.glfw;
+        self.gamepads = (0..16)
+            .filter_map(glfw::JoystickId::from_i32)
+            .filter_map(|id| glfw.get_joystick(id).get_gamepad_state())
+            .collect();
+
+        gl_debug::check_pending_panic();
     }
 
     pub fn get_time(&self) -> f64 {
@@ -112,6 +187,56 @@ impl Window {
     pub fn get_mouse_position(&self) -> (f64, f64) {
         self.window.get_cursor_pos()
     }
+
+    /// The characters typed since the last update, after the keyboard layout
+    /// and any input method (for typing Chinese or Japanese, say) have turned
+    /// the key presses into text.
+    pub fn get_typed_text(&self) -> &str {
+        &self.typed_text
+    }
+
+    /// The keys pressed since the last update, and the modifiers held with
+    /// them. Keys that are held down show up again each time they repeat.
+    pub fn get_key_presses(&self) -> &[(glfw::Key, glfw::Modifiers)] {
+        &self.key_presses
+    }
+
+    /// How far the mouse wheel was scrolled since the last update. Positive
+    /// is up, or away from the user.
+    pub fn get_scroll(&self) -> f32 {
+        self.scroll
+    }
+
+    pub fn get_clipboard_string(&self) -> Option<String> {
+        self.window.get_clipboard_string()
+    }
+
+    pub fn set_clipboard_string(&mut self, text: &str) {
+        self.window.set_clipboard_string(text);
+    }
+
+    /// Whether `button` is down on any of the connected gamepads. Gamepads
+    /// are read in `update`, so this doesn't change between updates.
+    pub fn is_gamepad_button_down(&self, button: glfw::GamepadButton) -> bool {
+        return self
+            .gamepads
+            .iter()
+            .any(|state| state.get_button_state(button) == glfw::Action::Press);
+    }
+
+    /// How far `axis` is pushed on whichever connected gamepad has it pushed
+    /// the furthest, from -1 to 1. Triggers go from -1 when let go of to 1.
+    pub fn get_gamepad_axis(&self, axis: glfw::GamepadAxis) -> f32 {
+        let values = self.gamepads.iter().map(|state| state.get_axis(axis));
+
+        return values.fold(0.0, |furthest, value| {
+            if value.abs() > furthest.abs() {
+                value
+            } else {
+                furthest
+            }
+        });
+    }
 }
 
 pub struct SceneManager {
@@ -131,7 +256,10 @@ impl SceneManager {
         self.active_scene = new_active_scene;
     }
 
+    /// Moves the shared tweens along before updating the scene, so the scene
+    /// sees this frame's values.
     pub fn update_active(&mut self, delta_time: f64) {
+        tween::update(delta_time);
         self.next_scene = self.active_scene.update(delta_time);
     }
 
@@ -0,0 +1,542 @@
+//! Shapes and the tests between them. Everything uses screen coordinates,
+//! with y going down.
+//!
+//! A `Rect` includes its top and left edges but not its bottom and right
+//! ones, so a point is only ever in one rect of a grid, and rects that only
+//! share an edge don't overlap. Every other shape includes its edges, and so
+//! do tests between a rect and another kind of shape.
+
+use super::Vector2;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rect<T: Copy> {
+    pub x: T,
+    pub y: T,
+    pub width: T,
+    pub height: T,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Circle<T: Copy> {
+    pub center: Vector2<T>,
+    pub radius: T,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LineSegment<T: Copy> {
+    pub start: Vector2<T>,
+    pub end: Vector2<T>,
+}
+
+/// A ray going from `origin` forever in `direction`, which always has a
+/// length of 1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ray2D<T: Copy> {
+    origin: Vector2<T>,
+    direction: Vector2<T>,
+}
+
+/// Where a ray hit something. Rays that start inside a shape hit it straight
+/// away, with a `distance` of 0 and a zero `normal`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RayHit<T: Copy> {
+    pub distance: T,
+    pub point: Vector2<T>,
+    /// Points out of the shape, towards where the ray came from.
+    pub normal: Vector2<T>,
+}
+
+/// A convex polygon. The points can go around either way, but it has to be
+/// convex, or the tests give wrong answers.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Polygon<T: Copy> {
+    pub points: Vec<Vector2<T>>,
+}
+
+impl<T: Copy> Rect<T> {
+    pub fn new(x: T, y: T, width: T, height: T) -> Rect<T> {
+        Rect {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+impl<T: Copy> Circle<T> {
+    pub fn new(center: Vector2<T>, radius: T) -> Circle<T> {
+        Circle { center, radius }
+    }
+}
+
+impl<T: Copy> LineSegment<T> {
+    pub fn new(start: Vector2<T>, end: Vector2<T>) -> LineSegment<T> {
+        LineSegment { start, end }
+    }
+}
+
+impl<T: Copy> Ray2D<T> {
+    pub fn get_origin(&self) -> Vector2<T> {
+        self.origin
+    }
+
+    pub fn get_direction(&self) -> Vector2<T> {
+        self.direction
+    }
+}
+
+impl<T: Copy> Polygon<T> {
+    pub fn new(points: Vec<Vector2<T>>) -> Polygon<T> {
+        Polygon { points }
+    }
+
+    /// Each edge, from each point to the next one.
+    pub fn edges(&self) -> impl Iterator<Item = LineSegment<T>> + '_ {
+        let count = self.points.len();
+
+        (0..count).map(move |i| LineSegment {
+            start: self.points[i],
+            end: self.points[(i + 1) % count],
+        })
+    }
+}
+
+macro_rules! impl_geometry {
+    ($float:ty) => {
+        impl Rect<$float> {
+            /// The rect between two opposite corners, in any order.
+            pub fn from_corners(a: Vector2<$float>, b: Vector2<$float>) -> Rect<$float> {
+                let min = a.min(b);
+                let max = a.max(b);
+
+                return Rect::new(min.x, min.y, max.x - min.x, max.y - min.y);
+            }
+
+            /// The top left corner.
+            pub fn get_min(&self) -> Vector2<$float> {
+                Vector2::new(self.x, self.y)
+            }
+
+            /// The bottom right corner.
+            pub fn get_max(&self) -> Vector2<$float> {
+                Vector2::new(self.x + self.width, self.y + self.height)
+            }
+
+            pub fn get_center(&self) -> Vector2<$float> {
+                Vector2::new(self.x + self.width / 2.0, self.y + self.height / 2.0)
+            }
+
+            pub fn get_size(&self) -> Vector2<$float> {
+                Vector2::new(self.width, self.height)
+            }
+
+            pub fn contains_point(&self, point: Vector2<$float>) -> bool {
+                point.x >= self.x
+                    && point.x < self.x + self.width
+                    && point.y >= self.y
+                    && point.y < self.y + self.height
+            }
+
+            pub fn contains_rect(&self, other: &Rect<$float>) -> bool {
+                other.x >= self.x
+                    && other.y >= self.y
+                    && other.x + other.width <= self.x + self.width
+                    && other.y + other.height <= self.y + self.height
+            }
+
+            pub fn overlaps(&self, other: &Rect<$float>) -> bool {
+                self.intersection(other).is_some()
+            }
+
+            pub fn overlaps_circle(&self, circle: &Circle<$float>) -> bool {
+                circle.overlaps_rect(self)
+            }
+
+            /// The area both rects cover, or `None` if they don't overlap.
+            pub fn intersection(&self, other: &Rect<$float>) -> Option<Rect<$float>> {
+                let min = self.get_min().max(other.get_min());
+                let max = self.get_max().min(other.get_max());
+
+                if max.x <= min.x || max.y <= min.y {
+                    return None;
+                }
+
+                return Some(Rect::<$float>::from_corners(min, max));
+            }
+
+            /// The smallest rect that covers both.
+            pub fn union(&self, other: &Rect<$float>) -> Rect<$float> {
+                Rect::<$float>::from_corners(
+                    self.get_min().min(other.get_min()),
+                    self.get_max().max(other.get_max()),
+                )
+            }
+
+            /// Moves every edge out by `amount`, or in if it's negative.
+            pub fn expand(&self, amount: $float) -> Rect<$float> {
+                Rect::new(
+                    self.x - amount,
+                    self.y - amount,
+                    self.width + amount * 2.0,
+                    self.height + amount * 2.0,
+                )
+            }
+
+            /// Grows the rect just enough to reach `point`.
+            pub fn expand_to_include(&self, point: Vector2<$float>) -> Rect<$float> {
+                Rect::<$float>::from_corners(self.get_min().min(point), self.get_max().max(point))
+            }
+
+            /// The point in the rect closest to `point`, which is `point`
+            /// itself if it's inside.
+            pub fn closest_point(&self, point: Vector2<$float>) -> Vector2<$float> {
+                point.clamp(self.get_min(), self.get_max())
+            }
+
+            pub fn to_polygon(&self) -> Polygon<$float> {
+                let (min, max) = (self.get_min(), self.get_max());
+
+                Polygon::new(vec![
+                    min,
+                    Vector2::new(max.x, min.y),
+                    max,
+                    Vector2::new(min.x, max.y),
+                ])
+            }
+        }
+
+        impl Circle<$float> {
+            pub fn contains_point(&self, point: Vector2<$float>) -> bool {
+                self.center.distance(point) <= self.radius
+            }
+
+            pub fn overlaps(&self, other: &Circle<$float>) -> bool {
+                self.center.distance(other.center) <= self.radius + other.radius
+            }
+
+            pub fn overlaps_rect(&self, rect: &Rect<$float>) -> bool {
+                self.contains_point(rect.closest_point(self.center))
+            }
+
+            /// The point in the circle closest to `point`, which is `point`
+            /// itself if it's inside.
+            pub fn closest_point(&self, point: Vector2<$float>) -> Vector2<$float> {
+                if self.contains_point(point) {
+                    return point;
+                }
+
+                return self.center + (point - self.center).normalize() * self.radius;
+            }
+
+            pub fn get_bounds(&self) -> Rect<$float> {
+                Rect::new(
+                    self.center.x - self.radius,
+                    self.center.y - self.radius,
+                    self.radius * 2.0,
+                    self.radius * 2.0,
+                )
+            }
+        }
+
+        impl LineSegment<$float> {
+            pub fn get_length(&self) -> $float {
+                self.start.distance(self.end)
+            }
+
+            pub fn closest_point(&self, point: Vector2<$float>) -> Vector2<$float> {
+                let along = self.end - self.start;
+                let length_squared = along.length_squared();
+
+                if length_squared == 0.0 {
+                    return self.start;
+                }
+
+                let t = ((point - self.start).dot(along) / length_squared).clamp(0.0, 1.0);
+                return self.start + along * t;
+            }
+
+            pub fn distance_to_point(&self, point: Vector2<$float>) -> $float {
+                self.closest_point(point).distance(point)
+            }
+
+            /// Where the two segments cross. Segments that lie along the same
+            /// line don't count as crossing.
+            pub fn intersection(&self, other: &LineSegment<$float>) -> Option<Vector2<$float>> {
+                let a = self.end - self.start;
+                let b = other.end - other.start;
+                let denominator = a.cross(b);
+
+                if denominator == 0.0 {
+                    return None;
+                }
+
+                let offset = other.start - self.start;
+                let t = offset.cross(b) / denominator;
+                let u = offset.cross(a) / denominator;
+
+                if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+                    return Some(self.start + a * t);
+                }
+
+                return None;
+            }
+
+            pub fn get_bounds(&self) -> Rect<$float> {
+                Rect::<$float>::from_corners(self.start, self.end)
+            }
+        }
+
+        impl Ray2D<$float> {
+            /// `direction` doesn't need a length of 1, but it can't be zero.
+            pub fn new(origin: Vector2<$float>, direction: Vector2<$float>) -> Ray2D<$float> {
+                Ray2D {
+                    origin,
+                    direction: direction.normalize(),
+                }
+            }
+
+            pub fn point_at(&self, distance: $float) -> Vector2<$float> {
+                self.origin + self.direction * distance
+            }
+
+            fn hit(&self, distance: $float, normal: Vector2<$float>) -> RayHit<$float> {
+                RayHit {
+                    distance,
+                    point: self.point_at(distance),
+                    normal,
+                }
+            }
+
+            pub fn cast_rect(&self, rect: &Rect<$float>) -> Option<RayHit<$float>> {
+                let (min, max) = (rect.get_min(), rect.get_max());
+
+                let mut near = <$float>::NEG_INFINITY;
+                let mut far = <$float>::INFINITY;
+                let mut normal = Vector2::new(0.0, 0.0);
+
+                // The slab method: where the ray crosses each pair of edges.
+                for axis in 0..2 {
+                    let (origin, direction, low, high) = if axis == 0 {
+                        (self.origin.x, self.direction.x, min.x, max.x)
+                    } else {
+                        (self.origin.y, self.direction.y, min.y, max.y)
+                    };
+
+                    if direction == 0.0 {
+                        if origin < low || origin > high {
+                            return None;
+                        }
+
+                        continue;
+                    }
+
+                    let (enter, exit) = ((low - origin) / direction, (high - origin) / direction);
+                    let (enter, exit) = if enter < exit {
+                        (enter, exit)
+                    } else {
+                        (exit, enter)
+                    };
+
+                    if enter > near {
+                        near = enter;
+
+                        normal = if axis == 0 {
+                            Vector2::new(-direction.signum(), 0.0)
+                        } else {
+                            Vector2::new(0.0, -direction.signum())
+                        };
+                    }
+
+                    far = far.min(exit);
+                }
+
+                if near > far || far < 0.0 {
+                    return None;
+                }
+
+                if near < 0.0 {
+                    return Some(self.hit(0.0, Vector2::new(0.0, 0.0)));
+                }
+
+                return Some(self.hit(near, normal));
+            }
+
+            pub fn cast_circle(&self, circle: &Circle<$float>) -> Option<RayHit<$float>> {
+                let offset = self.origin - circle.center;
+                let b = offset.dot(self.direction);
+                let c = offset.length_squared() - circle.radius * circle.radius;
+
+                if c <= 0.0 {
+                    return Some(self.hit(0.0, Vector2::new(0.0, 0.0)));
+                }
+
+                let discriminant = b * b - c;
+
+                if b > 0.0 || discriminant < 0.0 {
+                    return None;
+                }
+
+                let distance = -b - discriminant.sqrt();
+                let point = self.point_at(distance);
+
+                return Some(self.hit(distance, (point - circle.center).normalize()));
+            }
+
+            pub fn cast_segment(&self, segment: &LineSegment<$float>) -> Option<RayHit<$float>> {
+                let along = segment.end - segment.start;
+                let denominator = self.direction.cross(along);
+
+                if denominator == 0.0 {
+                    return None;
+                }
+
+                let offset = segment.start - self.origin;
+                let distance = offset.cross(along) / denominator;
+                let t = offset.cross(self.direction) / denominator;
+
+                if distance < 0.0 || !(0.0..=1.0).contains(&t) {
+                    return None;
+                }
+
+                let mut normal = along.perpendicular().normalize();
+
+                if normal.dot(self.direction) > 0.0 {
+                    normal = -normal;
+                }
+
+                return Some(self.hit(distance, normal));
+            }
+
+            pub fn cast_polygon(&self, polygon: &Polygon<$float>) -> Option<RayHit<$float>> {
+                if polygon.contains_point(self.origin) {
+                    return Some(self.hit(0.0, Vector2::new(0.0, 0.0)));
+                }
+
+                return polygon
+                    .edges()
+                    .filter_map(|edge| self.cast_segment(&edge))
+                    .min_by(|a, b| a.distance.total_cmp(&b.distance));
+            }
+        }
+
+        impl Polygon<$float> {
+            pub fn contains_point(&self, point: Vector2<$float>) -> bool {
+                if self.points.len() < 3 {
+                    return false;
+                }
+
+                let mut sign = 0.0;
+
+                // The point has to be on the same side of every edge.
+                for edge in self.edges() {
+                    let side = (edge.end - edge.start).cross(point - edge.start);
+
+                    if side == 0.0 {
+                        continue;
+                    }
+
+                    if sign == 0.0 {
+                        sign = side.signum();
+                    } else if side.signum() != sign {
+                        return false;
+                    }
+                }
+
+                return true;
+            }
+
+            // The smallest and largest `axis` goes along the polygon.
+            fn project(&self, axis: Vector2<$float>) -> ($float, $float) {
+                let mut min = <$float>::INFINITY;
+                let mut max = <$float>::NEG_INFINITY;
+
+                for point in &self.points {
+                    let projected = point.dot(axis);
+                    min = min.min(projected);
+                    max = max.max(projected);
+                }
+
+                return (min, max);
+            }
+
+            // A single point, or the same one over and over, has no edges to
+            // separate it by, so it would overlap everything.
+            fn is_degenerate(&self) -> bool {
+                self.points.iter().all(|point| *point == self.points[0])
+            }
+
+            /// Uses the separating axis theorem, so both have to be convex.
+            /// Polygons with fewer than 2 different points never overlap
+            /// anything.
+            pub fn overlaps(&self, other: &Polygon<$float>) -> bool {
+                if self.is_degenerate() || other.is_degenerate() {
+                    return false;
+                }
+
+                let axes = self
+                    .edges()
+                    .chain(other.edges())
+                    .map(|edge| (edge.end - edge.start).perpendicular());
+
+                for axis in axes {
+                    let (min_a, max_a) = self.project(axis);
+                    let (min_b, max_b) = other.project(axis);
+
+                    if max_a < min_b || max_b < min_a {
+                        return false;
+                    }
+                }
+
+                return true;
+            }
+
+            pub fn overlaps_rect(&self, rect: &Rect<$float>) -> bool {
+                self.overlaps(&rect.to_polygon())
+            }
+
+            pub fn overlaps_circle(&self, circle: &Circle<$float>) -> bool {
+                circle.contains_point(self.closest_point(circle.center))
+            }
+
+            /// The point in the polygon closest to `point`, which is `point`
+            /// itself if it's inside.
+            pub fn closest_point(&self, point: Vector2<$float>) -> Vector2<$float> {
+                if self.contains_point(point) || self.points.is_empty() {
+                    return point;
+                }
+
+                return self
+                    .edges()
+                    .map(|edge| edge.closest_point(point))
+                    .min_by(|a, b| a.distance(point).total_cmp(&b.distance(point)))
+                    .unwrap();
+            }
+
+            /// The smallest rect around every point.
+            pub fn get_bounds(&self) -> Rect<$float> {
+                let first = match self.points.first() {
+                    Some(first) => *first,
+                    None => return Rect::default(),
+                };
+
+                let (min, max) = self
+                    .points
+                    .iter()
+                    .fold((first, first), |(min, max), point| {
+                        (min.min(*point), max.max(*point))
+                    });
+
+                return Rect::<$float>::from_corners(min, max);
+            }
+        }
+    };
+}
+
+impl_geometry!(f32);
+impl_geometry!(f64);
@@ -0,0 +1,460 @@
+use super::{Vector2, Vector3, Vector4};
+use std::ops::Mul;
+
+/// A 3x3 matrix, mostly for 2D transforms in homogeneous coordinates.
+///
+/// It's stored row-major: the element at `row` and `column` is at
+/// `row * 3 + column`, and a translation goes in the last column. Matrices
+/// multiply column vectors, so `a * b` applies `b` first and then `a`.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Matrix3<T: Copy> {
+    matrix: [T; 9],
+}
+
+/// A 4x4 matrix.
+///
+/// It's stored row-major, like `Matrix3`: the element at `row` and `column`
+/// is at `row * 4 + column`, and a translation goes in the last column.
+/// OpenGL expects column-major, so the renderer uploads these with
+/// `transpose` set to `GL_TRUE`. Anything that hands `as_ptr` to OpenGL
+/// directly has to do the same.
+#[repr(C)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Matrix4<T: Copy> {
+    matrix: [T; 16],
+}
+
+impl<T: Copy> Matrix3<T> {
+    /// Makes a matrix with `b` along the diagonal and `a` everywhere else.
+    pub fn new(a: T, b: T) -> Matrix3<T> {
+        let mut matrix = [a; 9];
+        matrix[0] = b;
+        matrix[4] = b;
+        matrix[8] = b;
+
+        return Matrix3 { matrix };
+    }
+
+    pub fn from_rows(rows: [[T; 3]; 3]) -> Matrix3<T> {
+        let mut matrix = [rows[0][0]; 9];
+
+        for (row, values) in rows.iter().enumerate() {
+            matrix[row * 3..row * 3 + 3].copy_from_slice(values);
+        }
+
+        return Matrix3 { matrix };
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> T {
+        self.matrix[row * 3 + column]
+    }
+
+    pub fn set(&mut self, row: usize, column: usize, value: T) {
+        self.matrix[row * 3 + column] = value;
+    }
+
+    pub fn transpose(&self) -> Matrix3<T> {
+        let mut transposed = *self;
+
+        for row in 0..3 {
+            for column in 0..3 {
+                transposed.set(column, row, self.get(row, column));
+            }
+        }
+
+        return transposed;
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        return self.matrix.as_ptr();
+    }
+}
+
+impl<T: Copy> Matrix4<T> {
+    /// Makes a matrix with `b` along the diagonal and `a` everywhere else.
+    pub fn new(a: T, b: T) -> Matrix4<T> {
+        let mut matrix = [a; 16];
+        matrix[0] = b;
+        matrix[5] = b;
+        matrix[10] = b;
+        matrix[15] = b;
+
+        return Matrix4 { matrix };
+    }
+
+    pub fn from_rows(rows: [[T; 4]; 4]) -> Matrix4<T> {
+        let mut matrix = [rows[0][0]; 16];
+
+        for (row, values) in rows.iter().enumerate() {
+            matrix[row * 4..row * 4 + 4].copy_from_slice(values);
+        }
+
+        return Matrix4 { matrix };
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> T {
+        self.matrix[row * 4 + column]
+    }
+
+    pub fn set(&mut self, row: usize, column: usize, value: T) {
+        self.matrix[row * 4 + column] = value;
+    }
+
+    pub fn transpose(&self) -> Matrix4<T> {
+        let mut transposed = *self;
+
+        for row in 0..4 {
+            for column in 0..4 {
+                transposed.set(column, row, self.get(row, column));
+            }
+        }
+
+        return transposed;
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        return self.matrix.as_ptr();
+    }
+}
+
+macro_rules! impl_matrix_float {
+    ($float:ty) => {
+        impl Matrix3<$float> {
+            pub fn identity() -> Matrix3<$float> {
+                Matrix3::new(0.0, 1.0)
+            }
+
+            pub fn translation(offset: Vector2<$float>) -> Matrix3<$float> {
+                let mut matrix = Matrix3::<$float>::identity();
+                matrix.set(0, 2, offset.x);
+                matrix.set(1, 2, offset.y);
+
+                return matrix;
+            }
+
+            /// Rotates by `angle` radians, which is clockwise in screen
+            /// coordinates, the same as `Vector2::rotate`.
+            pub fn rotation(angle: $float) -> Matrix3<$float> {
+                let (sin, cos) = angle.sin_cos();
+
+                return Matrix3::from_rows([[cos, -sin, 0.0], [sin, cos, 0.0], [0.0, 0.0, 1.0]]);
+            }
+
+            pub fn scale(scale: Vector2<$float>) -> Matrix3<$float> {
+                return Matrix3::from_rows([
+                    [scale.x, 0.0, 0.0],
+                    [0.0, scale.y, 0.0],
+                    [0.0, 0.0, 1.0],
+                ]);
+            }
+
+            pub fn determinant(&self) -> $float {
+                let m = |row, column| self.get(row, column);
+
+                return m(0, 0) * (m(1, 1) * m(2, 2) - m(1, 2) * m(2, 1))
+                    - m(0, 1) * (m(1, 0) * m(2, 2) - m(1, 2) * m(2, 0))
+                    + m(0, 2) * (m(1, 0) * m(2, 1) - m(1, 1) * m(2, 0));
+            }
+
+            /// Returns `None` if the matrix can't be inverted, like a scale
+            /// of 0.
+            pub fn inverse(&self) -> Option<Matrix3<$float>> {
+                let determinant = self.determinant();
+
+                if determinant == 0.0 || !determinant.is_finite() {
+                    return None;
+                }
+
+                let m = |row: usize, column: usize| self.get(row % 3, column % 3);
+                let mut inverse = Matrix3::new(0.0, 0.0);
+
+                // The adjugate, built from cofactors that wrap around the
+                // edges, so the signs come out right on their own.
+                for row in 0..3 {
+                    for column in 0..3 {
+                        let cofactor = m(column + 1, row + 1) * m(column + 2, row + 2)
+                            - m(column + 1, row + 2) * m(column + 2, row + 1);
+                        inverse.set(row, column, cofactor / determinant);
+                    }
+                }
+
+                return Some(inverse);
+            }
+
+            pub fn transform_point(&self, point: Vector2<$float>) -> Vector2<$float> {
+                let result = *self * Vector3::new(point.x, point.y, 1.0);
+                return Vector2::new(result.x, result.y);
+            }
+
+            /// Like `transform_point`, but without the translation.
+            pub fn transform_vector(&self, vector: Vector2<$float>) -> Vector2<$float> {
+                let result = *self * Vector3::new(vector.x, vector.y, 0.0);
+                return Vector2::new(result.x, result.y);
+            }
+
+            /// The same 2D transform as a 4x4 matrix, leaving z alone, so it
+            /// can be used alongside `orthographic`.
+            pub fn to_matrix4(&self) -> Matrix4<$float> {
+                let m = |row, column| self.get(row, column);
+
+                return Matrix4::from_rows([
+                    [m(0, 0), m(0, 1), 0.0, m(0, 2)],
+                    [m(1, 0), m(1, 1), 0.0, m(1, 2)],
+                    [0.0, 0.0, 1.0, 0.0],
+                    [m(2, 0), m(2, 1), 0.0, m(2, 2)],
+                ]);
+            }
+        }
+
+        impl Mul for Matrix3<$float> {
+            type Output = Matrix3<$float>;
+
+            fn mul(self, other: Matrix3<$float>) -> Matrix3<$float> {
+                let mut result = Matrix3::new(0.0, 0.0);
+
+                for row in 0..3 {
+                    for column in 0..3 {
+                        let mut sum = 0.0;
+
+                        for i in 0..3 {
+                            sum += self.get(row, i) * other.get(i, column);
+                        }
+
+                        result.set(row, column, sum);
+                    }
+                }
+
+                return result;
+            }
+        }
+
+        impl Mul<Vector3<$float>> for Matrix3<$float> {
+            type Output = Vector3<$float>;
+
+            fn mul(self, vector: Vector3<$float>) -> Vector3<$float> {
+                let row = |row| Vector3::new(self.get(row, 0), self.get(row, 1), self.get(row, 2));
+                return Vector3::new(row(0).dot(vector), row(1).dot(vector), row(2).dot(vector));
+            }
+        }
+
+        impl Matrix4<$float> {
+            pub fn identity() -> Matrix4<$float> {
+                Matrix4::new(0.0, 1.0)
+            }
+
+            pub fn translation(offset: Vector3<$float>) -> Matrix4<$float> {
+                let mut matrix = Matrix4::<$float>::identity();
+                matrix.set(0, 3, offset.x);
+                matrix.set(1, 3, offset.y);
+                matrix.set(2, 3, offset.z);
+
+                return matrix;
+            }
+
+            pub fn scale(scale: Vector3<$float>) -> Matrix4<$float> {
+                let mut matrix = Matrix4::<$float>::identity();
+                matrix.set(0, 0, scale.x);
+                matrix.set(1, 1, scale.y);
+                matrix.set(2, 2, scale.z);
+
+                return matrix;
+            }
+
+            pub fn rotation_x(angle: $float) -> Matrix4<$float> {
+                let (sin, cos) = angle.sin_cos();
+
+                return Matrix4::from_rows([
+                    [1.0, 0.0, 0.0, 0.0],
+                    [0.0, cos, -sin, 0.0],
+                    [0.0, sin, cos, 0.0],
+                    [0.0, 0.0, 0.0, 1.0],
+                ]);
+            }
+
+            pub fn rotation_y(angle: $float) -> Matrix4<$float> {
+                let (sin, cos) = angle.sin_cos();
+
+                return Matrix4::from_rows([
+                    [cos, 0.0, sin, 0.0],
+                    [0.0, 1.0, 0.0, 0.0],
+                    [-sin, 0.0, cos, 0.0],
+                    [0.0, 0.0, 0.0, 1.0],
+                ]);
+            }
+
+            /// The rotation used for 2D, which is clockwise in screen
+            /// coordinates.
+            pub fn rotation_z(angle: $float) -> Matrix4<$float> {
+                let (sin, cos) = angle.sin_cos();
+
+                return Matrix4::from_rows([
+                    [cos, -sin, 0.0, 0.0],
+                    [sin, cos, 0.0, 0.0],
+                    [0.0, 0.0, 1.0, 0.0],
+                    [0.0, 0.0, 0.0, 1.0],
+                ]);
+            }
+
+            // The adjugate, which is the inverse before it's divided by the
+            // determinant. Its first column also gives the determinant.
+            fn adjugate(&self) -> [$float; 16] {
+                let m = &self.matrix;
+                let mut inverse = [0.0; 16];
+
+                inverse[0] = m[5] * m[10] * m[15] - m[5] * m[11] * m[14] - m[9] * m[6] * m[15]
+                    + m[9] * m[7] * m[14]
+                    + m[13] * m[6] * m[11]
+                    - m[13] * m[7] * m[10];
+                inverse[4] = -m[4] * m[10] * m[15] + m[4] * m[11] * m[14] + m[8] * m[6] * m[15]
+                    - m[8] * m[7] * m[14]
+                    - m[12] * m[6] * m[11]
+                    + m[12] * m[7] * m[10];
+                inverse[8] = m[4] * m[9] * m[15] - m[4] * m[11] * m[13] - m[8] * m[5] * m[15]
+                    + m[8] * m[7] * m[13]
+                    + m[12] * m[5] * m[11]
+                    - m[12] * m[7] * m[9];
+                inverse[12] = -m[4] * m[9] * m[14] + m[4] * m[10] * m[13] + m[8] * m[5] * m[14]
+                    - m[8] * m[6] * m[13]
+                    - m[12] * m[5] * m[10]
+                    + m[12] * m[6] * m[9];
+                inverse[1] = -m[1] * m[10] * m[15] + m[1] * m[11] * m[14] + m[9] * m[2] * m[15]
+                    - m[9] * m[3] * m[14]
+                    - m[13] * m[2] * m[11]
+                    + m[13] * m[3] * m[10];
+                inverse[5] = m[0] * m[10] * m[15] - m[0] * m[11] * m[14] - m[8] * m[2] * m[15]
+                    + m[8] * m[3] * m[14]
+                    + m[12] * m[2] * m[11]
+                    - m[12] * m[3] * m[10];
+                inverse[9] = -m[0] * m[9] * m[15] + m[0] * m[11] * m[13] + m[8] * m[1] * m[15]
+                    - m[8] * m[3] * m[13]
+                    - m[12] * m[1] * m[11]
+                    + m[12] * m[3] * m[9];
+                inverse[13] = m[0] * m[9] * m[14] - m[0] * m[10] * m[13] - m[8] * m[1] * m[14]
+                    + m[8] * m[2] * m[13]
+                    + m[12] * m[1] * m[10]
+                    - m[12] * m[2] * m[9];
+                inverse[2] = m[1] * m[6] * m[15] - m[1] * m[7] * m[14] - m[5] * m[2] * m[15]
+                    + m[5] * m[3] * m[14]
+                    + m[13] * m[2] * m[7]
+                    - m[13] * m[3] * m[6];
+                inverse[6] = -m[0] * m[6] * m[15] + m[0] * m[7] * m[14] + m[4] * m[2] * m[15]
+                    - m[4] * m[3] * m[14]
+                    - m[12] * m[2] * m[7]
+                    + m[12] * m[3] * m[6];
+                inverse[10] = m[0] * m[5] * m[15] - m[0] * m[7] * m[13] - m[4] * m[1] * m[15]
+                    + m[4] * m[3] * m[13]
+                    + m[12] * m[1] * m[7]
+                    - m[12] * m[3] * m[5];
+                inverse[14] = -m[0] * m[5] * m[14] + m[0] * m[6] * m[13] + m[4] * m[1] * m[14]
+                    - m[4] * m[2] * m[13]
+                    - m[12] * m[1] * m[6]
+                    + m[12] * m[2] * m[5];
+                inverse[3] = -m[1] * m[6] * m[11] + m[1] * m[7] * m[10] + m[5] * m[2] * m[11]
+                    - m[5] * m[3] * m[10]
+                    - m[9] * m[2] * m[7]
+                    + m[9] * m[3] * m[6];
+                inverse[7] = m[0] * m[6] * m[11] - m[0] * m[7] * m[10] - m[4] * m[2] * m[11]
+                    + m[4] * m[3] * m[10]
+                    + m[8] * m[2] * m[7]
+                    - m[8] * m[3] * m[6];
+                inverse[11] = -m[0] * m[5] * m[11] + m[0] * m[7] * m[9] + m[4] * m[1] * m[11]
+                    - m[4] * m[3] * m[9]
+                    - m[8] * m[1] * m[7]
+                    + m[8] * m[3] * m[5];
+                inverse[15] = m[0] * m[5] * m[10] - m[0] * m[6] * m[9] - m[4] * m[1] * m[10]
+                    + m[4] * m[2] * m[9]
+                    + m[8] * m[1] * m[6]
+                    - m[8] * m[2] * m[5];
+
+                return inverse;
+            }
+
+            pub fn determinant(&self) -> $float {
+                let adjugate = self.adjugate();
+                let m = &self.matrix;
+
+                return m[0] * adjugate[0]
+                    + m[1] * adjugate[4]
+                    + m[2] * adjugate[8]
+                    + m[3] * adjugate[12];
+            }
+
+            /// Returns `None` if the matrix can't be inverted.
+            pub fn inverse(&self) -> Option<Matrix4<$float>> {
+                let mut inverse = self.adjugate();
+                let determinant = self.determinant();
+
+                if determinant == 0.0 || !determinant.is_finite() {
+                    return None;
+                }
+
+                for value in &mut inverse {
+                    *value /= determinant;
+                }
+
+                return Some(Matrix4 { matrix: inverse });
+            }
+
+            pub fn transform_point(&self, point: Vector3<$float>) -> Vector3<$float> {
+                let result = *self * Vector4::new(point.x, point.y, point.z, 1.0);
+                return Vector3::new(result.x, result.y, result.z);
+            }
+
+            /// Like `transform_point`, but without the translation.
+            pub fn transform_vector(&self, vector: Vector3<$float>) -> Vector3<$float> {
+                let result = *self * Vector4::new(vector.x, vector.y, vector.z, 0.0);
+                return Vector3::new(result.x, result.y, result.z);
+            }
+        }
+
+        impl Mul for Matrix4<$float> {
+            type Output = Matrix4<$float>;
+
+            fn mul(self, other: Matrix4<$float>) -> Matrix4<$float> {
+                let mut result = Matrix4::new(0.0, 0.0);
+
+                for row in 0..4 {
+                    for column in 0..4 {
+                        let mut sum = 0.0;
+
+                        for i in 0..4 {
+                            sum += self.get(row, i) * other.get(i, column);
+                        }
+
+                        result.set(row, column, sum);
+                    }
+                }
+
+                return result;
+            }
+        }
+
+        impl Mul<Vector4<$float>> for Matrix4<$float> {
+            type Output = Vector4<$float>;
+
+            fn mul(self, vector: Vector4<$float>) -> Vector4<$float> {
+                let row = |row| {
+                    Vector4::new(
+                        self.get(row, 0),
+                        self.get(row, 1),
+                        self.get(row, 2),
+                        self.get(row, 3),
+                    )
+                };
+
+                return Vector4::new(
+                    row(0).dot(vector),
+                    row(1).dot(vector),
+                    row(2).dot(vector),
+                    row(3).dot(vector),
+                );
+            }
+        }
+    };
+}
+
+impl_matrix_float!(f32);
+impl_matrix_float!(f64);
@@ -0,0 +1,127 @@
+use super::{Matrix3, Matrix4, Vector2};
+use std::ops::Mul;
+
+/// A 2D affine transform, for things like sprites attached to other sprites.
+/// A point `p` ends up at `x_axis * p.x + y_axis * p.y + origin`.
+///
+/// `parent * child` gives the child's transform in the parent's space, so
+/// for a hierarchy, multiply down from the root.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Transform2D {
+    pub x_axis: Vector2<f32>,
+    pub y_axis: Vector2<f32>,
+    pub origin: Vector2<f32>,
+}
+
+impl Default for Transform2D {
+    fn default() -> Transform2D {
+        Transform2D::identity()
+    }
+}
+
+impl Transform2D {
+    pub fn identity() -> Transform2D {
+        Transform2D {
+            x_axis: Vector2::new(1.0, 0.0),
+            y_axis: Vector2::new(0.0, 1.0),
+            origin: Vector2::new(0.0, 0.0),
+        }
+    }
+
+    /// Scales, then rotates by `rotation` radians (clockwise on screen), then
+    /// moves to `position`.
+    pub fn new(position: Vector2<f32>, rotation: f32, scale: Vector2<f32>) -> Transform2D {
+        let (sin, cos) = rotation.sin_cos();
+
+        Transform2D {
+            x_axis: Vector2::new(cos, sin) * scale.x,
+            y_axis: Vector2::new(-sin, cos) * scale.y,
+            origin: position,
+        }
+    }
+
+    pub fn translation(offset: Vector2<f32>) -> Transform2D {
+        Transform2D {
+            origin: offset,
+            ..Transform2D::identity()
+        }
+    }
+
+    pub fn rotation(angle: f32) -> Transform2D {
+        Transform2D::new(Vector2::new(0.0, 0.0), angle, Vector2::new(1.0, 1.0))
+    }
+
+    pub fn scale(scale: Vector2<f32>) -> Transform2D {
+        Transform2D::new(Vector2::new(0.0, 0.0), 0.0, scale)
+    }
+
+    pub fn get_position(&self) -> Vector2<f32> {
+        self.origin
+    }
+
+    /// The rotation in radians, going by the x axis.
+    pub fn get_rotation(&self) -> f32 {
+        self.x_axis.angle()
+    }
+
+    /// The lengths of the axes. A transform that's been mirrored gives a
+    /// negative y scale.
+    pub fn get_scale(&self) -> Vector2<f32> {
+        let sign = self.x_axis.cross(self.y_axis).signum();
+        return Vector2::new(self.x_axis.length(), self.y_axis.length() * sign);
+    }
+
+    pub fn transform_point(&self, point: Vector2<f32>) -> Vector2<f32> {
+        self.x_axis * point.x + self.y_axis * point.y + self.origin
+    }
+
+    /// Like `transform_point`, but without the translation.
+    pub fn transform_vector(&self, vector: Vector2<f32>) -> Vector2<f32> {
+        self.x_axis * vector.x + self.y_axis * vector.y
+    }
+
+    /// Returns `None` if the transform squashes everything flat, like a
+    /// scale of 0.
+    pub fn inverse(&self) -> Option<Transform2D> {
+        let determinant = self.x_axis.cross(self.y_axis);
+
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+
+        let x_axis = Vector2::new(self.y_axis.y, -self.x_axis.y) / determinant;
+        let y_axis = Vector2::new(-self.y_axis.x, self.x_axis.x) / determinant;
+        let origin = -(x_axis * self.origin.x + y_axis * self.origin.y);
+
+        return Some(Transform2D {
+            x_axis,
+            y_axis,
+            origin,
+        });
+    }
+
+    pub fn to_matrix3(&self) -> Matrix3<f32> {
+        Matrix3::from_rows([
+            [self.x_axis.x, self.y_axis.x, self.origin.x],
+            [self.x_axis.y, self.y_axis.y, self.origin.y],
+            [0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn to_matrix4(&self) -> Matrix4<f32> {
+        self.to_matrix3().to_matrix4()
+    }
+}
+
+impl Mul for Transform2D {
+    type Output = Transform2D;
+
+    fn mul(self, child: Transform2D) -> Transform2D {
+        Transform2D {
+            x_axis: self.transform_vector(child.x_axis),
+            y_axis: self.transform_vector(child.y_axis),
+            origin: self.transform_point(child.origin),
+        }
+    }
+}
@@ -0,0 +1,331 @@
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vector2<T: Copy> {
+    pub x: T,
+    pub y: T,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vector3<T: Copy> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vector4<T: Copy> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+    pub w: T,
+}
+
+// Everything that works the same way for every size of vector. Operators
+// with another vector, like `*`, work on each component separately.
+macro_rules! impl_vector {
+    ($name:ident, $size:literal, $($field:ident),+) => {
+        impl<T: Copy> $name<T> {
+            pub fn new($($field: T),+) -> $name<T> {
+                $name { $($field),+ }
+            }
+
+            pub fn from_scalar(s: T) -> $name<T> {
+                $name { $($field: s),+ }
+            }
+        }
+
+        impl<T: Copy + Add<Output = T> + Mul<Output = T>> $name<T> {
+            pub fn dot(self, other: $name<T>) -> T {
+                let products = [$(self.$field * other.$field),+];
+                let mut sum = products[0];
+
+                for product in &products[1..] {
+                    sum = sum + *product;
+                }
+
+                return sum;
+            }
+
+            pub fn length_squared(self) -> T {
+                self.dot(self)
+            }
+        }
+
+        impl<T: Copy + PartialOrd> $name<T> {
+            /// The smaller of each component.
+            pub fn min(self, other: $name<T>) -> $name<T> {
+                $name {
+                    $($field: if other.$field < self.$field { other.$field } else { self.$field }),+
+                }
+            }
+
+            /// The larger of each component.
+            pub fn max(self, other: $name<T>) -> $name<T> {
+                $name {
+                    $($field: if other.$field > self.$field { other.$field } else { self.$field }),+
+                }
+            }
+
+            pub fn clamp(self, min: $name<T>, max: $name<T>) -> $name<T> {
+                self.max(min).min(max)
+            }
+        }
+
+        impl<T: Copy + Add<Output = T>> Add for $name<T> {
+            type Output = $name<T>;
+
+            fn add(self, other: $name<T>) -> $name<T> {
+                $name { $($field: self.$field + other.$field),+ }
+            }
+        }
+
+        impl<T: Copy + Sub<Output = T>> Sub for $name<T> {
+            type Output = $name<T>;
+
+            fn sub(self, other: $name<T>) -> $name<T> {
+                $name { $($field: self.$field - other.$field),+ }
+            }
+        }
+
+        impl<T: Copy + Mul<Output = T>> Mul for $name<T> {
+            type Output = $name<T>;
+
+            fn mul(self, other: $name<T>) -> $name<T> {
+                $name { $($field: self.$field * other.$field),+ }
+            }
+        }
+
+        impl<T: Copy + Div<Output = T>> Div for $name<T> {
+            type Output = $name<T>;
+
+            fn div(self, other: $name<T>) -> $name<T> {
+                $name { $($field: self.$field / other.$field),+ }
+            }
+        }
+
+        impl<T: Copy + Mul<Output = T>> Mul<T> for $name<T> {
+            type Output = $name<T>;
+
+            fn mul(self, scalar: T) -> $name<T> {
+                $name { $($field: self.$field * scalar),+ }
+            }
+        }
+
+        impl<T: Copy + Div<Output = T>> Div<T> for $name<T> {
+            type Output = $name<T>;
+
+            fn div(self, scalar: T) -> $name<T> {
+                $name { $($field: self.$field / scalar),+ }
+            }
+        }
+
+        impl<T: Copy + Neg<Output = T>> Neg for $name<T> {
+            type Output = $name<T>;
+
+            fn neg(self) -> $name<T> {
+                $name { $($field: -self.$field),+ }
+            }
+        }
+
+        impl<T: Copy + Add<Output = T>> AddAssign for $name<T> {
+            fn add_assign(&mut self, other: $name<T>) {
+                *self = *self + other;
+            }
+        }
+
+        impl<T: Copy + Sub<Output = T>> SubAssign for $name<T> {
+            fn sub_assign(&mut self, other: $name<T>) {
+                *self = *self - other;
+            }
+        }
+
+        impl<T: Copy + Mul<Output = T>> MulAssign<T> for $name<T> {
+            fn mul_assign(&mut self, scalar: T) {
+                *self = *self * scalar;
+            }
+        }
+
+        impl<T: Copy + Div<Output = T>> DivAssign<T> for $name<T> {
+            fn div_assign(&mut self, scalar: T) {
+                *self = *self / scalar;
+            }
+        }
+
+        impl<T: Copy> From<[T; $size]> for $name<T> {
+            fn from([$($field),+]: [T; $size]) -> $name<T> {
+                $name { $($field),+ }
+            }
+        }
+
+        impl<T: Copy> From<$name<T>> for [T; $size] {
+            fn from(vector: $name<T>) -> [T; $size] {
+                [$(vector.$field),+]
+            }
+        }
+
+        impl_vector!(@float $name, f32, $($field),+);
+        impl_vector!(@float $name, f64, $($field),+);
+    };
+
+    (@float $name:ident, $float:ty, $($field:ident),+) => {
+        impl $name<$float> {
+            pub fn length(self) -> $float {
+                self.length_squared().sqrt()
+            }
+
+            /// Returns a vector pointing the same way with a length of 1. A
+            /// vector with no length stays as it is, instead of becoming NaN.
+            pub fn normalize(self) -> $name<$float> {
+                let length = self.length();
+
+                if length == 0.0 {
+                    return self;
+                }
+
+                return self / length;
+            }
+
+            pub fn distance(self, other: $name<$float>) -> $float {
+                (other - self).length()
+            }
+
+            /// `t` is 0 at `self` and 1 at `other`, and it isn't clamped.
+            pub fn lerp(self, other: $name<$float>, t: $float) -> $name<$float> {
+                self + (other - self) * t
+            }
+
+            /// The angle between the two vectors in radians, from 0 to pi.
+            pub fn angle_between(self, other: $name<$float>) -> $float {
+                let lengths = self.length() * other.length();
+
+                if lengths == 0.0 {
+                    return 0.0;
+                }
+
+                return (self.dot(other) / lengths).clamp(-1.0, 1.0).acos();
+            }
+
+            /// Bounces the vector off a surface. `normal` should have a
+            /// length of 1.
+            pub fn reflect(self, normal: $name<$float>) -> $name<$float> {
+                self - normal * (2.0 * self.dot(normal))
+            }
+        }
+
+        impl Mul<$name<$float>> for $float {
+            type Output = $name<$float>;
+
+            fn mul(self, vector: $name<$float>) -> $name<$float> {
+                vector * self
+            }
+        }
+    };
+}
+
+impl_vector!(Vector2, 2, x, y);
+impl_vector!(Vector3, 3, x, y, z);
+impl_vector!(Vector4, 4, x, y, z, w);
+
+impl<T: Copy + Mul<Output = T> + Sub<Output = T>> Vector2<T> {
+    /// The z of the 3D cross product, which is positive when `other` is
+    /// clockwise from `self` in screen coordinates.
+    pub fn cross(self, other: Vector2<T>) -> T {
+        self.x * other.y - self.y * other.x
+    }
+}
+
+impl<T: Copy + Neg<Output = T>> Vector2<T> {
+    /// Turns the vector a quarter turn, clockwise in screen coordinates.
+    pub fn perpendicular(self) -> Vector2<T> {
+        Vector2 {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+}
+
+macro_rules! impl_vector2_float {
+    ($float:ty) => {
+        impl Vector2<$float> {
+            /// A vector with a length of 1, `angle` radians from the x axis.
+            pub fn from_angle(angle: $float) -> Vector2<$float> {
+                Vector2 {
+                    x: angle.cos(),
+                    y: angle.sin(),
+                }
+            }
+
+            /// The angle from the x axis in radians, from -pi to pi.
+            pub fn angle(self) -> $float {
+                self.y.atan2(self.x)
+            }
+
+            /// Rotates by `angle` radians, which is clockwise in screen
+            /// coordinates since y points down.
+            pub fn rotate(self, angle: $float) -> Vector2<$float> {
+                let (sin, cos) = angle.sin_cos();
+
+                Vector2 {
+                    x: self.x * cos - self.y * sin,
+                    y: self.x * sin + self.y * cos,
+                }
+            }
+        }
+    };
+}
+
+impl_vector2_float!(f32);
+impl_vector2_float!(f64);
+
+impl<T: Copy + Mul<Output = T> + Sub<Output = T>> Vector3<T> {
+    pub fn cross(self, other: Vector3<T>) -> Vector3<T> {
+        Vector3 {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+}
+
+impl<T: Copy> From<(T, T)> for Vector2<T> {
+    fn from((x, y): (T, T)) -> Vector2<T> {
+        Vector2 { x, y }
+    }
+}
+
+impl<T: Copy> From<Vector2<T>> for (T, T) {
+    fn from(vector: Vector2<T>) -> (T, T) {
+        (vector.x, vector.y)
+    }
+}
+
+impl<T: Copy> From<(T, T, T)> for Vector3<T> {
+    fn from((x, y, z): (T, T, T)) -> Vector3<T> {
+        Vector3 { x, y, z }
+    }
+}
+
+impl<T: Copy> From<Vector3<T>> for (T, T, T) {
+    fn from(vector: Vector3<T>) -> (T, T, T) {
+        (vector.x, vector.y, vector.z)
+    }
+}
+
+impl<T: Copy> From<(T, T, T, T)> for Vector4<T> {
+    fn from((x, y, z, w): (T, T, T, T)) -> Vector4<T> {
+        Vector4 { x, y, z, w }
+    }
+}
+
+impl<T: Copy> From<Vector4<T>> for (T, T, T, T) {
+    fn from(vector: Vector4<T>) -> (T, T, T, T) {
+        (vector.x, vector.y, vector.z, vector.w)
+    }
+}
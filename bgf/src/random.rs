@@ -0,0 +1,295 @@
+//! Random numbers that come out the same on every platform for the same
+//! seed, for things like replays and seeded levels, plus noise for
+//! procedural generation.
+
+mod noise;
+
+pub use noise::{Fbm, Noise2D, Perlin, Simplex, ValueNoise};
+
+use crate::graphics::Color;
+use crate::math::Vector2;
+use std::ops::{Range, RangeInclusive};
+
+// From the reference PCG implementation.
+const MULTIPLIER: u64 = 6364136223846793005;
+const DEFAULT_STREAM: u64 = 1442695040888963407;
+
+/// A PCG32 random number generator. It's small, fast, and its whole state
+/// can be saved (with the `serde` feature) and picked up again later.
+///
+/// It isn't any good for cryptography. It also implements `rand::RngCore`,
+/// so everything in `rand` that takes an `Rng` works with it too.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Random {
+    state: u64,
+    increment: u64,
+}
+
+/// Ranges that `Random::range` can pick from. Integer ranges are picked from
+/// evenly, without the bias of using `%`.
+pub trait RandomRange {
+    type Output;
+
+    fn sample(&self, random: &mut Random) -> Self::Output;
+}
+
+macro_rules! impl_integer_range {
+    ($($int:ty => $unsigned:ty),*) => {
+        $(
+            impl RandomRange for Range<$int> {
+                type Output = $int;
+
+                /// Panics if the range is empty.
+                fn sample(&self, random: &mut Random) -> $int {
+                    assert!(self.start < self.end, "can't pick from an empty range");
+
+                    let size = self.end.wrapping_sub(self.start) as $unsigned as u64;
+                    return self.start.wrapping_add(random.below(size) as $int);
+                }
+            }
+
+            impl RandomRange for RangeInclusive<$int> {
+                type Output = $int;
+
+                /// Panics if the range is empty.
+                fn sample(&self, random: &mut Random) -> $int {
+                    let (start, end) = (*self.start(), *self.end());
+                    assert!(start <= end, "can't pick from an empty range");
+
+                    // The size only overflows for the whole range of a 64
+                    // bit type, where any number will do.
+                    let size = (end.wrapping_sub(start) as $unsigned as u64).wrapping_add(1);
+
+                    if size == 0 {
+                        return random.next_u64() as $int;
+                    }
+
+                    return start.wrapping_add(random.below(size) as $int);
+                }
+            }
+        )*
+    };
+}
+
+impl_integer_range!(
+    i8 => u8,
+    i16 => u16,
+    i32 => u32,
+    i64 => u64,
+    isize => usize,
+    u8 => u8,
+    u16 => u16,
+    u32 => u32,
+    u64 => u64,
+    usize => usize
+);
+
+impl RandomRange for Range<f32> {
+    type Output = f32;
+
+    fn sample(&self, random: &mut Random) -> f32 {
+        self.start + (self.end - self.start) * random.next_f32()
+    }
+}
+
+impl RandomRange for Range<f64> {
+    type Output = f64;
+
+    fn sample(&self, random: &mut Random) -> f64 {
+        self.start + (self.end - self.start) * random.next_f64()
+    }
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        Random::with_stream(seed, DEFAULT_STREAM)
+    }
+
+    /// Generators with the same seed but different streams give completely
+    /// different numbers, so one seed can give, say, a generator for the
+    /// level and another for the enemies.
+    pub fn with_stream(seed: u64, stream: u64) -> Random {
+        let mut random = Random {
+            state: 0,
+            increment: (stream << 1) | 1,
+        };
+
+        random.step();
+        random.state = random.state.wrapping_add(seed);
+        random.step();
+
+        return random;
+    }
+
+    fn step(&mut self) {
+        self.state = self
+            .state
+            .wrapping_mul(MULTIPLIER)
+            .wrapping_add(self.increment);
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.step();
+
+        let shifted = (((old >> 18) ^ old) >> 27) as u32;
+        return shifted.rotate_right((old >> 59) as u32);
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let high = self.next_u32() as u64;
+        return (high << 32) | self.next_u32() as u64;
+    }
+
+    /// From 0 up to, but not including, 1.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// From 0 up to, but not including, 1.
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // A number from 0 up to `limit`, without any bias towards small numbers.
+    fn below(&mut self, limit: u64) -> u64 {
+        // Numbers below this would make the smaller results come up more
+        // often, so they get thrown away.
+        let threshold = limit.wrapping_neg() % limit;
+
+        loop {
+            let value = self.next_u64();
+
+            if value >= threshold {
+                return value % limit;
+            }
+        }
+    }
+
+    /// Picks from a range like `0..10`, `1..=6` or `0.0..1.0`.
+    pub fn range<R: RandomRange>(&mut self, range: R) -> R::Output {
+        range.sample(self)
+    }
+
+    /// True `probability` of the time, from 0 to 1.
+    pub fn chance(&mut self, probability: f64) -> bool {
+        self.next_f64() < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+
+        return Some(&items[self.range(0..items.len())]);
+    }
+
+    /// Picks an index, where each one comes up in proportion to its weight.
+    /// Returns `None` if there aren't any weights above 0. Negative weights
+    /// count as 0.
+    pub fn weighted_index(&mut self, weights: &[f64]) -> Option<usize> {
+        let total: f64 = weights.iter().map(|weight| weight.max(0.0)).sum();
+
+        if total <= 0.0 || !total.is_finite() {
+            return None;
+        }
+
+        let mut target = self.next_f64() * total;
+
+        for (index, weight) in weights.iter().enumerate() {
+            let weight = weight.max(0.0);
+
+            if target < weight {
+                return Some(index);
+            }
+
+            target -= weight;
+        }
+
+        // Rounding can leave a tiny bit of the target over, so that goes to
+        // the last item that could've been picked.
+        return weights.iter().rposition(|weight| *weight > 0.0);
+    }
+
+    /// Like `weighted_index`, but with the weights next to the items.
+    pub fn choose_weighted<'a, T>(&mut self, items: &'a [(T, f64)]) -> Option<&'a T> {
+        let weights: Vec<f64> = items.iter().map(|(_, weight)| *weight).collect();
+        return self.weighted_index(&weights).map(|index| &items[index].0);
+    }
+
+    /// Puts the items in a random order, with every order equally likely.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.range(0..=i);
+            items.swap(i, j);
+        }
+    }
+
+    // Picks points in the square around the circle until one lands inside.
+    // Going through an angle would need `sin` and `cos`, which can round
+    // differently on different platforms, where this is plain arithmetic.
+    fn in_unit_circle(&mut self) -> Vector2<f32> {
+        loop {
+            let x = self.next_f32() * 2.0 - 1.0;
+            let y = self.next_f32() * 2.0 - 1.0;
+            let point = Vector2::new(x, y);
+
+            if point.length_squared() <= 1.0 {
+                return point;
+            }
+        }
+    }
+
+    /// A point anywhere inside a circle around (0, 0), with every point
+    /// equally likely.
+    pub fn in_circle(&mut self, radius: f32) -> Vector2<f32> {
+        self.in_unit_circle() * radius
+    }
+
+    /// A point on the edge of a circle around (0, 0).
+    pub fn on_circle(&mut self, radius: f32) -> Vector2<f32> {
+        loop {
+            let point = self.in_unit_circle();
+            let length = point.length();
+
+            // Points right in the middle barely have a direction.
+            if length > 1e-3 {
+                return point / length * radius;
+            }
+        }
+    }
+
+    /// A fully opaque color, with each channel picked separately.
+    pub fn color(&mut self) -> Color {
+        Color::rgb(self.next_f32(), self.next_f32(), self.next_f32())
+    }
+
+    /// A fully opaque color with a random hue. Keeping the saturation and
+    /// value the same gives colors that look good next to each other.
+    pub fn hue_color(&mut self, saturation: f32, value: f32) -> Color {
+        Color::from_hsv(self.next_f32() * 360.0, saturation, value, 1.0)
+    }
+}
+
+impl rand::RngCore for Random {
+    fn next_u32(&mut self) -> u32 {
+        Random::next_u32(self)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        Random::next_u64(self)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        for chunk in dest.chunks_mut(4) {
+            let bytes = Random::next_u32(self).to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        return Ok(());
+    }
+}
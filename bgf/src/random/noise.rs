@@ -0,0 +1,233 @@
+use super::Random;
+
+/// Smooth 2D noise. Nearby points give similar values, which go from about
+/// -1 to 1.
+pub trait Noise2D {
+    fn get(&self, x: f32, y: f32) -> f32;
+}
+
+// The corners of the grid get their gradients and values from this. It's
+// doubled up so that looking up one entry plus another never goes off the
+// end.
+#[derive(Clone, Debug)]
+struct Permutation {
+    table: [u8; 512],
+}
+
+impl Permutation {
+    fn new(seed: u64) -> Permutation {
+        let mut values: Vec<u8> = (0..=255).collect();
+        Random::new(seed).shuffle(&mut values);
+
+        let mut table = [0; 512];
+        for (i, entry) in table.iter_mut().enumerate() {
+            *entry = values[i % 256];
+        }
+
+        return Permutation { table };
+    }
+
+    fn hash(&self, x: i32, y: i32) -> u8 {
+        let first = self.table[(x & 255) as usize] as usize;
+        return self.table[first + (y & 255) as usize];
+    }
+}
+
+// Only uses the eight directions that don't need any trig, so the results
+// are exactly the same everywhere.
+fn gradient(hash: u8, x: f32, y: f32) -> f32 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+// Eases from 0 to 1 with no sudden changes in slope, so the grid doesn't
+// show.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6.0 - 15.0) + 10.0)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+/// Classic Perlin noise. It's 0 on every whole number.
+#[derive(Clone, Debug)]
+pub struct Perlin {
+    permutation: Permutation,
+}
+
+impl Perlin {
+    pub fn new(seed: u64) -> Perlin {
+        Perlin {
+            permutation: Permutation::new(seed),
+        }
+    }
+}
+
+impl Noise2D for Perlin {
+    fn get(&self, x: f32, y: f32) -> f32 {
+        let (cell_x, cell_y) = (x.floor(), y.floor());
+        let (ix, iy) = (cell_x as i32, cell_y as i32);
+        let (fx, fy) = (x - cell_x, y - cell_y);
+
+        let corner = |dx: i32, dy: i32| {
+            let hash = self.permutation.hash(ix + dx, iy + dy);
+            gradient(hash, fx - dx as f32, fy - dy as f32)
+        };
+
+        let (u, v) = (fade(fx), fade(fy));
+        let top = lerp(corner(0, 0), corner(1, 0), u);
+        let bottom = lerp(corner(0, 1), corner(1, 1), u);
+
+        return lerp(top, bottom, v);
+    }
+}
+
+/// Simplex noise. It looks a lot like Perlin noise, but without the lines
+/// along the grid.
+#[derive(Clone, Debug)]
+pub struct Simplex {
+    permutation: Permutation,
+}
+
+impl Simplex {
+    pub fn new(seed: u64) -> Simplex {
+        Simplex {
+            permutation: Permutation::new(seed),
+        }
+    }
+}
+
+// For going between the square grid and the grid of triangles, which are
+// (sqrt(3) - 1) / 2 and (3 - sqrt(3)) / 6.
+const SKEW: f32 = 0.36602542;
+const UNSKEW: f32 = 0.21132487;
+
+impl Noise2D for Simplex {
+    fn get(&self, x: f32, y: f32) -> f32 {
+        let skew = (x + y) * SKEW;
+        let (cell_x, cell_y) = ((x + skew).floor(), (y + skew).floor());
+        let (ix, iy) = (cell_x as i32, cell_y as i32);
+
+        let unskew = (cell_x + cell_y) * UNSKEW;
+        let (x0, y0) = (x - (cell_x - unskew), y - (cell_y - unskew));
+
+        // Which of the two triangles in the cell the point is in.
+        let (step_x, step_y) = if x0 > y0 { (1, 0) } else { (0, 1) };
+
+        let corners = [
+            (0, 0, x0, y0),
+            (
+                step_x,
+                step_y,
+                x0 - step_x as f32 + UNSKEW,
+                y0 - step_y as f32 + UNSKEW,
+            ),
+            (1, 1, x0 - 1.0 + 2.0 * UNSKEW, y0 - 1.0 + 2.0 * UNSKEW),
+        ];
+
+        let mut total = 0.0;
+
+        for (dx, dy, x, y) in corners {
+            let falloff = 0.5 - x * x - y * y;
+
+            if falloff > 0.0 {
+                // Not powi, which isn't guaranteed to round the same
+                // everywhere.
+                let squared = falloff * falloff;
+                let hash = self.permutation.hash(ix + dx, iy + dy);
+                total += squared * squared * gradient(hash, x, y);
+            }
+        }
+
+        // Scales it out to about -1 to 1.
+        return total * 70.0;
+    }
+}
+
+/// Smoothly blends between random values at every whole number. It's
+/// blockier than Perlin noise, but cheaper.
+#[derive(Clone, Debug)]
+pub struct ValueNoise {
+    permutation: Permutation,
+}
+
+impl ValueNoise {
+    pub fn new(seed: u64) -> ValueNoise {
+        ValueNoise {
+            permutation: Permutation::new(seed),
+        }
+    }
+}
+
+impl Noise2D for ValueNoise {
+    fn get(&self, x: f32, y: f32) -> f32 {
+        let (cell_x, cell_y) = (x.floor(), y.floor());
+        let (ix, iy) = (cell_x as i32, cell_y as i32);
+
+        let corner =
+            |dx: i32, dy: i32| self.permutation.hash(ix + dx, iy + dy) as f32 / 255.0 * 2.0 - 1.0;
+
+        let (u, v) = (fade(x - cell_x), fade(y - cell_y));
+        let top = lerp(corner(0, 0), corner(1, 0), u);
+        let bottom = lerp(corner(0, 1), corner(1, 1), u);
+
+        return lerp(top, bottom, v);
+    }
+}
+
+/// Fractal Brownian motion: adds up `octaves` layers of noise, each one
+/// `lacunarity` times finer and `gain` times weaker than the last, for
+/// things like terrain that have detail at every scale.
+#[derive(Clone, Debug)]
+pub struct Fbm<N: Noise2D> {
+    pub noise: N,
+    pub octaves: u32,
+    pub lacunarity: f32,
+    pub gain: f32,
+}
+
+impl<N: Noise2D> Fbm<N> {
+    /// Uses a lacunarity of 2 and a gain of 0.5.
+    pub fn new(noise: N, octaves: u32) -> Fbm<N> {
+        Fbm {
+            noise,
+            octaves,
+            lacunarity: 2.0,
+            gain: 0.5,
+        }
+    }
+}
+
+impl<N: Noise2D> Noise2D for Fbm<N> {
+    /// Divided by the total of every layer's strength, so it stays in about
+    /// -1 to 1 however many octaves there are.
+    fn get(&self, x: f32, y: f32) -> f32 {
+        let mut total = 0.0;
+        let mut frequency = 1.0;
+        let mut amplitude = 1.0;
+        let mut amplitudes = 0.0;
+
+        for _ in 0..self.octaves {
+            total += self.noise.get(x * frequency, y * frequency) * amplitude;
+            amplitudes += amplitude;
+
+            frequency *= self.lacunarity;
+            amplitude *= self.gain;
+        }
+
+        if amplitudes == 0.0 {
+            return 0.0;
+        }
+
+        return total / amplitudes;
+    }
+}
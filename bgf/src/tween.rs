@@ -0,0 +1,358 @@
+//! Animating values over time, for things like menus sliding in or buttons
+//! fading. A `TweenValue` holds the value being animated, a `Tween` moves it
+//! towards a target, and a `TweenManager` keeps every running tween going
+//! when it's updated with the frame's delta time. Read the value back with
+//! `TweenValue::get` when drawing.
+//!
+//! Tweens can be put one after the other with a `Sequence`, or run together
+//! with a `Parallel`, and either of those can be put in the other.
+//!
+//! Scenes usually don't need a manager of their own. `tween::add` puts an
+//! animation on a shared one, which `SceneManager::update_active` moves along
+//! before updating the scene.
+
+mod easing;
+mod group;
+
+pub use easing::Easing;
+pub use group::{Parallel, Sequence};
+
+use crate::graphics::Color;
+use crate::math::{Transform2D, Vector2, Vector3, Vector4};
+use std::cell::{Cell, RefCell};
+use std::f32::consts::PI;
+use std::rc::Rc;
+
+/// Anything that can be blended between two values. `t` goes from 0 to 1,
+/// but can go a bit past either end with easings like `Easing::BackOut`.
+pub trait Tweenable: Copy {
+    fn interpolate(&self, to: &Self, t: f32) -> Self;
+}
+
+impl Tweenable for f32 {
+    fn interpolate(&self, to: &f32, t: f32) -> f32 {
+        self + (to - self) * t
+    }
+}
+
+impl Tweenable for f64 {
+    fn interpolate(&self, to: &f64, t: f32) -> f64 {
+        self + (to - self) * t as f64
+    }
+}
+
+macro_rules! impl_tweenable_vector {
+    ($($name:ident),*) => {
+        $(
+            impl Tweenable for $name<f32> {
+                fn interpolate(&self, to: &$name<f32>, t: f32) -> $name<f32> {
+                    self.lerp(*to, t)
+                }
+            }
+
+            impl Tweenable for $name<f64> {
+                fn interpolate(&self, to: &$name<f64>, t: f32) -> $name<f64> {
+                    self.lerp(*to, t as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_tweenable_vector!(Vector2, Vector3, Vector4);
+
+impl Tweenable for Color {
+    fn interpolate(&self, to: &Color, t: f32) -> Color {
+        self.lerp(to, t)
+    }
+}
+
+impl Tweenable for Transform2D {
+    /// Blends the position, rotation and scale separately, so the transform
+    /// doesn't shrink halfway through a turn. The rotation goes the short way
+    /// around.
+    fn interpolate(&self, to: &Transform2D, t: f32) -> Transform2D {
+        let turn = (to.get_rotation() - self.get_rotation() + PI).rem_euclid(PI * 2.0) - PI;
+
+        Transform2D::new(
+            self.get_position().lerp(to.get_position(), t),
+            self.get_rotation() + turn * t,
+            self.get_scale().lerp(to.get_scale(), t),
+        )
+    }
+}
+
+/// A value that tweens can animate. Clones share the same value, so keep
+/// one around to read from and give the tweens a reference to it.
+#[derive(Clone, Debug, Default)]
+pub struct TweenValue<T: Copy> {
+    value: Rc<Cell<T>>,
+}
+
+impl<T: Copy> TweenValue<T> {
+    pub fn new(value: T) -> TweenValue<T> {
+        TweenValue {
+            value: Rc::new(Cell::new(value)),
+        }
+    }
+
+    pub fn get(&self) -> T {
+        self.value.get()
+    }
+
+    /// Tweens that are running on the value will overwrite this on their
+    /// next update.
+    pub fn set(&self, value: T) {
+        self.value.set(value);
+    }
+}
+
+/// Something that plays out over time, and that a `TweenManager` can run.
+pub trait Animation {
+    /// Moves it along by `seconds`. Returns `None` while it's still going.
+    /// Once it's done, returns how many of the seconds were left over, so
+    /// whatever comes next in a `Sequence` can use them.
+    fn advance(&mut self, seconds: f64) -> Option<f64>;
+}
+
+/// Moves a `TweenValue` to `to` over `duration` seconds.
+pub struct Tween<T: Tweenable> {
+    value: TweenValue<T>,
+    from: Option<T>,
+    to: T,
+    // Where this play started from. It's taken when the delay's over, unless
+    // `from` was given.
+    start: Option<T>,
+    duration: f64,
+    delay: f64,
+    easing: Easing,
+    // `None` repeats forever.
+    repeats: Option<u32>,
+    yoyo: bool,
+    elapsed: f64,
+    finished: bool,
+    on_complete: Option<Box<dyn FnMut()>>,
+}
+
+impl<T: Tweenable> Tween<T> {
+    /// Starts from whatever the value is when the tween starts, which is
+    /// after the delay, or when it's reached in a `Sequence`.
+    pub fn new(value: &TweenValue<T>, to: T, duration: f64) -> Tween<T> {
+        Tween {
+            value: value.clone(),
+            from: None,
+            to,
+            start: None,
+            duration,
+            delay: 0.0,
+            easing: Easing::Linear,
+            repeats: Some(0),
+            yoyo: false,
+            elapsed: 0.0,
+            finished: false,
+            on_complete: None,
+        }
+    }
+
+    /// Starts from `from` instead of the value's current value.
+    pub fn from(mut self, from: T) -> Tween<T> {
+        self.from = Some(from);
+        self
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Tween<T> {
+        self.easing = easing;
+        self
+    }
+
+    /// Waits this many seconds before starting. The delay only happens once,
+    /// not before every repeat.
+    pub fn with_delay(mut self, delay: f64) -> Tween<T> {
+        self.delay = delay;
+        self
+    }
+
+    /// Plays it this many more times after the first.
+    pub fn with_repeats(mut self, repeats: u32) -> Tween<T> {
+        self.repeats = Some(repeats);
+        self
+    }
+
+    /// Never finishes, so it'll hold up a `Sequence` and never call its
+    /// completion callback.
+    pub fn repeat_forever(mut self) -> Tween<T> {
+        self.repeats = None;
+        self
+    }
+
+    /// Goes back to the start on every other repeat, instead of jumping back.
+    pub fn with_yoyo(mut self) -> Tween<T> {
+        self.yoyo = true;
+        self
+    }
+
+    pub fn on_complete(mut self, callback: impl FnMut() + 'static) -> Tween<T> {
+        self.on_complete = Some(Box::new(callback));
+        self
+    }
+
+    fn set_progress(&self, start: T, cycle: u64, progress: f64) {
+        let backwards = self.yoyo && cycle % 2 == 1;
+        let progress = if backwards { 1.0 - progress } else { progress };
+
+        let t = self.easing.apply(progress as f32);
+        self.value.set(start.interpolate(&self.to, t));
+    }
+}
+
+impl<T: Tweenable> Animation for Tween<T> {
+    fn advance(&mut self, seconds: f64) -> Option<f64> {
+        if self.finished {
+            return Some(seconds);
+        }
+
+        self.elapsed += seconds;
+
+        if self.elapsed < self.delay {
+            return None;
+        }
+
+        let start = *self
+            .start
+            .get_or_insert_with(|| self.from.unwrap_or(self.value.get()));
+        let playing = self.elapsed - self.delay;
+
+        // A tween with no duration would repeat forever in no time, so it
+        // just jumps to the end.
+        let plays = match self.repeats {
+            _ if self.duration <= 0.0 => Some(1),
+            Some(repeats) => Some(repeats as u64 + 1),
+            None => None,
+        };
+
+        if let Some(plays) = plays {
+            let total = self.duration.max(0.0) * plays as f64;
+
+            if playing >= total {
+                self.set_progress(start, plays - 1, 1.0);
+                self.finished = true;
+
+                if let Some(callback) = &mut self.on_complete {
+                    callback();
+                }
+
+                return Some((playing - total).min(seconds));
+            }
+        }
+
+        let cycles = playing / self.duration;
+        self.set_progress(start, cycles as u64, cycles.fract());
+
+        return None;
+    }
+}
+
+/// Tells a `TweenManager` which animation to cancel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TweenId(u64);
+
+thread_local! {
+    // Every manager takes IDs from here, so that animations can move from one
+    // to another without two of them ending up with the same ID.
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+
+    // The manager that `SceneManager` updates.
+    static SHARED: RefCell<TweenManager> = RefCell::new(TweenManager::new());
+}
+
+/// Runs every animation that's been added, and drops them once they finish.
+pub struct TweenManager {
+    animations: Vec<(TweenId, Box<dyn Animation>)>,
+}
+
+impl Default for TweenManager {
+    fn default() -> TweenManager {
+        TweenManager::new()
+    }
+}
+
+impl TweenManager {
+    pub fn new() -> TweenManager {
+        TweenManager {
+            animations: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, animation: impl Animation + 'static) -> TweenId {
+        let id = TweenId(NEXT_ID.with(|next| next.replace(next.get() + 1)));
+
+        self.animations.push((id, Box::new(animation)));
+        return id;
+    }
+
+    /// Moves everything along by the frame's delta time, in seconds.
+    /// Completion callbacks get called from in here.
+    pub fn update(&mut self, delta_time: f64) {
+        self.animations
+            .retain_mut(|(_, animation)| animation.advance(delta_time).is_none());
+    }
+
+    /// Stops the animation where it is, without calling its completion
+    /// callback. Returns false if it had already finished.
+    pub fn cancel(&mut self, id: TweenId) -> bool {
+        let count = self.animations.len();
+        self.animations.retain(|(other, _)| *other != id);
+
+        return self.animations.len() != count;
+    }
+
+    pub fn is_running(&self, id: TweenId) -> bool {
+        self.animations.iter().any(|(other, _)| *other == id)
+    }
+
+    /// Cancels everything.
+    pub fn clear(&mut self) {
+        self.animations.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.animations.is_empty()
+    }
+}
+
+/// Adds an animation to the shared manager, which `SceneManager::update_active`
+/// keeps going.
+pub fn add(animation: impl Animation + 'static) -> TweenId {
+    SHARED.with(|shared| shared.borrow_mut().add(animation))
+}
+
+/// Moves the shared manager along. `SceneManager::update_active` calls this,
+/// so only games that don't use it need to.
+pub fn update(delta_time: f64) {
+    // It's taken out while it runs, so that completion callbacks can add more
+    // animations. Those get put back in with the rest afterwards.
+    let mut running = SHARED.with(|shared| std::mem::take(&mut *shared.borrow_mut()));
+    running.update(delta_time);
+
+    SHARED.with(|shared| {
+        let mut shared = shared.borrow_mut();
+        let added = std::mem::replace(&mut *shared, running);
+        shared.animations.extend(added.animations);
+    });
+}
+
+/// Cancels an animation on the shared manager. Completion callbacks can only
+/// cancel animations that other callbacks added in the same update.
+pub fn cancel(id: TweenId) -> bool {
+    SHARED.with(|shared| shared.borrow_mut().cancel(id))
+}
+
+pub fn is_running(id: TweenId) -> bool {
+    SHARED.with(|shared| shared.borrow().is_running(id))
+}
+
+/// Cancels everything on the shared manager, like when leaving a scene
+/// whose animations shouldn't finish.
+pub fn clear() {
+    SHARED.with(|shared| shared.borrow_mut().clear());
+}
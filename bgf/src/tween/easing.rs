@@ -0,0 +1,166 @@
+use std::f32::consts::PI;
+
+/// The standard Robert Penner easing curves. Each one takes how far along a
+/// tween is, from 0 to 1, and gives back how far along the value should be.
+/// Most of them give 0 for 0 and 1 for 1, but `Back` and `Elastic` go past
+/// the ends on the way.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    SineIn,
+    SineOut,
+    SineInOut,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    QuartIn,
+    QuartOut,
+    QuartInOut,
+    QuintIn,
+    QuintOut,
+    QuintInOut,
+    ExpoIn,
+    ExpoOut,
+    ExpoInOut,
+    CircIn,
+    CircOut,
+    CircInOut,
+    BackIn,
+    BackOut,
+    BackInOut,
+    ElasticIn,
+    ElasticOut,
+    ElasticInOut,
+    BounceIn,
+    BounceOut,
+    BounceInOut,
+    /// For curves that aren't on the list.
+    Custom(fn(f32) -> f32),
+}
+
+// How far past the ends the Back curves go.
+const BACK_OVERSHOOT: f32 = 1.70158;
+
+fn power_in(t: f32, power: i32) -> f32 {
+    t.powi(power)
+}
+
+fn power_out(t: f32, power: i32) -> f32 {
+    1.0 - (1.0 - t).powi(power)
+}
+
+fn power_in_out(t: f32, power: i32) -> f32 {
+    if t < 0.5 {
+        2.0f32.powi(power - 1) * t.powi(power)
+    } else {
+        1.0 - (2.0 - 2.0 * t).powi(power) / 2.0
+    }
+}
+
+fn bounce_out(t: f32) -> f32 {
+    const N: f32 = 7.5625;
+    const D: f32 = 2.75;
+
+    if t < 1.0 / D {
+        N * t * t
+    } else if t < 2.0 / D {
+        let t = t - 1.5 / D;
+        N * t * t + 0.75
+    } else if t < 2.5 / D {
+        let t = t - 2.25 / D;
+        N * t * t + 0.9375
+    } else {
+        let t = t - 2.625 / D;
+        N * t * t + 0.984375
+    }
+}
+
+impl Easing {
+    /// `t` gets clamped to 0 to 1 first.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+
+            Easing::SineIn => 1.0 - (t * PI / 2.0).cos(),
+            Easing::SineOut => (t * PI / 2.0).sin(),
+            Easing::SineInOut => -((PI * t).cos() - 1.0) / 2.0,
+
+            Easing::QuadIn => power_in(t, 2),
+            Easing::QuadOut => power_out(t, 2),
+            Easing::QuadInOut => power_in_out(t, 2),
+            Easing::CubicIn => power_in(t, 3),
+            Easing::CubicOut => power_out(t, 3),
+            Easing::CubicInOut => power_in_out(t, 3),
+            Easing::QuartIn => power_in(t, 4),
+            Easing::QuartOut => power_out(t, 4),
+            Easing::QuartInOut => power_in_out(t, 4),
+            Easing::QuintIn => power_in(t, 5),
+            Easing::QuintOut => power_out(t, 5),
+            Easing::QuintInOut => power_in_out(t, 5),
+
+            // These never quite reach the ends on their own.
+            Easing::ExpoIn if t == 0.0 => 0.0,
+            Easing::ExpoIn => 2.0f32.powf(10.0 * t - 10.0),
+            Easing::ExpoOut if t == 1.0 => 1.0,
+            Easing::ExpoOut => 1.0 - 2.0f32.powf(-10.0 * t),
+            Easing::ExpoInOut if t == 0.0 || t == 1.0 => t,
+            Easing::ExpoInOut if t < 0.5 => 2.0f32.powf(20.0 * t - 10.0) / 2.0,
+            Easing::ExpoInOut => (2.0 - 2.0f32.powf(-20.0 * t + 10.0)) / 2.0,
+
+            Easing::CircIn => 1.0 - (1.0 - t * t).sqrt(),
+            Easing::CircOut => (1.0 - (t - 1.0) * (t - 1.0)).sqrt(),
+            Easing::CircInOut if t < 0.5 => (1.0 - (1.0 - 4.0 * t * t).sqrt()) / 2.0,
+            Easing::CircInOut => ((1.0 - (2.0 - 2.0 * t).powi(2)).sqrt() + 1.0) / 2.0,
+
+            Easing::BackIn => (BACK_OVERSHOOT + 1.0) * t * t * t - BACK_OVERSHOOT * t * t,
+            Easing::BackOut => {
+                let t = t - 1.0;
+                1.0 + (BACK_OVERSHOOT + 1.0) * t * t * t + BACK_OVERSHOOT * t * t
+            }
+            Easing::BackInOut => {
+                let overshoot = BACK_OVERSHOOT * 1.525;
+
+                if t < 0.5 {
+                    (2.0 * t).powi(2) * ((overshoot + 1.0) * 2.0 * t - overshoot) / 2.0
+                } else {
+                    let t = 2.0 * t - 2.0;
+                    (t * t * ((overshoot + 1.0) * t + overshoot) + 2.0) / 2.0
+                }
+            }
+
+            Easing::ElasticIn | Easing::ElasticOut | Easing::ElasticInOut
+                if t == 0.0 || t == 1.0 =>
+            {
+                t
+            }
+            Easing::ElasticIn => {
+                -(2.0f32.powf(10.0 * t - 10.0)) * ((10.0 * t - 10.75) * PI * 2.0 / 3.0).sin()
+            }
+            Easing::ElasticOut => {
+                2.0f32.powf(-10.0 * t) * ((10.0 * t - 0.75) * PI * 2.0 / 3.0).sin() + 1.0
+            }
+            Easing::ElasticInOut => {
+                let wave = ((20.0 * t - 11.125) * PI * 2.0 / 4.5).sin();
+
+                if t < 0.5 {
+                    -(2.0f32.powf(20.0 * t - 10.0) * wave) / 2.0
+                } else {
+                    2.0f32.powf(-20.0 * t + 10.0) * wave / 2.0 + 1.0
+                }
+            }
+
+            Easing::BounceIn => 1.0 - bounce_out(1.0 - t),
+            Easing::BounceOut => bounce_out(t),
+            Easing::BounceInOut if t < 0.5 => (1.0 - bounce_out(1.0 - 2.0 * t)) / 2.0,
+            Easing::BounceInOut => (1.0 + bounce_out(2.0 * t - 1.0)) / 2.0,
+
+            Easing::Custom(curve) => curve(t),
+        }
+    }
+}
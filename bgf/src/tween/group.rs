@@ -0,0 +1,132 @@
+use super::Animation;
+
+/// Plays animations one after the other.
+#[derive(Default)]
+pub struct Sequence {
+    animations: Vec<Box<dyn Animation>>,
+    current: usize,
+    finished: bool,
+    on_complete: Option<Box<dyn FnMut()>>,
+}
+
+impl Sequence {
+    pub fn new() -> Sequence {
+        Sequence::default()
+    }
+
+    pub fn then(mut self, animation: impl Animation + 'static) -> Sequence {
+        self.animations.push(Box::new(animation));
+        self
+    }
+
+    /// Waits this many seconds before going on to the next one.
+    pub fn wait(self, seconds: f64) -> Sequence {
+        self.then(Wait { left: seconds })
+    }
+
+    pub fn on_complete(mut self, callback: impl FnMut() + 'static) -> Sequence {
+        self.on_complete = Some(Box::new(callback));
+        self
+    }
+}
+
+impl Animation for Sequence {
+    fn advance(&mut self, mut seconds: f64) -> Option<f64> {
+        if self.finished {
+            return Some(seconds);
+        }
+
+        // One long frame can get through more than one of them.
+        while let Some(animation) = self.animations.get_mut(self.current) {
+            seconds = animation.advance(seconds)?;
+            self.current += 1;
+        }
+
+        self.finished = true;
+
+        if let Some(callback) = &mut self.on_complete {
+            callback();
+        }
+
+        return Some(seconds);
+    }
+}
+
+/// Plays animations all at once, and finishes when the longest one does.
+#[derive(Default)]
+pub struct Parallel {
+    // Each one goes to `None` once it's finished.
+    animations: Vec<Option<Box<dyn Animation>>>,
+    finished: bool,
+    on_complete: Option<Box<dyn FnMut()>>,
+}
+
+impl Parallel {
+    pub fn new() -> Parallel {
+        Parallel::default()
+    }
+
+    pub fn with(mut self, animation: impl Animation + 'static) -> Parallel {
+        self.animations.push(Some(Box::new(animation)));
+        self
+    }
+
+    pub fn on_complete(mut self, callback: impl FnMut() + 'static) -> Parallel {
+        self.on_complete = Some(Box::new(callback));
+        self
+    }
+}
+
+impl Animation for Parallel {
+    fn advance(&mut self, seconds: f64) -> Option<f64> {
+        if self.finished {
+            return Some(seconds);
+        }
+
+        let mut left_over = seconds;
+
+        for slot in &mut self.animations {
+            let left = match slot {
+                Some(animation) => animation.advance(seconds),
+                None => continue,
+            };
+
+            if let Some(left) = left {
+                left_over = left_over.min(left);
+                *slot = None;
+            }
+        }
+
+        if self.animations.iter().any(Option::is_some) {
+            return None;
+        }
+
+        self.finished = true;
+
+        if let Some(callback) = &mut self.on_complete {
+            callback();
+        }
+
+        return Some(left_over);
+    }
+}
+
+// A gap in a `Sequence`.
+struct Wait {
+    left: f64,
+}
+
+impl Animation for Wait {
+    fn advance(&mut self, seconds: f64) -> Option<f64> {
+        self.left -= seconds;
+
+        if self.left > 0.0 {
+            return None;
+        }
+
+        let left_over = (-self.left).min(seconds);
+        self.left = 0.0;
+
+        return Some(left_over);
+    }
+}
@@ -1,3 +1,19 @@
+mod context;
+mod input;
+pub mod layout;
+mod navigation;
+mod text_edit;
+mod theme;
+mod widgets;
+
+pub use context::{Response, Ui, WidgetId};
+pub use input::{NavAction, NavigationInput, UiInput, UiKey};
+pub use navigation::UiEvent;
+pub use text_edit::{TextFilter, TextInputSettings};
+pub use theme::{Theme, WidgetColors, WidgetSlices};
+
+use crate::math::{Rect, Vector2};
+
 pub struct ButtonChecker {
     mouse_x: f64,
     mouse_y: f64,
@@ -16,9 +32,13 @@ impl ButtonChecker {
         self.mouse_y = y;
     }
 
+    /// Goes by `Rect::contains_point`, so the top and left edges count as
+    /// hovered but the bottom and right ones don't.
     pub fn is_button_hovered(&self, x: f64, y: f64, width: f64, height: f64) -> bool {
-        let (x2, y2) = (width + x, height + y);
+        self.is_rect_hovered(&Rect::new(x, y, width, height))
+    }
 
-        self.mouse_x > x && self.mouse_x < x2 && self.mouse_y > y && self.mouse_y < y2
+    pub fn is_rect_hovered(&self, rect: &Rect<f64>) -> bool {
+        rect.contains_point(Vector2::new(self.mouse_x, self.mouse_y))
     }
 }
@@ -0,0 +1,575 @@
+use super::navigation::{Focusable, UiEvent};
+use super::text_edit::TextEdit;
+use super::{NavAction, Theme, UiInput};
+use crate::graphics::{colors, measure_text, Color, NineSlice, Renderer2D};
+use crate::math::{Rect, Vector2};
+use crate::Window;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+const ROUNDED_RECT_SEGMENTS: u32 = 4;
+
+/// Tells widgets apart from one frame to the next. It comes from the
+/// widget's label, so two widgets with the same label need `Ui::push_id`,
+/// or a `##` in the label: `"Play##main"` shows "Play", but has a different
+/// ID to `"Play##options"`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WidgetId(u64);
+
+// What the mouse is "holding" when it was pressed on nothing at all, so that
+// dragging across widgets doesn't do anything to them.
+const BACKGROUND: WidgetId = WidgetId(0);
+
+impl WidgetId {
+    pub(super) fn child(&self, key: impl Hash) -> WidgetId {
+        let mut hasher = DefaultHasher::new();
+        self.0.hash(&mut hasher);
+        key.hash(&mut hasher);
+
+        return WidgetId(hasher.finish());
+    }
+}
+
+/// What happened to a widget this frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Response {
+    /// The mouse is over it, and isn't busy with another widget.
+    pub hovered: bool,
+    /// It's being held down.
+    pub active: bool,
+    /// It was pressed and let go of, without the mouse leaving it.
+    pub clicked: bool,
+    /// Its value changed.
+    pub changed: bool,
+    /// It has the keyboard and gamepad focus.
+    pub focused: bool,
+}
+
+pub(super) enum Shape {
+    Rect {
+        rect: Rect<f32>,
+        rounding: f32,
+    },
+    Text {
+        position: Vector2<f32>,
+        text: String,
+    },
+    NineSlice {
+        rect: Rect<f32>,
+        slice: NineSlice,
+    },
+}
+
+pub(super) struct DrawCommand {
+    shape: Shape,
+    color: Color,
+    clip: Option<Rect<f32>>,
+}
+
+// An open dropdown list. Widgets underneath it can't be clicked.
+pub(super) struct Popup {
+    pub(super) owner: WidgetId,
+    pub(super) owner_rect: Rect<f32>,
+    pub(super) rect: Rect<f32>,
+}
+
+/// An immediate-mode UI. Every frame, call `begin_frame`, then the widget
+/// functions, which draw the widget and say what happened to it, then
+/// `end_frame` (or `end_frame_with_window`, when the input came from a
+/// `Window`), and finally `render` once the rest of the frame has been
+/// drawn.
+///
+/// Only one widget can be "active" (held down by the mouse) at a time, and it
+/// stays that way until the mouse button is let go, so dragging from one
+/// widget to another never clicks the second one.
+///
+/// Widgets can also be moved between with the keyboard or a gamepad, through
+/// `UiInput::navigation`. Tabbing goes through them in the order they're
+/// added, and directions go to the nearest one that way. Activating the
+/// focused widget is the same as clicking it.
+pub struct Ui {
+    pub(super) theme: Theme,
+    pub(super) input: UiInput,
+    mouse_was_down: bool,
+    /// Whether the mouse button went down or up this frame.
+    pub(super) pressed: bool,
+    pub(super) released: bool,
+    delta_time: f64,
+
+    id_stack: Vec<WidgetId>,
+    pub(super) hot: Option<WidgetId>,
+    last_hot: Option<WidgetId>,
+    hover_time: f64,
+    pub(super) active: Option<WidgetId>,
+    active_seen: bool,
+    // Where the mouse was in the active widget when it was pressed.
+    pub(super) drag_offset: Vector2<f32>,
+    // The text input that has the keyboard, and where its caret is.
+    pub(super) text_edit: Option<TextEdit>,
+    pub(super) copied_text: Option<String>,
+    pub(super) last_widget: Option<WidgetId>,
+    pub(super) popup: Option<Popup>,
+    pub(super) popup_seen: bool,
+    pub(super) mouse_over_panel: bool,
+    pub(super) scroll_offsets: HashMap<WidgetId, f32>,
+    pub(super) clip_stack: Vec<Rect<f32>>,
+    pub(super) panel_stack: Vec<WidgetId>,
+
+    pub(super) nav_focus: Option<WidgetId>,
+    // Only shown once the keyboard or a gamepad has been used, until the
+    // mouse is clicked.
+    pub(super) focus_visible: bool,
+    pub(super) focusables: Vec<Focusable>,
+    pub(super) captured_navigation: Vec<NavAction>,
+    pub(super) events: Vec<UiEvent>,
+
+    commands: Vec<DrawCommand>,
+    // Drawn after everything else, for dropdown lists and tooltips.
+    overlay: Vec<DrawCommand>,
+    pub(super) tooltip: Option<String>,
+}
+
+impl Default for Ui {
+    fn default() -> Ui {
+        Ui::new()
+    }
+}
+
+impl Ui {
+    pub fn new() -> Ui {
+        Ui {
+            theme: Theme::default(),
+            input: UiInput::default(),
+            mouse_was_down: false,
+            pressed: false,
+            released: false,
+            delta_time: 0.0,
+            id_stack: Vec::new(),
+            hot: None,
+            last_hot: None,
+            hover_time: 0.0,
+            active: None,
+            active_seen: false,
+            drag_offset: Vector2::default(),
+            text_edit: None,
+            copied_text: None,
+            last_widget: None,
+            popup: None,
+            popup_seen: false,
+            mouse_over_panel: false,
+            scroll_offsets: HashMap::new(),
+            clip_stack: Vec::new(),
+            panel_stack: Vec::new(),
+            nav_focus: None,
+            focus_visible: false,
+            focusables: Vec::new(),
+            captured_navigation: Vec::new(),
+            events: Vec::new(),
+            commands: Vec::new(),
+            overlay: Vec::new(),
+            tooltip: None,
+        }
+    }
+
+    pub fn get_theme(&self) -> &Theme {
+        &self.theme
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+    }
+
+    pub fn begin_frame(&mut self, input: &UiInput, delta_time: f64) {
+        self.pressed = input.mouse_down && !self.mouse_was_down;
+        self.released = !input.mouse_down && self.mouse_was_down;
+        self.mouse_was_down = input.mouse_down;
+        self.input = input.clone();
+        self.delta_time = delta_time;
+
+        self.id_stack.clear();
+        self.clip_stack.clear();
+        self.panel_stack.clear();
+        self.focusables.clear();
+        self.captured_navigation.clear();
+        self.events.clear();
+        self.commands.clear();
+        self.overlay.clear();
+        self.hot = None;
+        self.active_seen = false;
+        self.last_widget = None;
+        self.popup_seen = false;
+        self.mouse_over_panel = false;
+        self.tooltip = None;
+        self.copied_text = None;
+    }
+
+    pub fn end_frame(&mut self) {
+        // The active widget wasn't drawn this frame, or the mouse was let go
+        // of somewhere it didn't notice.
+        if self.released || (!self.active_seen && self.active != Some(BACKGROUND)) {
+            self.active = None;
+        }
+
+        if self.pressed && self.active.is_none() {
+            self.active = Some(BACKGROUND);
+        }
+
+        if let Some(popup) = &self.popup {
+            let mouse = self.input.mouse_position;
+            let clicked_away = self.pressed
+                && !popup.rect.contains_point(mouse)
+                && !popup.owner_rect.contains_point(mouse);
+
+            if clicked_away || !self.popup_seen {
+                let owner = popup.owner;
+                self.popup = None;
+
+                // Rather than losing the focus along with the list.
+                if self.is_focus_in_popup() {
+                    self.set_focus(Some(owner));
+                }
+            }
+        }
+
+        if self.pressed {
+            self.focus_visible = false;
+        }
+
+        self.navigate();
+
+        if self.hot.is_some() && self.hot == self.last_hot {
+            self.hover_time += self.delta_time;
+        } else {
+            self.hover_time = 0.0;
+        }
+
+        self.last_hot = self.hot;
+
+        if let Some(text) = self.tooltip.take() {
+            let (width, height) = measure_text(&text, self.theme.text_scale);
+            let padding = self.theme.padding / 2.0;
+            let position = self.input.mouse_position + Vector2::new(12.0, 16.0);
+
+            let rect = Rect::new(
+                position.x,
+                position.y,
+                width + padding * 2.0,
+                height + padding * 2.0,
+            );
+
+            self.push_overlay(
+                Shape::Rect {
+                    rect,
+                    rounding: 0.0,
+                },
+                self.theme.tooltip,
+            );
+            self.push_overlay(
+                Shape::Text {
+                    position: position + Vector2::new(padding, padding),
+                    text,
+                },
+                self.theme.text,
+            );
+        }
+    }
+
+    /// Ends the frame like `end_frame`, and also puts anything copied or cut
+    /// out of a text input on the window's clipboard.
+    pub fn end_frame_with_window(&mut self, window: &mut Window) {
+        self.end_frame();
+
+        if let Some(text) = &self.copied_text {
+            window.set_clipboard_string(text);
+        }
+    }
+
+    /// Whether the mouse is on the UI, or busy with it, so the game shouldn't
+    /// do anything with it. Call this after the widgets.
+    pub fn wants_mouse(&self) -> bool {
+        let over_popup = self
+            .popup
+            .as_ref()
+            .is_some_and(|popup| popup.rect.contains_point(self.input.mouse_position));
+
+        return self.hot.is_some()
+            || over_popup
+            || self.mouse_over_panel
+            || self.active.is_some_and(|active| active != BACKGROUND);
+    }
+
+    /// Whether a text input has the keyboard, so the game shouldn't use it.
+    pub fn wants_keyboard(&self) -> bool {
+        self.text_edit.is_some()
+    }
+
+    /// Text that was copied or cut out of a text input this frame.
+    /// `end_frame_with_window` already puts it on the clipboard, so this is
+    /// for input that doesn't come from a `Window`.
+    pub fn get_copied_text(&self) -> Option<&str> {
+        self.copied_text.as_deref()
+    }
+
+    /// Makes the widgets up to the matching `pop_id` have different IDs to
+    /// ones with the same label elsewhere, like for each item in a list.
+    pub fn push_id(&mut self, key: impl Hash) {
+        let id = self.get_parent_id().child(key);
+        self.id_stack.push(id);
+    }
+
+    pub fn pop_id(&mut self) {
+        self.id_stack.pop();
+    }
+
+    fn get_parent_id(&self) -> WidgetId {
+        self.id_stack.last().copied().unwrap_or(WidgetId(1))
+    }
+
+    pub fn get_id(&self, label: &str) -> WidgetId {
+        self.get_parent_id().child(label)
+    }
+
+    /// The part of the label before any `##`.
+    pub(super) fn display_text(label: &str) -> &str {
+        label.split("##").next().unwrap_or(label)
+    }
+
+    pub(super) fn is_mouse_over(&self, rect: &Rect<f32>) -> bool {
+        let mouse = self.input.mouse_position;
+
+        let clipped = self
+            .clip_stack
+            .last()
+            .is_some_and(|clip| !clip.contains_point(mouse));
+
+        let under_popup = self
+            .popup
+            .as_ref()
+            .is_some_and(|popup| popup.rect.contains_point(mouse));
+
+        return rect.contains_point(mouse) && !clipped && !under_popup;
+    }
+
+    /// Works out the hot and active state for a widget, and lets it be
+    /// focused. Widgets that aren't under the mouse can still be active, like
+    /// a slider that's being dragged off the end.
+    pub(super) fn interact(&mut self, id: WidgetId, rect: &Rect<f32>) -> Response {
+        self.add_focusable(id, rect, false);
+
+        let over = self.is_mouse_over(rect);
+        return self.interact_with(id, rect, over);
+    }
+
+    pub(super) fn interact_with(&mut self, id: WidgetId, rect: &Rect<f32>, over: bool) -> Response {
+        let hovered = over && (self.active.is_none() || self.active == Some(id));
+        let mut response = Response {
+            hovered,
+            ..Response::default()
+        };
+
+        if hovered {
+            self.hot = Some(id);
+        }
+
+        if self.active == Some(id) {
+            self.active_seen = true;
+
+            if self.released {
+                self.active = None;
+                response.clicked = hovered;
+            } else {
+                response.active = true;
+            }
+        } else if hovered && self.pressed {
+            self.active = Some(id);
+            self.active_seen = true;
+            self.drag_offset = self.input.mouse_position - rect.get_min();
+            response.active = true;
+
+            if self.focusables.last().is_some_and(|last| last.id == id) {
+                self.set_focus(Some(id));
+            }
+        }
+
+        if self.nav_focus == Some(id) {
+            response.focused = true;
+
+            if self.was_navigation_pressed(NavAction::Activate) {
+                self.capture_navigation(&[NavAction::Activate]);
+                response.clicked = true;
+            }
+        }
+
+        self.last_widget = Some(id);
+        return response;
+    }
+
+    /// Shows `text` next to the mouse when it's been on the last widget for a
+    /// while.
+    pub fn tooltip(&mut self, text: &str) {
+        let waited = self.hover_time >= self.theme.tooltip_delay;
+
+        if waited && self.last_widget.is_some() && self.last_widget == self.hot {
+            self.tooltip = Some(text.to_string());
+        }
+    }
+
+    fn make_command(&self, shape: Shape, color: Color) -> DrawCommand {
+        DrawCommand {
+            shape,
+            color,
+            clip: self.clip_stack.last().copied(),
+        }
+    }
+
+    pub(super) fn push_shape(&mut self, shape: Shape, color: Color) {
+        let command = self.make_command(shape, color);
+        self.commands.push(command);
+    }
+
+    pub(super) fn push_overlay(&mut self, shape: Shape, color: Color) {
+        self.overlay.push(DrawCommand {
+            shape,
+            color,
+            clip: None,
+        });
+    }
+
+    pub(super) fn draw_rect(&mut self, rect: Rect<f32>, color: Color) {
+        let rounding = self.theme.rounding;
+        self.push_shape(Shape::Rect { rect, rounding }, color);
+    }
+
+    /// A nine-slice if the theme has one, or a rounded rect if it doesn't.
+    pub(super) fn skinned_shape(
+        &self,
+        rect: Rect<f32>,
+        slice: Option<NineSlice>,
+        color: Color,
+    ) -> (Shape, Color) {
+        match slice {
+            Some(slice) => (Shape::NineSlice { rect, slice }, colors::WHITE),
+            None => (
+                Shape::Rect {
+                    rect,
+                    rounding: self.theme.rounding,
+                },
+                color,
+            ),
+        }
+    }
+
+    /// Draws the background of a button, or anything else that looks like
+    /// one.
+    pub(super) fn draw_widget(&mut self, rect: Rect<f32>, response: &Response) {
+        let slice = self.theme.widget_slices.get(response);
+        let (shape, color) = self.skinned_shape(rect, slice, self.theme.widget.get(response));
+        self.push_shape(shape, color);
+    }
+
+    pub(super) fn draw_field(&mut self, rect: Rect<f32>) {
+        let (shape, color) = self.skinned_shape(rect, self.theme.field_slice, self.theme.field);
+        self.push_shape(shape, color);
+    }
+
+    pub(super) fn draw_panel(&mut self, rect: Rect<f32>) {
+        let (shape, color) = self.skinned_shape(rect, self.theme.panel_slice, self.theme.panel);
+        self.push_shape(shape, color);
+    }
+
+    /// Draws text vertically centered in `rect`, and horizontally too if
+    /// `centered` is set. Otherwise it's inset by the padding.
+    pub(super) fn draw_text_in(
+        &mut self,
+        rect: &Rect<f32>,
+        text: &str,
+        color: Color,
+        centered: bool,
+    ) {
+        let (width, height) = measure_text(text, self.theme.text_scale);
+
+        let x = if centered {
+            rect.x + (rect.width - width) / 2.0
+        } else {
+            rect.x + self.theme.padding
+        };
+
+        let position = Vector2::new(x, rect.y + (rect.height - height) / 2.0);
+        self.push_shape(
+            Shape::Text {
+                position,
+                text: text.to_string(),
+            },
+            color,
+        );
+    }
+
+    /// Draws everything from this frame on top of whatever's already there.
+    pub fn render(&self, renderer: &mut Renderer2D) {
+        renderer.begin();
+
+        for command in self.commands.iter().chain(&self.overlay) {
+            self.render_command(renderer, command);
+        }
+
+        renderer.end();
+    }
+
+    fn render_command(&self, renderer: &mut Renderer2D, command: &DrawCommand) {
+        let scale = self.theme.text_scale;
+
+        match &command.shape {
+            Shape::Rect { rect, rounding } => {
+                let clipped = match command.clip {
+                    Some(clip) => match rect.intersection(&clip) {
+                        Some(clipped) => clipped,
+                        None => return,
+                    },
+                    None => *rect,
+                };
+
+                // Rounded corners that have been cut off would look odd.
+                if *rounding > 0.0 && clipped == *rect {
+                    renderer.draw_rounded_rect(
+                        rect.x,
+                        rect.y,
+                        rect.width,
+                        rect.height,
+                        *rounding,
+                        ROUNDED_RECT_SEGMENTS,
+                        &command.color,
+                    );
+                } else {
+                    renderer.draw_quad(
+                        clipped.x,
+                        clipped.y,
+                        clipped.width,
+                        clipped.height,
+                        &command.color,
+                        -1.0,
+                    );
+                }
+            }
+            Shape::Text { position, text } => {
+                // Text can't be cut in half, so anything that doesn't fit
+                // inside the clip rect at all is left out.
+                let (width, height) = measure_text(text, scale);
+                let bounds = Rect::new(position.x, position.y, width, height);
+
+                if command
+                    .clip
+                    .is_some_and(|clip| !clip.contains_rect(&bounds))
+                {
+                    return;
+                }
+
+                renderer.draw_text(position.x, position.y, scale, text, &command.color);
+            }
+            Shape::NineSlice { rect, slice } => match &command.clip {
+                Some(clip) => renderer.draw_nine_slice_clipped(rect, slice, &command.color, clip),
+                None => renderer.draw_nine_slice(rect, slice, &command.color),
+            },
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use crate::math::Vector2;
+use crate::Window;
+
+/// Keys that widgets care about, like for editing text. The last few are
+/// shortcuts, like control and C for copy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum UiKey {
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Up,
+    Down,
+    Home,
+    End,
+    Enter,
+    Escape,
+    Tab,
+    SelectAll,
+    Copy,
+    Cut,
+    Paste,
+    Undo,
+    Redo,
+}
+
+/// Everything the UI needs to know about the input for one frame. It can
+/// come from a `Window`, or be made up, like in tests.
+#[derive(Clone, Debug, Default)]
+pub struct UiInput {
+    pub mouse_position: Vector2<f32>,
+    /// Whether the left mouse button is down.
+    pub mouse_down: bool,
+    /// How far the mouse wheel was scrolled this frame. Positive is up.
+    pub scroll: f32,
+    /// The characters that were typed this frame.
+    pub text: String,
+    /// The keys that were pressed this frame, including repeats from them
+    /// being held down.
+    pub keys: Vec<UiKey>,
+    /// Whether shift is held, for selecting text with the arrow keys.
+    pub shift: bool,
+    /// Whether control is held, for moving a word at a time.
+    pub control: bool,
+    /// What's on the clipboard, for pasting. It only needs to be there on
+    /// frames where `UiKey::Paste` was pressed.
+    pub clipboard: Option<String>,
+    /// Presses for moving between widgets with a keyboard or gamepad, like
+    /// from `NavigationInput`.
+    pub navigation: Vec<NavAction>,
+}
+
+// The UI key for a key press, if it has one. Shortcuts work with either
+// control or super (command on macOS).
+fn get_ui_key(key: glfw::Key, modifiers: glfw::Modifiers) -> Option<UiKey> {
+    use glfw::{Key, Modifiers};
+
+    let shortcut = modifiers.intersects(Modifiers::Control | Modifiers::Super);
+    let shift = modifiers.contains(Modifiers::Shift);
+
+    let ui_key = match key {
+        Key::Backspace => UiKey::Backspace,
+        Key::Delete => UiKey::Delete,
+        Key::Left => UiKey::Left,
+        Key::Right => UiKey::Right,
+        Key::Up => UiKey::Up,
+        Key::Down => UiKey::Down,
+        Key::Home => UiKey::Home,
+        Key::End => UiKey::End,
+        Key::Enter | Key::KpEnter => UiKey::Enter,
+        Key::Escape => UiKey::Escape,
+        Key::Tab => UiKey::Tab,
+        Key::A if shortcut => UiKey::SelectAll,
+        Key::C if shortcut => UiKey::Copy,
+        Key::X if shortcut => UiKey::Cut,
+        Key::V if shortcut => UiKey::Paste,
+        Key::Z if shortcut && shift => UiKey::Redo,
+        Key::Z if shortcut => UiKey::Undo,
+        Key::Y if shortcut => UiKey::Redo,
+        _ => return None,
+    };
+
+    return Some(ui_key);
+}
+
+impl UiInput {
+    /// The mouse, scrolling, keys and typing since the window's last update.
+    pub fn from_window(window: &Window) -> UiInput {
+        use glfw::Key;
+
+        let (x, y) = window.get_mouse_position();
+        let keys: Vec<UiKey> = window
+            .get_key_presses()
+            .iter()
+            .filter_map(|&(key, modifiers)| get_ui_key(key, modifiers))
+            .collect();
+
+        // Reading the clipboard can be slow, so it's only done when it's
+        // needed.
+        let clipboard = if keys.contains(&UiKey::Paste) {
+            window.get_clipboard_string()
+        } else {
+            None
+        };
+
+        UiInput {
+            mouse_position: Vector2::new(x as f32, y as f32),
+            mouse_down: window.is_mouse_button_down(glfw::MouseButton::Button1),
+            scroll: window.get_scroll(),
+            text: window.get_typed_text().to_string(),
+            keys,
+            shift: window.is_key_down(Key::LeftShift) || window.is_key_down(Key::RightShift),
+            control: window.is_key_down(Key::LeftControl) || window.is_key_down(Key::RightControl),
+            clipboard,
+            ..UiInput::default()
+        }
+    }
+
+    pub fn was_key_pressed(&self, key: UiKey) -> bool {
+        self.keys.contains(&key)
+    }
+
+    pub fn was_navigation_pressed(&self, action: NavAction) -> bool {
+        self.navigation.contains(&action)
+    }
+}
+
+/// Things that move the focus between widgets, or do something with the
+/// focused one, without the mouse.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NavAction {
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Along the tab order.
+    Next,
+    Previous,
+    /// Like clicking the focused widget.
+    Activate,
+    /// Closes an open dropdown, or tells the game to go back.
+    Cancel,
+}
+
+impl NavAction {
+    fn repeats(&self) -> bool {
+        !matches!(self, NavAction::Activate | NavAction::Cancel)
+    }
+}
+
+/// Turns the keys and gamepad buttons that are held down into navigation
+/// presses. Everything but activate and cancel repeats while it's held, like
+/// keys do in a text box.
+///
+/// The keyboard uses the arrow keys, tab and shift tab, enter or space, and
+/// escape. Gamepads use the d-pad or the left stick, the bumpers, A, and B.
+#[derive(Clone, Debug)]
+pub struct NavigationInput {
+    /// How long something has to be held before it starts repeating, in
+    /// seconds.
+    pub repeat_delay: f64,
+    pub repeat_interval: f64,
+    /// How far the stick has to be pushed to count.
+    pub stick_threshold: f32,
+    // What was held last time, and for how long.
+    held: Vec<(NavAction, f64)>,
+}
+
+impl Default for NavigationInput {
+    fn default() -> NavigationInput {
+        NavigationInput::new()
+    }
+}
+
+impl NavigationInput {
+    pub fn new() -> NavigationInput {
+        NavigationInput {
+            repeat_delay: 0.4,
+            repeat_interval: 0.1,
+            stick_threshold: 0.5,
+            held: Vec::new(),
+        }
+    }
+
+    /// What was pressed or repeated since the last time, from the window's
+    /// keyboard and gamepads.
+    pub fn poll(&mut self, window: &Window, delta_time: f64) -> Vec<NavAction> {
+        use glfw::{GamepadAxis, GamepadButton, Key};
+
+        let key = |key: Key| window.is_key_down(key);
+        let button = |button: GamepadButton| window.is_gamepad_button_down(button);
+
+        let shift = key(Key::LeftShift) || key(Key::RightShift);
+        let stick_x = window.get_gamepad_axis(GamepadAxis::AxisLeftX);
+        let stick_y = window.get_gamepad_axis(GamepadAxis::AxisLeftY);
+        let threshold = self.stick_threshold;
+
+        let held = [
+            (
+                NavAction::Up,
+                key(Key::Up) || button(GamepadButton::ButtonDpadUp) || stick_y < -threshold,
+            ),
+            (
+                NavAction::Down,
+                key(Key::Down) || button(GamepadButton::ButtonDpadDown) || stick_y > threshold,
+            ),
+            (
+                NavAction::Left,
+                key(Key::Left) || button(GamepadButton::ButtonDpadLeft) || stick_x < -threshold,
+            ),
+            (
+                NavAction::Right,
+                key(Key::Right) || button(GamepadButton::ButtonDpadRight) || stick_x > threshold,
+            ),
+            (
+                NavAction::Next,
+                (key(Key::Tab) && !shift) || button(GamepadButton::ButtonRightBumper),
+            ),
+            (
+                NavAction::Previous,
+                (key(Key::Tab) && shift) || button(GamepadButton::ButtonLeftBumper),
+            ),
+            (
+                NavAction::Activate,
+                key(Key::Enter) || key(Key::Space) || button(GamepadButton::ButtonA),
+            ),
+            (
+                NavAction::Cancel,
+                key(Key::Escape) || button(GamepadButton::ButtonB),
+            ),
+        ];
+
+        let held: Vec<NavAction> = held
+            .iter()
+            .filter(|(_, down)| *down)
+            .map(|(action, _)| *action)
+            .collect();
+
+        return self.update(&held, delta_time);
+    }
+
+    /// Like `poll`, but with what's held down given directly, for other input
+    /// devices or for testing.
+    pub fn update(&mut self, held: &[NavAction], delta_time: f64) -> Vec<NavAction> {
+        let mut pressed = Vec::new();
+        let mut still_held = Vec::with_capacity(held.len());
+
+        for &action in held {
+            let previous = self.held.iter().find(|(other, _)| *other == action);
+
+            let time = match previous {
+                None => {
+                    pressed.push(action);
+                    0.0
+                }
+                Some((_, time)) => {
+                    let new_time = time + delta_time;
+
+                    // How many repeats there have been by each time.
+                    let repeats = |time: f64| {
+                        if time < self.repeat_delay {
+                            0
+                        } else {
+                            ((time - self.repeat_delay) / self.repeat_interval) as u64 + 1
+                        }
+                    };
+
+                    if action.repeats() && repeats(new_time) > repeats(*time) {
+                        pressed.push(action);
+                    }
+
+                    new_time
+                }
+            };
+
+            still_held.push((action, time));
+        }
+
+        self.held = still_held;
+        return pressed;
+    }
+}
@@ -0,0 +1,429 @@
+//! Works out where things go from the rect they have to fit in, so nothing
+//! has to be placed by hand in pixels. None of it needs a window or the `Ui`,
+//! so game code can use it too.
+
+use crate::math::{Rect, Vector2};
+
+/// Empty space around the inside edges of a rect.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Margins {
+    pub left: f32,
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl Margins {
+    pub fn new(left: f32, top: f32, right: f32, bottom: f32) -> Margins {
+        Margins {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    pub fn all(margin: f32) -> Margins {
+        Margins::new(margin, margin, margin, margin)
+    }
+
+    pub fn symmetric(horizontal: f32, vertical: f32) -> Margins {
+        Margins::new(horizontal, vertical, horizontal, vertical)
+    }
+
+    pub fn get_horizontal(&self) -> f32 {
+        self.left + self.right
+    }
+
+    pub fn get_vertical(&self) -> f32 {
+        self.top + self.bottom
+    }
+
+    /// What's left of `rect` inside the margins. It never goes below zero
+    /// size, even if the margins are bigger than the rect.
+    pub fn shrink(&self, rect: &Rect<f32>) -> Rect<f32> {
+        Rect::new(
+            rect.x + self.left,
+            rect.y + self.top,
+            (rect.width - self.get_horizontal()).max(0.0),
+            (rect.height - self.get_vertical()).max(0.0),
+        )
+    }
+}
+
+/// Which part of its parent something sticks to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// How far across and down the parent the anchor is, from 0 to 1.
+    pub fn get_factors(&self) -> (f32, f32) {
+        match self {
+            Anchor::TopLeft => (0.0, 0.0),
+            Anchor::Top => (0.5, 0.0),
+            Anchor::TopRight => (1.0, 0.0),
+            Anchor::Left => (0.0, 0.5),
+            Anchor::Center => (0.5, 0.5),
+            Anchor::Right => (1.0, 0.5),
+            Anchor::BottomLeft => (0.0, 1.0),
+            Anchor::Bottom => (0.5, 1.0),
+            Anchor::BottomRight => (1.0, 1.0),
+        }
+    }
+}
+
+/// A `size` rect stuck to `anchor` in `parent`, kept `margins` away from its
+/// edges. A bottom right anchor with margins of 10 puts it 10 pixels in from
+/// the bottom right corner, wherever that is.
+pub fn anchor(
+    parent: &Rect<f32>,
+    size: Vector2<f32>,
+    anchor: Anchor,
+    margins: &Margins,
+) -> Rect<f32> {
+    let area = margins.shrink(parent);
+    let (x, y) = anchor.get_factors();
+
+    return Rect::new(
+        area.x + (area.width - size.x) * x,
+        area.y + (area.height - size.y) * y,
+        size.x,
+        size.y,
+    );
+}
+
+/// How big something wants to be along a stack, and how it gives and takes
+/// space when there's too much or too little, a lot like flexbox.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Size {
+    /// How big it is before growing or shrinking.
+    pub basis: f32,
+    /// How much of the leftover space it gets, compared to everything else.
+    pub grow: f32,
+    /// How much it gives up when there isn't enough space, compared to
+    /// everything else. It's weighted by the basis too, so bigger things
+    /// shrink more.
+    pub shrink: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+impl Default for Size {
+    fn default() -> Size {
+        Size {
+            basis: 0.0,
+            grow: 0.0,
+            shrink: 1.0,
+            min: 0.0,
+            max: f32::INFINITY,
+        }
+    }
+}
+
+impl Size {
+    /// Always exactly `size`.
+    pub fn fixed(size: f32) -> Size {
+        Size {
+            basis: size,
+            shrink: 0.0,
+            min: size,
+            max: size,
+            ..Size::default()
+        }
+    }
+
+    /// Starts at `basis`, and can grow or shrink from there.
+    pub fn new(basis: f32) -> Size {
+        Size {
+            basis,
+            ..Size::default()
+        }
+    }
+
+    /// Starts at nothing and takes `grow` shares of the leftover space.
+    pub fn flex(grow: f32) -> Size {
+        Size {
+            grow,
+            ..Size::default()
+        }
+    }
+
+    pub fn with_grow(mut self, grow: f32) -> Size {
+        self.grow = grow;
+        self
+    }
+
+    pub fn with_shrink(mut self, shrink: f32) -> Size {
+        self.shrink = shrink;
+        self
+    }
+
+    pub fn with_min(mut self, min: f32) -> Size {
+        self.min = min;
+        self
+    }
+
+    pub fn with_max(mut self, max: f32) -> Size {
+        self.max = max;
+        self
+    }
+
+    fn clamp(&self, size: f32) -> f32 {
+        size.min(self.max).max(self.min)
+    }
+}
+
+/// Shares out `available` between `sizes`. Anything that would go past its
+/// min or max gets stuck there, and the rest is shared out again between the
+/// others, so the results add up to `available` whenever the limits allow.
+pub fn distribute(sizes: &[Size], available: f32) -> Vec<f32> {
+    let mut result: Vec<f32> = sizes.iter().map(|size| size.clamp(size.basis)).collect();
+    let mut frozen = vec![false; sizes.len()];
+
+    loop {
+        let free = available - result.iter().sum::<f32>();
+
+        let weights: Vec<f32> = sizes
+            .iter()
+            .zip(&result)
+            .zip(&frozen)
+            .map(|((size, current), frozen)| match (frozen, free > 0.0) {
+                (true, _) => 0.0,
+                (false, true) => size.grow,
+                (false, false) => size.shrink * current,
+            })
+            .collect();
+
+        let total: f32 = weights.iter().sum();
+
+        if free == 0.0 || total <= 0.0 {
+            break;
+        }
+
+        let targets: Vec<f32> = result
+            .iter()
+            .zip(&weights)
+            .map(|(current, weight)| current + free * weight / total)
+            .collect();
+
+        let mut clamped = false;
+
+        for i in 0..sizes.len() {
+            let limited = sizes[i].clamp(targets[i]);
+
+            if !frozen[i] && limited != targets[i] {
+                result[i] = limited;
+                frozen[i] = true;
+                clamped = true;
+            }
+        }
+
+        if !clamped {
+            for i in 0..sizes.len() {
+                if !frozen[i] {
+                    result[i] = targets[i];
+                }
+            }
+
+            break;
+        }
+    }
+
+    return result;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    #[default]
+    Horizontal,
+    Vertical,
+}
+
+/// Where any space that nothing grew into goes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Justify {
+    #[default]
+    Start,
+    Center,
+    End,
+    /// Between the items, so the first and last ones touch the ends.
+    SpaceBetween,
+}
+
+/// Puts things one after another in a row or a column. Across the stack,
+/// everything fills the whole space.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Stack {
+    pub direction: Direction,
+    pub spacing: f32,
+    pub padding: Margins,
+    pub justify: Justify,
+}
+
+impl Stack {
+    pub fn horizontal() -> Stack {
+        Stack::default()
+    }
+
+    pub fn vertical() -> Stack {
+        Stack {
+            direction: Direction::Vertical,
+            ..Stack::default()
+        }
+    }
+
+    pub fn with_spacing(mut self, spacing: f32) -> Stack {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn with_padding(mut self, padding: Margins) -> Stack {
+        self.padding = padding;
+        self
+    }
+
+    pub fn with_justify(mut self, justify: Justify) -> Stack {
+        self.justify = justify;
+        self
+    }
+
+    /// A rect for each of `sizes`, in order.
+    pub fn layout(&self, parent: &Rect<f32>, sizes: &[Size]) -> Vec<Rect<f32>> {
+        let area = self.padding.shrink(parent);
+        let horizontal = self.direction == Direction::Horizontal;
+
+        let (start, length, cross_start, cross_length) = if horizontal {
+            (area.x, area.width, area.y, area.height)
+        } else {
+            (area.y, area.height, area.x, area.width)
+        };
+
+        let gaps = sizes.len().saturating_sub(1) as f32;
+        let available = (length - self.spacing * gaps).max(0.0);
+        let lengths = distribute(sizes, available);
+        let leftover = (available - lengths.iter().sum::<f32>()).max(0.0);
+
+        let (mut position, spacing) = match self.justify {
+            Justify::Start => (start, self.spacing),
+            Justify::Center => (start + leftover / 2.0, self.spacing),
+            Justify::End => (start + leftover, self.spacing),
+            Justify::SpaceBetween if gaps > 0.0 => (start, self.spacing + leftover / gaps),
+            Justify::SpaceBetween => (start, self.spacing),
+        };
+
+        let mut rects = Vec::with_capacity(lengths.len());
+
+        for length in lengths {
+            rects.push(if horizontal {
+                Rect::new(position, cross_start, length, cross_length)
+            } else {
+                Rect::new(cross_start, position, cross_length, length)
+            });
+
+            position += length + spacing;
+        }
+
+        return rects;
+    }
+}
+
+/// Cuts a rect up into evenly sized cells.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Grid {
+    pub columns: usize,
+    pub rows: usize,
+    /// The space between columns and between rows.
+    pub spacing: Vector2<f32>,
+    pub padding: Margins,
+}
+
+impl Grid {
+    pub fn new(columns: usize, rows: usize) -> Grid {
+        Grid {
+            columns,
+            rows,
+            spacing: Vector2::default(),
+            padding: Margins::default(),
+        }
+    }
+
+    pub fn with_spacing(mut self, spacing: Vector2<f32>) -> Grid {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn with_padding(mut self, padding: Margins) -> Grid {
+        self.padding = padding;
+        self
+    }
+
+    pub fn get_cell_size(&self, parent: &Rect<f32>) -> Vector2<f32> {
+        let area = self.padding.shrink(parent);
+
+        let size = |length: f32, count: usize, spacing: f32| {
+            if count == 0 {
+                return 0.0;
+            }
+
+            let count = count as f32;
+            ((length - spacing * (count - 1.0)) / count).max(0.0)
+        };
+
+        return Vector2::new(
+            size(area.width, self.columns, self.spacing.x),
+            size(area.height, self.rows, self.spacing.y),
+        );
+    }
+
+    pub fn cell(&self, parent: &Rect<f32>, column: usize, row: usize) -> Rect<f32> {
+        self.span(parent, column, row, 1, 1)
+    }
+
+    /// A rect covering `columns` by `rows` cells, starting at `column` and
+    /// `row`, including the spacing between them.
+    pub fn span(
+        &self,
+        parent: &Rect<f32>,
+        column: usize,
+        row: usize,
+        columns: usize,
+        rows: usize,
+    ) -> Rect<f32> {
+        let area = self.padding.shrink(parent);
+        let cell = self.get_cell_size(parent);
+        let step = cell + self.spacing;
+
+        let length = |count: usize, cell: f32, spacing: f32| {
+            let count = count.max(1) as f32;
+            cell * count + spacing * (count - 1.0)
+        };
+
+        return Rect::new(
+            area.x + step.x * column as f32,
+            area.y + step.y * row as f32,
+            length(columns, cell.x, self.spacing.x),
+            length(rows, cell.y, self.spacing.y),
+        );
+    }
+
+    /// The first `count` cells, going across each row before the next one
+    /// down.
+    pub fn layout(&self, parent: &Rect<f32>, count: usize) -> Vec<Rect<f32>> {
+        let columns = self.columns.max(1);
+
+        return (0..count)
+            .map(|i| self.cell(parent, i % columns, i / columns))
+            .collect();
+    }
+}
@@ -0,0 +1,258 @@
+use super::context::Shape;
+use super::{NavAction, Ui, WidgetId};
+use crate::math::{Rect, Vector2};
+
+/// Something that happened in the UI as a whole, rather than to one widget.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UiEvent {
+    FocusChanged {
+        from: Option<WidgetId>,
+        to: Option<WidgetId>,
+    },
+    /// Cancel was pressed with nothing in the UI to close, so the game might
+    /// want to go back a menu.
+    Cancelled,
+}
+
+// A widget that can be moved to with the keyboard or a gamepad, in the order
+// they were added.
+pub(super) struct Focusable {
+    pub(super) id: WidgetId,
+    rect: Rect<f32>,
+    clip: Option<Rect<f32>>,
+    // The scroll panel it's in, so it can be scrolled into view.
+    panel: Option<WidgetId>,
+    in_popup: bool,
+}
+
+// Picks the rect that's closest in `direction`, counting being off to the side
+// as twice as far, so that moving down a column doesn't jump to the next one
+// over just because it's a bit closer.
+fn nearest_in_direction(
+    from: &Rect<f32>,
+    rects: impl Iterator<Item = (usize, Rect<f32>)>,
+    direction: Vector2<f32>,
+) -> Option<usize> {
+    let origin = from.get_center();
+    let mut nearest = None;
+    let mut nearest_score = f32::INFINITY;
+
+    for (i, rect) in rects {
+        let offset = rect.get_center() - origin;
+        let along = offset.dot(direction);
+
+        if along <= 0.0 {
+            continue;
+        }
+
+        let across = (offset - direction * along).length();
+        let score = along + across * 2.0;
+
+        if score < nearest_score {
+            nearest = Some(i);
+            nearest_score = score;
+        }
+    }
+
+    return nearest;
+}
+
+impl Ui {
+    pub fn get_focus(&self) -> Option<WidgetId> {
+        self.nav_focus
+    }
+
+    /// Moves the keyboard and gamepad focus, like to the first button when a
+    /// menu opens.
+    pub fn set_focus(&mut self, id: Option<WidgetId>) {
+        if id != self.nav_focus {
+            self.events.push(UiEvent::FocusChanged {
+                from: self.nav_focus,
+                to: id,
+            });
+            self.nav_focus = id;
+        }
+    }
+
+    /// What happened during the last frame. They're kept until the next
+    /// `begin_frame`.
+    pub fn get_events(&self) -> &[UiEvent] {
+        &self.events
+    }
+
+    pub(super) fn was_navigation_pressed(&self, action: NavAction) -> bool {
+        self.input.was_navigation_pressed(action) && !self.captured_navigation.contains(&action)
+    }
+
+    /// Stops `actions` from moving the focus this frame, for widgets that use
+    /// them for something else while they're focused.
+    pub(super) fn capture_navigation(&mut self, actions: &[NavAction]) {
+        self.captured_navigation.extend_from_slice(actions);
+    }
+
+    pub(super) fn is_focus_in_popup(&self) -> bool {
+        self.focusables
+            .iter()
+            .any(|focusable| focusable.in_popup && Some(focusable.id) == self.nav_focus)
+    }
+
+    pub(super) fn add_focusable(&mut self, id: WidgetId, rect: &Rect<f32>, in_popup: bool) {
+        self.focusables.push(Focusable {
+            id,
+            rect: *rect,
+            clip: self.clip_stack.last().copied(),
+            panel: self.panel_stack.last().copied(),
+            in_popup,
+        });
+
+        if self.nav_focus == Some(id) && self.focus_visible && !in_popup {
+            self.draw_focus_outline(rect);
+        }
+    }
+
+    fn draw_focus_outline(&mut self, rect: &Rect<f32>) {
+        let thickness = self.theme.focus_thickness;
+        let outer = rect.expand(thickness);
+        let inner_height = outer.height - thickness * 2.0;
+
+        let sides = [
+            Rect::new(outer.x, outer.y, outer.width, thickness),
+            Rect::new(
+                outer.x,
+                outer.y + outer.height - thickness,
+                outer.width,
+                thickness,
+            ),
+            Rect::new(outer.x, outer.y + thickness, thickness, inner_height),
+            Rect::new(
+                outer.x + outer.width - thickness,
+                outer.y + thickness,
+                thickness,
+                inner_height,
+            ),
+        ];
+
+        let color = self.theme.focus;
+
+        for rect in sides {
+            self.push_shape(
+                Shape::Rect {
+                    rect,
+                    rounding: 0.0,
+                },
+                color,
+            );
+        }
+    }
+
+    /// Moves the focus for this frame's navigation presses. Only the widgets
+    /// in an open dropdown can be focused until it's closed.
+    pub(super) fn navigate(&mut self) {
+        let popup_open = self.popup.is_some();
+        let candidates: Vec<usize> = (0..self.focusables.len())
+            .filter(|&i| self.focusables[i].in_popup == popup_open)
+            .collect();
+
+        // The focused widget has gone away.
+        let current = candidates
+            .iter()
+            .position(|&i| Some(self.focusables[i].id) == self.nav_focus);
+
+        if current.is_none() && self.nav_focus.is_some() {
+            self.set_focus(None);
+        }
+
+        let actions: Vec<NavAction> = self
+            .input
+            .navigation
+            .iter()
+            .copied()
+            .filter(|action| !self.captured_navigation.contains(action))
+            .collect();
+
+        let mut current = current;
+
+        for action in actions {
+            let count = candidates.len();
+
+            let direction = match action {
+                NavAction::Up => Vector2::new(0.0, -1.0),
+                NavAction::Down => Vector2::new(0.0, 1.0),
+                NavAction::Left => Vector2::new(-1.0, 0.0),
+                NavAction::Right => Vector2::new(1.0, 0.0),
+                NavAction::Cancel => {
+                    match self.popup.take() {
+                        Some(popup) => self.set_focus(Some(popup.owner)),
+                        None => self.events.push(UiEvent::Cancelled),
+                    }
+
+                    return;
+                }
+                // Anything focused would have used it already.
+                NavAction::Activate => continue,
+                NavAction::Next | NavAction::Previous if count == 0 => continue,
+                NavAction::Next => {
+                    current = Some(current.map_or(0, |i| (i + 1) % count));
+                    continue;
+                }
+                NavAction::Previous => {
+                    current = Some(current.map_or(count - 1, |i| (i + count - 1) % count));
+                    continue;
+                }
+            };
+
+            current = match current {
+                Some(i) => {
+                    let from = self.focusables[candidates[i]].rect;
+                    let rects = candidates
+                        .iter()
+                        .enumerate()
+                        .filter(|&(j, _)| j != i)
+                        .map(|(j, &index)| (j, self.focusables[index].rect));
+
+                    nearest_in_direction(&from, rects, direction).or(Some(i))
+                }
+                None if count > 0 => Some(0),
+                None => None,
+            };
+        }
+
+        let focusable = match current {
+            Some(i) => &self.focusables[candidates[i]],
+            None => return,
+        };
+
+        if Some(focusable.id) != self.nav_focus {
+            let (id, rect, clip, panel) = (
+                focusable.id,
+                focusable.rect,
+                focusable.clip,
+                focusable.panel,
+            );
+
+            self.set_focus(Some(id));
+            self.focus_visible = true;
+
+            if let (Some(clip), Some(panel)) = (clip, panel) {
+                self.scroll_into_view(panel, &rect, &clip);
+            }
+        }
+    }
+
+    // Scrolls `panel` so that all of `rect` is in `clip`, if it fits.
+    fn scroll_into_view(&mut self, panel: WidgetId, rect: &Rect<f32>, clip: &Rect<f32>) {
+        let above = rect.y - clip.y;
+        let below = (rect.y + rect.height) - (clip.y + clip.height);
+
+        let change = if above < 0.0 {
+            above
+        } else if below > 0.0 {
+            below.min(above)
+        } else {
+            return;
+        };
+
+        // It gets kept in range the next time the panel is drawn.
+        *self.scroll_offsets.entry(panel).or_insert(0.0) += change;
+    }
+}
@@ -0,0 +1,398 @@
+use super::WidgetId;
+use crate::graphics::measure_text;
+use std::ops::Range;
+
+// How many changes can be undone.
+const MAX_UNDO: usize = 100;
+
+/// Which characters a text input lets in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextFilter {
+    #[default]
+    Any,
+    /// Digits, with an optional minus sign at the start and one decimal
+    /// point.
+    Numeric,
+    /// Letters and digits, from any alphabet.
+    Alphanumeric,
+}
+
+impl TextFilter {
+    /// Whether all of `text` is allowed. Numbers don't have to be finished,
+    /// so `-` and `1.` are fine.
+    pub fn accepts(&self, text: &str) -> bool {
+        match self {
+            TextFilter::Any => true,
+            TextFilter::Numeric => {
+                let digits = text.strip_prefix('-').unwrap_or(text);
+
+                digits.chars().all(|c| c.is_ascii_digit() || c == '.')
+                    && digits.matches('.').count() <= 1
+            }
+            TextFilter::Alphanumeric => text.chars().all(char::is_alphanumeric),
+        }
+    }
+}
+
+/// Limits on what can be typed into a text input, for
+/// `Ui::text_input_with`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextInputSettings {
+    /// The most characters it can hold. Anything typed or pasted past that is
+    /// left out.
+    pub max_length: Option<usize>,
+    pub filter: TextFilter,
+}
+
+// What the last change to the text was, so that typing a word can be undone
+// all at once rather than a letter at a time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum Change {
+    Typing,
+    Deleting,
+    Other,
+}
+
+struct Snapshot {
+    text: String,
+    caret: usize,
+    anchor: usize,
+}
+
+// The caret, selection and undo history of the text input that has the
+// keyboard. Positions are byte offsets into the text, and are always on
+// character boundaries.
+pub(super) struct TextEdit {
+    pub(super) id: WidgetId,
+    pub(super) caret: usize,
+    // The other end of the selection, which is the same as the caret when
+    // nothing is selected.
+    pub(super) anchor: usize,
+    // How far the text has been scrolled left to keep the caret in view.
+    pub(super) scroll: f32,
+    undo: Vec<Snapshot>,
+    redo: Vec<Snapshot>,
+    last_change: Option<Change>,
+}
+
+fn previous_boundary(text: &str, position: usize) -> usize {
+    text[..position]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+fn next_boundary(text: &str, position: usize) -> usize {
+    text[position..]
+        .chars()
+        .next()
+        .map_or(position, |c| position + c.len_utf8())
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+// The start of the word before `position`, skipping anything that isn't part
+// of a word first.
+fn previous_word(text: &str, position: usize) -> usize {
+    let mut position = position;
+
+    for in_word in [false, true] {
+        while let Some(c) = text[..position].chars().next_back() {
+            if is_word_char(c) != in_word {
+                break;
+            }
+
+            position -= c.len_utf8();
+        }
+    }
+
+    return position;
+}
+
+// The start of the next word after `position`.
+fn next_word(text: &str, position: usize) -> usize {
+    let mut position = position;
+
+    for in_word in [true, false] {
+        while let Some(c) = text[position..].chars().next() {
+            if is_word_char(c) != in_word {
+                break;
+            }
+
+            position += c.len_utf8();
+        }
+    }
+
+    return position;
+}
+
+// Every position the caret can be at, from the start to the end.
+fn caret_positions(text: &str) -> impl Iterator<Item = usize> + '_ {
+    text.char_indices()
+        .map(|(i, _)| i)
+        .chain(std::iter::once(text.len()))
+}
+
+/// How far into `text` the caret at `position` is drawn.
+pub(super) fn caret_offset(text: &str, position: usize, scale: f32) -> f32 {
+    measure_text(&text[..position], scale).0
+}
+
+/// The caret position closest to `x` pixels into `text`.
+pub(super) fn caret_at(text: &str, x: f32, scale: f32) -> usize {
+    let mut nearest = 0;
+    let mut nearest_distance = f32::INFINITY;
+
+    for position in caret_positions(text) {
+        let distance = (caret_offset(text, position, scale) - x).abs();
+
+        if distance < nearest_distance {
+            nearest = position;
+            nearest_distance = distance;
+        }
+    }
+
+    return nearest;
+}
+
+/// The part of `text` that fits between `from` and `from + width` pixels in.
+/// Text can't be drawn cut in half, so characters that only partly fit are
+/// left out.
+pub(super) fn visible_range(text: &str, from: f32, width: f32, scale: f32) -> Range<usize> {
+    let mut start = None;
+    let mut end = 0;
+
+    for position in caret_positions(text) {
+        let x = caret_offset(text, position, scale);
+
+        if start.is_none() && x >= from - 0.5 {
+            start = Some(position);
+        }
+
+        if x <= from + width + 0.5 {
+            end = position;
+        }
+    }
+
+    let start = start.unwrap_or(text.len());
+    return start..end.max(start);
+}
+
+impl TextEdit {
+    /// Starts editing with the caret at the end.
+    pub(super) fn new(id: WidgetId, text: &str) -> TextEdit {
+        TextEdit {
+            id,
+            caret: text.len(),
+            anchor: text.len(),
+            scroll: 0.0,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            last_change: None,
+        }
+    }
+
+    pub(super) fn get_selection(&self) -> Range<usize> {
+        self.caret.min(self.anchor)..self.caret.max(self.anchor)
+    }
+
+    pub(super) fn get_selected_text<'a>(&self, text: &'a str) -> &'a str {
+        &text[self.get_selection()]
+    }
+
+    /// Keeps the caret and selection in the text, in case it was changed by
+    /// something other than the text input.
+    pub(super) fn clamp(&mut self, text: &str) {
+        for position in [&mut self.caret, &mut self.anchor] {
+            while *position > text.len() || !text.is_char_boundary(*position) {
+                *position -= 1;
+            }
+        }
+    }
+
+    /// Moves the caret, dragging the selection along with it if `extend` is
+    /// set.
+    pub(super) fn move_caret(&mut self, position: usize, extend: bool) {
+        self.caret = position;
+
+        if !extend {
+            self.anchor = position;
+        }
+
+        self.last_change = None;
+    }
+
+    /// Without `extend`, moving left or right from a selection goes to that
+    /// end of it.
+    pub(super) fn move_left(&mut self, text: &str, word: bool, extend: bool) {
+        let selection = self.get_selection();
+
+        let position = if word {
+            previous_word(text, self.caret)
+        } else if !selection.is_empty() && !extend {
+            selection.start
+        } else {
+            previous_boundary(text, self.caret)
+        };
+
+        self.move_caret(position, extend);
+    }
+
+    pub(super) fn move_right(&mut self, text: &str, word: bool, extend: bool) {
+        let selection = self.get_selection();
+
+        let position = if word {
+            next_word(text, self.caret)
+        } else if !selection.is_empty() && !extend {
+            selection.end
+        } else {
+            next_boundary(text, self.caret)
+        };
+
+        self.move_caret(position, extend);
+    }
+
+    pub(super) fn select_all(&mut self, text: &str) {
+        self.anchor = 0;
+        self.move_caret(text.len(), true);
+    }
+
+    fn snapshot(&self, text: &str) -> Snapshot {
+        Snapshot {
+            text: text.to_string(),
+            caret: self.caret,
+            anchor: self.anchor,
+        }
+    }
+
+    // Remembers the text from before a change, unless it's more of the same
+    // change as last time.
+    fn save(&mut self, text: &str, change: Change) {
+        if self.last_change != Some(change) || change == Change::Other {
+            self.undo.push(self.snapshot(text));
+
+            if self.undo.len() > MAX_UNDO {
+                self.undo.remove(0);
+            }
+        }
+
+        self.redo.clear();
+        self.last_change = Some(change);
+    }
+
+    /// Replaces the selection with as much of `typed` as `settings` allows,
+    /// skipping control characters. Returns whether the text changed.
+    pub(super) fn insert(
+        &mut self,
+        text: &mut String,
+        typed: &str,
+        settings: &TextInputSettings,
+        change: Change,
+    ) -> bool {
+        let selection = self.get_selection();
+        let mut new_text = format!("{}{}", &text[..selection.start], &text[selection.end..]);
+        let mut length = new_text.chars().count();
+        let mut position = selection.start;
+
+        for c in typed.chars().filter(|c| !c.is_control()) {
+            if settings.max_length.is_some_and(|max| length >= max) {
+                break;
+            }
+
+            new_text.insert(position, c);
+
+            if !settings.filter.accepts(&new_text) {
+                new_text.remove(position);
+                continue;
+            }
+
+            position += c.len_utf8();
+            length += 1;
+        }
+
+        // Nothing got in, so whatever was selected stays.
+        if position == selection.start {
+            return false;
+        }
+
+        self.save(text, change);
+        *text = new_text;
+        self.caret = position;
+        self.anchor = position;
+
+        return true;
+    }
+
+    /// Deletes the selection, or if there isn't one, the character or word
+    /// before or after the caret.
+    pub(super) fn delete(&mut self, text: &mut String, forwards: bool, word: bool) -> bool {
+        let mut range = self.get_selection();
+
+        if range.is_empty() {
+            range = match (forwards, word) {
+                (false, false) => previous_boundary(text, self.caret)..self.caret,
+                (false, true) => previous_word(text, self.caret)..self.caret,
+                (true, false) => self.caret..next_boundary(text, self.caret),
+                (true, true) => self.caret..next_word(text, self.caret),
+            };
+        }
+
+        if range.is_empty() {
+            return false;
+        }
+
+        self.save(text, Change::Deleting);
+        text.replace_range(range.clone(), "");
+        self.caret = range.start;
+        self.anchor = range.start;
+
+        return true;
+    }
+
+    /// Deletes the selection and returns what it was, if there was one.
+    pub(super) fn cut(&mut self, text: &mut String) -> Option<String> {
+        let selected = self.get_selected_text(text).to_string();
+
+        if selected.is_empty() {
+            return None;
+        }
+
+        self.delete(text, false, false);
+        self.last_change = None;
+
+        return Some(selected);
+    }
+
+    pub(super) fn undo(&mut self, text: &mut String) -> bool {
+        let snapshot = match self.undo.pop() {
+            Some(snapshot) => snapshot,
+            None => return false,
+        };
+
+        self.redo.push(self.snapshot(text));
+        self.restore(text, snapshot);
+
+        return true;
+    }
+
+    pub(super) fn redo(&mut self, text: &mut String) -> bool {
+        let snapshot = match self.redo.pop() {
+            Some(snapshot) => snapshot,
+            None => return false,
+        };
+
+        self.undo.push(self.snapshot(text));
+        self.restore(text, snapshot);
+
+        return true;
+    }
+
+    fn restore(&mut self, text: &mut String, snapshot: Snapshot) {
+        *text = snapshot.text;
+        self.caret = snapshot.caret;
+        self.anchor = snapshot.anchor;
+        self.last_change = None;
+    }
+}
@@ -0,0 +1,280 @@
+use super::Response;
+use crate::graphics::{Color, NineSlice, Renderer2D, SliceMode};
+use crate::Error;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// A color for each state a widget can be in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct WidgetColors {
+    pub normal: Color,
+    pub hovered: Color,
+    pub active: Color,
+}
+
+impl WidgetColors {
+    pub fn get(&self, response: &Response) -> Color {
+        if response.active {
+            self.active
+        } else if response.hovered {
+            self.hovered
+        } else {
+            self.normal
+        }
+    }
+}
+
+/// A nine-slice for each state a widget can be in. States without one use
+/// the normal one, and widgets with none at all are drawn with plain colors.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WidgetSlices {
+    pub normal: Option<NineSlice>,
+    pub hovered: Option<NineSlice>,
+    pub active: Option<NineSlice>,
+}
+
+impl WidgetSlices {
+    pub fn get(&self, response: &Response) -> Option<NineSlice> {
+        let slice = if response.active {
+            self.active
+        } else if response.hovered {
+            self.hovered
+        } else {
+            None
+        };
+
+        return slice.or(self.normal);
+    }
+}
+
+/// How the widgets look. There's only the renderer's built-in font, so the
+/// font is just how big the text is drawn.
+///
+/// It can be loaded from a text file with a `name = value` line for each
+/// field that isn't the default, and `#` for comments. Colors are hex codes
+/// or names like `sky_blue`, and the widget states are `widget`,
+/// `widget_hovered` and `widget_active`. The nine-slices go in
+/// `widget_slice`, `widget_hovered_slice`, `widget_active_slice`,
+/// `field_slice` and `panel_slice`, like `panel_slice = panel.png 12` or
+/// `button.png 8 6 8 10 tile_edges tile_center`: the texture, relative to
+/// the theme file, then either one border for every side or left, top,
+/// right and bottom ones, then optionally which parts tile instead of
+/// stretching.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub text: Color,
+    /// For placeholder text, and anything else that's less important.
+    pub text_dim: Color,
+    pub text_scale: f32,
+    /// The space between the edge of a widget and what's in it.
+    pub padding: f32,
+    pub rounding: f32,
+    /// Buttons, dropdowns, checkboxes and slider handles.
+    pub widget: WidgetColors,
+    pub widget_slices: WidgetSlices,
+    /// The insides of text inputs and slider tracks.
+    pub field: Color,
+    pub field_slice: Option<NineSlice>,
+    /// Checkmarks, the filled part of sliders, and text carets.
+    pub accent: Color,
+    /// Behind selected text.
+    pub selection: Color,
+    /// Scroll panels and dropdown lists.
+    pub panel: Color,
+    pub panel_slice: Option<NineSlice>,
+    pub tooltip: Color,
+    /// The outline around the widget that has the keyboard and gamepad
+    /// focus.
+    pub focus: Color,
+    pub focus_thickness: f32,
+    pub scrollbar_width: f32,
+    /// How many pixels one step of the mouse wheel scrolls.
+    pub scroll_speed: f32,
+    /// How many seconds the mouse has to stay on a widget before its tooltip
+    /// shows up.
+    pub tooltip_delay: f64,
+}
+
+impl Default for Theme {
+    fn default() -> Theme {
+        Theme {
+            text: Color::rgb(0.92, 0.92, 0.92),
+            text_dim: Color::rgb(0.55, 0.55, 0.55),
+            text_scale: 2.0,
+            padding: 8.0,
+            rounding: 4.0,
+            widget: WidgetColors {
+                normal: Color::rgb(0.25, 0.27, 0.31),
+                hovered: Color::rgb(0.32, 0.35, 0.4),
+                active: Color::rgb(0.2, 0.22, 0.25),
+            },
+            widget_slices: WidgetSlices::default(),
+            field: Color::rgb(0.14, 0.15, 0.17),
+            field_slice: None,
+            accent: Color::rgb(0.3, 0.6, 1.0),
+            selection: Color::new(0.3, 0.6, 1.0, 0.35),
+            panel: Color::new(0.1, 0.11, 0.13, 0.95),
+            panel_slice: None,
+            tooltip: Color::rgb(0.05, 0.05, 0.06),
+            focus: Color::rgb(1.0, 0.8, 0.3),
+            focus_thickness: 2.0,
+            scrollbar_width: 10.0,
+            scroll_speed: 40.0,
+            tooltip_delay: 0.5,
+        }
+    }
+}
+
+impl Theme {
+    /// Loads a theme file, and the textures for its nine-slices into
+    /// `renderer`.
+    pub fn load(path: &str, renderer: &mut Renderer2D) -> Result<Theme, Error> {
+        let text = std::fs::read_to_string(path).map_err(|error| Error::Io {
+            path: path.to_string(),
+            error,
+        })?;
+
+        let directory = Path::new(path).parent().unwrap_or(Path::new(""));
+
+        return Theme::parse(&text, path, |texture| {
+            renderer.load_texture(&directory.join(texture).to_string_lossy())
+        });
+    }
+
+    /// Reads a theme from `text`, with `load_texture` turning the texture
+    /// paths in it into texture ids. It's given the paths just as they're
+    /// written, and only once each, however many times they're used.
+    pub fn from_str_with_textures(
+        text: &str,
+        load_texture: impl FnMut(&str) -> Result<f32, Error>,
+    ) -> Result<Theme, Error> {
+        return Theme::parse(text, "<string>", load_texture);
+    }
+
+    fn parse(
+        text: &str,
+        path: &str,
+        mut load_texture: impl FnMut(&str) -> Result<f32, Error>,
+    ) -> Result<Theme, Error> {
+        let mut theme = Theme::default();
+        // Nine-slices often share a texture, which only has to be loaded once.
+        let mut textures: HashMap<String, f32> = HashMap::new();
+
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let error = |message: String| Error::Parse {
+                path: path.to_string(),
+                line: i + 1,
+                message,
+            };
+
+            let (name, value) = match line.split_once('=') {
+                Some((name, value)) => (name.trim(), value.trim()),
+                None => return Err(error("expected `name = value`".to_string())),
+            };
+
+            let color = || {
+                Color::from_hex(value)
+                    .or_else(|| Color::from_name(value))
+                    .ok_or_else(|| error(format!("`{}` isn't a color", value)))
+            };
+
+            let number = || {
+                value
+                    .parse::<f32>()
+                    .map_err(|_| error(format!("`{}` isn't a number", value)))
+            };
+
+            let mut slice = || -> Result<Option<NineSlice>, Error> {
+                match parse_slice(value) {
+                    Ok((texture_path, slice)) => {
+                        let texture = match textures.get(texture_path) {
+                            Some(texture) => *texture,
+                            None => {
+                                let texture = load_texture(texture_path)?;
+                                textures.insert(texture_path.to_string(), texture);
+                                texture
+                            }
+                        };
+
+                        Ok(Some(NineSlice { texture, ..slice }))
+                    }
+                    Err(message) => Err(error(message)),
+                }
+            };
+
+            match name {
+                "text" => theme.text = color()?,
+                "text_dim" => theme.text_dim = color()?,
+                "text_scale" => theme.text_scale = number()?,
+                "padding" => theme.padding = number()?,
+                "rounding" => theme.rounding = number()?,
+                "widget" => theme.widget.normal = color()?,
+                "widget_hovered" => theme.widget.hovered = color()?,
+                "widget_active" => theme.widget.active = color()?,
+                "widget_slice" => theme.widget_slices.normal = slice()?,
+                "widget_hovered_slice" => theme.widget_slices.hovered = slice()?,
+                "widget_active_slice" => theme.widget_slices.active = slice()?,
+                "field" => theme.field = color()?,
+                "field_slice" => theme.field_slice = slice()?,
+                "accent" => theme.accent = color()?,
+                "selection" => theme.selection = color()?,
+                "panel" => theme.panel = color()?,
+                "panel_slice" => theme.panel_slice = slice()?,
+                "tooltip" => theme.tooltip = color()?,
+                "focus" => theme.focus = color()?,
+                "focus_thickness" => theme.focus_thickness = number()?,
+                "scrollbar_width" => theme.scrollbar_width = number()?,
+                "scroll_speed" => theme.scroll_speed = number()?,
+                "tooltip_delay" => theme.tooltip_delay = number()? as f64,
+                _ => return Err(error(format!("there's no theme setting called `{}`", name))),
+            }
+        }
+
+        return Ok(theme);
+    }
+}
+
+// Splits something like `panel.png 8 4 8 4 tile_center` into the texture path
+// and the rest of the nine-slice.
+fn parse_slice(value: &str) -> Result<(&str, NineSlice), String> {
+    let mut words = value.split_whitespace();
+
+    let texture = match words.next() {
+        Some(texture) => texture,
+        None => return Err("expected a texture and its borders".to_string()),
+    };
+
+    let mut borders = Vec::new();
+    let mut slice = NineSlice::new(-1.0, 0.0);
+
+    for word in words {
+        match word {
+            "tile_edges" => slice.edge_mode = SliceMode::Tile,
+            "tile_center" => slice.center_mode = SliceMode::Tile,
+            _ => match word.parse::<f32>() {
+                Ok(border) if border >= 0.0 => borders.push(border),
+                Ok(_) => return Err(format!("borders can't be negative, found `{}`", word)),
+                _ => {
+                    return Err(format!(
+                        "expected a border, `tile_edges` or `tile_center`, found `{}`",
+                        word
+                    ))
+                }
+            },
+        }
+    }
+
+    match borders[..] {
+        [border] => slice = slice.with_borders(border, border, border, border),
+        [left, top, right, bottom] => slice = slice.with_borders(left, top, right, bottom),
+        _ => return Err("expected either 1 or 4 borders".to_string()),
+    }
+
+    return Ok((texture, slice));
+}
@@ -0,0 +1,534 @@
+use super::context::{Popup, Shape};
+use super::text_edit::{self, Change, TextEdit};
+use super::{NavAction, Response, TextInputSettings, Ui, UiKey};
+use crate::math::{Rect, Vector2};
+
+impl Ui {
+    /// Text with its top left corner at `position`.
+    pub fn label(&mut self, text: &str, position: Vector2<f32>) {
+        let color = self.theme.text;
+        self.push_shape(
+            Shape::Text {
+                position,
+                text: text.to_string(),
+            },
+            color,
+        );
+    }
+
+    pub fn button(&mut self, label: &str, rect: Rect<f32>) -> Response {
+        let id = self.get_id(label);
+        let response = self.interact(id, &rect);
+
+        let text = self.theme.text;
+        self.draw_widget(rect, &response);
+        self.draw_text_in(&rect, Ui::display_text(label), text, true);
+
+        return response;
+    }
+
+    /// A box on the left of `rect` with the label next to it. Clicking
+    /// anywhere in `rect` flips `checked`.
+    pub fn checkbox(&mut self, label: &str, rect: Rect<f32>, checked: &mut bool) -> Response {
+        let id = self.get_id(label);
+        let mut response = self.interact(id, &rect);
+
+        if response.clicked {
+            *checked = !*checked;
+            response.changed = true;
+        }
+
+        let size = rect.height;
+        let check_box = Rect::new(rect.x, rect.y, size, size);
+        self.draw_widget(check_box, &response);
+
+        if *checked {
+            let inset = size / 4.0;
+            let mark = Rect::new(
+                rect.x + inset,
+                rect.y + inset,
+                size - inset * 2.0,
+                size - inset * 2.0,
+            );
+
+            let accent = self.theme.accent;
+            self.draw_rect(mark, accent);
+        }
+
+        let text_rect = Rect::new(rect.x + size, rect.y, rect.width - size, rect.height);
+        let text = self.theme.text;
+        self.draw_text_in(&text_rect, Ui::display_text(label), text, false);
+
+        return response;
+    }
+
+    /// Dragging anywhere along it sets `value` to somewhere between `min` and
+    /// `max`. The drag keeps going if the mouse leaves it. While it's focused,
+    /// left and right move it by a twentieth of the way.
+    pub fn slider(
+        &mut self,
+        label: &str,
+        rect: Rect<f32>,
+        value: &mut f32,
+        min: f32,
+        max: f32,
+    ) -> Response {
+        let id = self.get_id(label);
+        let mut response = self.interact(id, &rect);
+
+        if response.active && rect.width > 0.0 {
+            let t = ((self.input.mouse_position.x - rect.x) / rect.width).clamp(0.0, 1.0);
+            let new_value = min + (max - min) * t;
+
+            if new_value != *value {
+                *value = new_value;
+                response.changed = true;
+            }
+        }
+
+        if response.focused {
+            let step = (max - min) / 20.0;
+            let mut new_value = *value;
+
+            if self.was_navigation_pressed(NavAction::Left) {
+                new_value -= step;
+            }
+
+            if self.was_navigation_pressed(NavAction::Right) {
+                new_value += step;
+            }
+
+            self.capture_navigation(&[NavAction::Left, NavAction::Right]);
+            let new_value = new_value.clamp(min.min(max), max.max(min));
+
+            if new_value != *value {
+                *value = new_value;
+                response.changed = true;
+            }
+        }
+
+        let t = if max != min {
+            ((*value - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let accent = self.theme.accent;
+        self.draw_field(rect);
+        self.draw_rect(
+            Rect::new(rect.x, rect.y, rect.width * t, rect.height),
+            accent,
+        );
+
+        let handle_width = rect.height / 2.0;
+        let handle = Rect::new(
+            rect.x + (rect.width - handle_width) * t,
+            rect.y,
+            handle_width,
+            rect.height,
+        );
+
+        self.draw_widget(handle, &response);
+
+        let text = format!("{} {:.2}", Ui::display_text(label), value);
+        let text_color = self.theme.text;
+        self.draw_text_in(&rect, text.trim_start(), text_color, true);
+
+        return response;
+    }
+
+    /// A single line of text that can be typed into once it's been clicked or
+    /// activated. Clicking anywhere else, moving the focus away, or pressing
+    /// enter or escape, lets go of the keyboard again. The label is shown
+    /// when it's empty.
+    ///
+    /// The caret can be moved with the mouse, the arrow keys, and home and
+    /// end, a word at a time with control held, and shift or dragging selects.
+    /// It has the usual shortcuts for copying, cutting, pasting and undoing.
+    /// Copied text goes on the clipboard when the frame is ended with
+    /// `end_frame_with_window`, and otherwise comes out of `get_copied_text`.
+    /// The built-in font only has ASCII, so anything else typed is kept but
+    /// not drawn.
+    pub fn text_input(&mut self, label: &str, rect: Rect<f32>, text: &mut String) -> Response {
+        return self.text_input_with(label, rect, text, TextInputSettings::default());
+    }
+
+    /// A `text_input` with limits on what can go in it.
+    pub fn text_input_with(
+        &mut self,
+        label: &str,
+        rect: Rect<f32>,
+        text: &mut String,
+        settings: TextInputSettings,
+    ) -> Response {
+        let id = self.get_id(label);
+        let mut response = self.interact(id, &rect);
+        let is_editing = |ui: &Ui| ui.text_edit.as_ref().is_some_and(|edit| edit.id == id);
+
+        if is_editing(self) && ((self.pressed && !response.hovered) || !response.focused) {
+            self.text_edit = None;
+        } else if !is_editing(self) && ((self.pressed && response.hovered) || response.clicked) {
+            self.text_edit = Some(TextEdit::new(id, text));
+        }
+
+        let mut edit = match self.text_edit.take() {
+            Some(edit) if edit.id == id => Some(edit),
+            other => {
+                self.text_edit = other;
+                None
+            }
+        };
+
+        let scale = self.theme.text_scale;
+        let padding = self.theme.padding;
+        let visible_width = (rect.width - padding * 2.0).max(0.0);
+        let mut finished = false;
+
+        if let Some(edit) = &mut edit {
+            // The arrow keys and enter are for editing, but tabbing away still
+            // works.
+            self.capture_navigation(&[
+                NavAction::Up,
+                NavAction::Down,
+                NavAction::Left,
+                NavAction::Right,
+                NavAction::Activate,
+                NavAction::Cancel,
+            ]);
+
+            edit.clamp(text);
+            let (shift, word) = (self.input.shift, self.input.control);
+
+            // Clicking puts the caret under the mouse, and dragging selects.
+            if response.active {
+                let x = self.input.mouse_position.x - (rect.x + padding - edit.scroll);
+                let extend = !self.pressed || shift;
+                edit.move_caret(text_edit::caret_at(text, x, scale), extend);
+            }
+
+            let mut changed = false;
+
+            if !self.input.text.is_empty() {
+                changed |= edit.insert(text, &self.input.text, &settings, Change::Typing);
+            }
+
+            for key in &self.input.keys {
+                match key {
+                    UiKey::Backspace => changed |= edit.delete(text, false, word),
+                    UiKey::Delete => changed |= edit.delete(text, true, word),
+                    UiKey::Left => edit.move_left(text, word, shift),
+                    UiKey::Right => edit.move_right(text, word, shift),
+                    UiKey::Home | UiKey::Up => edit.move_caret(0, shift),
+                    UiKey::End | UiKey::Down => edit.move_caret(text.len(), shift),
+                    UiKey::SelectAll => edit.select_all(text),
+                    UiKey::Copy => {
+                        let selected = edit.get_selected_text(text);
+
+                        if !selected.is_empty() {
+                            self.copied_text = Some(selected.to_string());
+                        }
+                    }
+                    UiKey::Cut => {
+                        if let Some(selected) = edit.cut(text) {
+                            self.copied_text = Some(selected);
+                            changed = true;
+                        }
+                    }
+                    UiKey::Paste => {
+                        if let Some(pasted) = &self.input.clipboard {
+                            changed |= edit.insert(text, pasted, &settings, Change::Other);
+                        }
+                    }
+                    UiKey::Undo => changed |= edit.undo(text),
+                    UiKey::Redo => changed |= edit.redo(text),
+                    UiKey::Enter | UiKey::Escape => finished = true,
+                    UiKey::Tab => {}
+                }
+            }
+
+            response.changed = changed;
+
+            // Scrolls just far enough to keep the caret in view.
+            let caret_x = text_edit::caret_offset(text, edit.caret, scale);
+            let (text_width, _) = crate::graphics::measure_text(text, scale);
+            let max_scroll = (text_width - visible_width).max(0.0);
+
+            edit.scroll = edit
+                .scroll
+                .max(caret_x - visible_width)
+                .min(caret_x)
+                .clamp(0.0, max_scroll);
+        }
+
+        self.draw_field(rect);
+
+        // Anything past the edge gets left out, rather than spilling over.
+        self.clip_stack.push(rect);
+
+        if text.is_empty() && edit.is_none() {
+            let dim = self.theme.text_dim;
+            self.draw_text_in(&rect, Ui::display_text(label), dim, false);
+        } else {
+            let scroll = edit.as_ref().map_or(0.0, |edit| edit.scroll);
+            let text_x = rect.x + padding - scroll;
+            let offset = |position: usize| text_x + text_edit::caret_offset(text, position, scale);
+
+            if let Some(edit) = &edit {
+                let selection = edit.get_selection();
+
+                if !selection.is_empty() {
+                    let (start, end) = (offset(selection.start), offset(selection.end));
+                    let highlight = Rect::new(
+                        start,
+                        rect.y + padding / 2.0,
+                        end - start,
+                        rect.height - padding,
+                    );
+                    let color = self.theme.selection;
+
+                    self.push_shape(
+                        Shape::Rect {
+                            rect: highlight,
+                            rounding: 0.0,
+                        },
+                        color,
+                    );
+                }
+            }
+
+            let visible = text_edit::visible_range(text, scroll, visible_width, scale);
+            let (_, height) = crate::graphics::measure_text(text, scale);
+            let position =
+                Vector2::new(offset(visible.start), rect.y + (rect.height - height) / 2.0);
+
+            let color = self.theme.text;
+            self.push_shape(
+                Shape::Text {
+                    position,
+                    text: text[visible].to_string(),
+                },
+                color,
+            );
+        }
+
+        if let Some(edit) = &edit {
+            let caret_x =
+                rect.x + padding - edit.scroll + text_edit::caret_offset(text, edit.caret, scale);
+            let caret = Rect::new(caret_x, rect.y + padding / 2.0, 1.0, rect.height - padding);
+
+            let accent = self.theme.accent;
+            self.push_shape(
+                Shape::Rect {
+                    rect: caret,
+                    rounding: 0.0,
+                },
+                accent,
+            );
+        }
+
+        self.clip_stack.pop();
+
+        if !finished {
+            self.text_edit = self.text_edit.take().or(edit);
+        }
+
+        return response;
+    }
+
+    /// Shows the selected option, and opens a list of all of them below
+    /// `rect` when clicked. The list goes on top of everything else, and
+    /// nothing under it can be clicked while it's open.
+    pub fn dropdown(
+        &mut self,
+        label: &str,
+        rect: Rect<f32>,
+        options: &[&str],
+        selected: &mut usize,
+    ) -> Response {
+        let id = self.get_id(label);
+        let mut response = self.interact(id, &rect);
+        let was_open = self.popup.as_ref().is_some_and(|popup| popup.owner == id);
+
+        let list = Rect::new(
+            rect.x,
+            rect.y + rect.height,
+            rect.width,
+            rect.height * options.len() as f32,
+        );
+
+        if response.clicked {
+            if was_open {
+                self.popup = None;
+            } else {
+                self.popup = Some(Popup {
+                    owner: id,
+                    owner_rect: rect,
+                    rect: list,
+                });
+
+                self.set_focus(Some(id.child(*selected)));
+            }
+        }
+
+        let text = self.theme.text;
+        self.draw_widget(rect, &response);
+
+        let current = options.get(*selected).copied().unwrap_or("");
+        self.draw_text_in(&rect, current, text, false);
+
+        let arrow = Rect::new(
+            rect.x + rect.width - rect.height,
+            rect.y,
+            rect.height,
+            rect.height,
+        );
+        self.draw_text_in(&arrow, "v", text, true);
+
+        let is_open = self.popup.as_ref().is_some_and(|popup| popup.owner == id);
+
+        if !is_open {
+            return response;
+        }
+
+        self.popup_seen = true;
+
+        let (shape, color) = self.skinned_shape(list, self.theme.panel_slice, self.theme.panel);
+        self.push_overlay(shape, color);
+
+        for (i, option) in options.iter().enumerate() {
+            let option_rect = Rect::new(
+                list.x,
+                list.y + rect.height * i as f32,
+                list.width,
+                rect.height,
+            );
+
+            // The list is on top of everything, so the clip rect and the list
+            // itself don't get in the way here.
+            let over = option_rect.contains_point(self.input.mouse_position);
+            self.add_focusable(id.child(i), &option_rect, true);
+            let option_response = self.interact_with(id.child(i), &option_rect, over);
+
+            if option_response.hovered || option_response.focused || i == *selected {
+                let highlight = if option_response.focused && !option_response.active {
+                    self.theme.widget.hovered
+                } else {
+                    self.theme.widget.get(&option_response)
+                };
+
+                self.push_overlay(
+                    Shape::Rect {
+                        rect: option_rect,
+                        rounding: 0.0,
+                    },
+                    highlight,
+                );
+            }
+
+            let (_, height) = crate::graphics::measure_text(option, self.theme.text_scale);
+            let position = Vector2::new(
+                option_rect.x + self.theme.padding,
+                option_rect.y + (option_rect.height - height) / 2.0,
+            );
+
+            self.push_overlay(
+                Shape::Text {
+                    position,
+                    text: option.to_string(),
+                },
+                text,
+            );
+
+            if option_response.clicked {
+                if *selected != i {
+                    *selected = i;
+                    response.changed = true;
+                }
+
+                self.popup = None;
+                self.set_focus(Some(id));
+            }
+        }
+
+        // So that tooltips go with the dropdown, not its last option.
+        self.last_widget = Some(id);
+        return response;
+    }
+
+    /// Starts a panel with `content_height` worth of widgets in it, which
+    /// scrolls with the mouse wheel or by dragging its scroll bar. The widgets
+    /// go in the returned rect, which has already been moved up by how far
+    /// it's scrolled, and anything outside the panel isn't drawn or clickable.
+    /// Has to be followed by `end_scroll_panel`.
+    pub fn begin_scroll_panel(
+        &mut self,
+        label: &str,
+        rect: Rect<f32>,
+        content_height: f32,
+    ) -> Rect<f32> {
+        let id = self.get_id(label);
+        let max_scroll = (content_height - rect.height).max(0.0);
+        let mut scroll = self.scroll_offsets.get(&id).copied().unwrap_or(0.0);
+
+        if self.is_mouse_over(&rect) {
+            scroll -= self.input.scroll * self.theme.scroll_speed;
+            self.mouse_over_panel = true;
+        }
+
+        self.draw_panel(rect);
+
+        let scrollbar_width = if max_scroll > 0.0 {
+            self.theme.scrollbar_width
+        } else {
+            0.0
+        };
+
+        if max_scroll > 0.0 {
+            let track_height = rect.height;
+            let thumb_height = (rect.height / content_height * track_height).max(scrollbar_width);
+            let travel = track_height - thumb_height;
+            let x = rect.x + rect.width - scrollbar_width;
+
+            let thumb = Rect::new(
+                x,
+                rect.y + scroll.clamp(0.0, max_scroll) / max_scroll * travel,
+                scrollbar_width,
+                thumb_height,
+            );
+
+            // Not focusable, since the keyboard can scroll by moving the focus.
+            let over = self.is_mouse_over(&thumb);
+            let response = self.interact_with(id.child("scrollbar"), &thumb, over);
+
+            if response.active && travel > 0.0 {
+                let thumb_y = self.input.mouse_position.y - self.drag_offset.y;
+                scroll = (thumb_y - rect.y) / travel * max_scroll;
+            }
+
+            self.draw_widget(thumb, &response);
+        }
+
+        scroll = scroll.clamp(0.0, max_scroll);
+        self.scroll_offsets.insert(id, scroll);
+
+        let viewport = Rect::new(rect.x, rect.y, rect.width - scrollbar_width, rect.height);
+        let clip = match self.clip_stack.last() {
+            Some(outer) => viewport
+                .intersection(outer)
+                .unwrap_or(Rect::new(rect.x, rect.y, 0.0, 0.0)),
+            None => viewport,
+        };
+
+        self.clip_stack.push(clip);
+        self.panel_stack.push(id);
+        self.push_id(label);
+
+        return Rect::new(rect.x, rect.y - scroll, viewport.width, content_height);
+    }
+
+    pub fn end_scroll_panel(&mut self) {
+        self.pop_id();
+        self.panel_stack.pop();
+        self.clip_stack.pop();
+    }
+}
@@ -0,0 +1,362 @@
+use bgf::audio::{Audio, NullBackend, PlaySettings, Sound, VolumeSettings, WavFileBackend};
+
+fn mix(audio: &Audio, frames: usize) -> Vec<f32> {
+    let mut output = vec![0.0; frames * 2];
+    audio.mix(&mut output);
+    return output;
+}
+
+fn left(output: &[f32]) -> Vec<f32> {
+    output.iter().step_by(2).copied().collect()
+}
+
+#[test]
+fn mixes_mono_into_both_channels_with_volume_and_pan() {
+    let audio = Audio::new(NullBackend::new(100)).unwrap();
+    let sound = Sound::from_samples(vec![0.5; 10], 1, 100);
+
+    audio.play(&sound);
+    assert_eq!(&mix(&audio, 1)[..], &[0.5, 0.5]);
+
+    audio.stop_all();
+    audio.play_with(
+        &sound,
+        PlaySettings {
+            volume: 0.5,
+            pan: 1.0,
+            ..Default::default()
+        },
+    );
+    assert_eq!(&mix(&audio, 1)[..], &[0.0, 0.25]);
+}
+
+#[test]
+fn sums_sounds_and_clamps_the_result() {
+    let audio = Audio::new(NullBackend::new(100)).unwrap();
+    let sound = Sound::from_samples(vec![0.75, -0.75], 2, 100);
+
+    audio.play(&sound);
+    audio.play(&sound);
+
+    assert_eq!(&mix(&audio, 1)[..], &[1.0, -1.0]);
+}
+
+#[test]
+fn finishes_at_the_end_unless_looping() {
+    let audio = Audio::new(NullBackend::new(100)).unwrap();
+    let sound = Sound::from_samples(vec![0.1, 0.2, 0.3], 1, 100);
+
+    let once = audio.play(&sound);
+    assert_eq!(left(&mix(&audio, 5)), vec![0.1, 0.2, 0.3, 0.0, 0.0]);
+    assert!(!once.is_playing());
+
+    let looping = audio.play_with(
+        &sound,
+        PlaySettings {
+            looping: true,
+            ..Default::default()
+        },
+    );
+    assert_eq!(left(&mix(&audio, 5)), vec![0.1, 0.2, 0.3, 0.1, 0.2]);
+    assert!(looping.is_playing());
+
+    looping.set_looping(false);
+    mix(&audio, 5);
+    assert!(!looping.is_playing());
+}
+
+#[test]
+fn resamples_to_the_output_rate_and_pitch() {
+    let audio = Audio::new(NullBackend::new(100)).unwrap();
+    let sound = Sound::from_samples(vec![0.0, 0.5, 1.0], 1, 50);
+
+    audio.play(&sound);
+    assert_eq!(
+        left(&mix(&audio, 7)),
+        vec![0.0, 0.25, 0.5, 0.75, 1.0, 1.0, 0.0]
+    );
+
+    audio.play_with(
+        &sound,
+        PlaySettings {
+            pitch: 4.0,
+            ..Default::default()
+        },
+    );
+    assert_eq!(left(&mix(&audio, 3)), vec![0.0, 1.0, 0.0]);
+}
+
+#[test]
+fn pauses_resumes_and_stops() {
+    let audio = Audio::new(NullBackend::new(100)).unwrap();
+    let sound = Sound::from_samples(vec![0.1, 0.2, 0.3, 0.4], 1, 100);
+
+    let handle = audio.play(&sound);
+    assert_eq!(left(&mix(&audio, 1)), vec![0.1]);
+
+    handle.pause();
+    assert!(handle.is_paused());
+    assert_eq!(left(&mix(&audio, 2)), vec![0.0, 0.0]);
+
+    handle.resume();
+    assert_eq!(left(&mix(&audio, 1)), vec![0.2]);
+
+    handle.stop();
+    assert!(!handle.is_playing());
+    assert_eq!(left(&mix(&audio, 1)), vec![0.0]);
+
+    // Handles to finished sounds don't do anything.
+    handle.set_volume(1.0);
+    handle.resume();
+    assert!(!handle.is_playing());
+}
+
+#[test]
+fn fades_in_and_out() {
+    let audio = Audio::new(NullBackend::new(4)).unwrap();
+    let sound = Sound::from_samples(vec![1.0; 100], 1, 4);
+
+    let handle = audio.play_with(
+        &sound,
+        PlaySettings {
+            fade_in: 1.0,
+            ..Default::default()
+        },
+    );
+    assert_eq!(left(&mix(&audio, 5)), vec![0.0, 0.25, 0.5, 0.75, 1.0]);
+
+    handle.fade_out(0.5);
+    assert_eq!(left(&mix(&audio, 3)), vec![1.0, 0.5, 0.0]);
+    assert!(!handle.is_playing());
+}
+
+#[test]
+fn writes_the_mix_to_a_wav_file() {
+    let path = std::env::temp_dir().join("bgf_audio_mixer_test.wav");
+    let path = path.to_str().unwrap();
+
+    {
+        let mut audio = Audio::new(WavFileBackend::new(path, 1000)).unwrap();
+        audio.play_with(
+            &Sound::from_samples(vec![0.5; 1000], 1, 1000),
+            PlaySettings {
+                pan: -1.0,
+                ..Default::default()
+            },
+        );
+
+        audio.update(0.05).unwrap();
+        audio.update(0.05).unwrap();
+    }
+
+    let written = Sound::load(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    assert_eq!(written.get_channels(), 2);
+    assert_eq!(written.get_sample_rate(), 1000);
+    assert!((written.get_duration() - 0.1).abs() < 1e-9);
+}
+
+#[test]
+fn rejects_files_that_are_not_audio() {
+    let path = std::env::temp_dir().join("bgf_audio_mixer_test.txt");
+    std::fs::write(&path, "not audio").unwrap();
+
+    let error = Sound::load(path.to_str().unwrap()).err().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(matches!(error, bgf::Error::AudioDecode { .. }));
+}
+
+#[test]
+fn rejects_files_that_are_cut_short() {
+    let path = std::env::temp_dir().join("bgf_audio_mixer_short_test.wav");
+    std::fs::write(&path, "RI").unwrap();
+
+    let error = Sound::load(path.to_str().unwrap()).err().unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(matches!(error, bgf::Error::AudioDecode { .. }));
+}
+
+#[test]
+fn streams_music_and_loops_it() {
+    let path = std::env::temp_dir().join("bgf_audio_mixer_music_test.wav");
+    let path = path.to_str().unwrap();
+    let ramp: Vec<f32> = (0..10).map(|i| i as f32 / 10.0).collect();
+
+    {
+        let mut audio = Audio::new(WavFileBackend::new(path, 100)).unwrap();
+        audio.play(&Sound::from_samples(ramp.clone(), 1, 100));
+        audio.update(0.1).unwrap();
+    }
+
+    let mut audio = Audio::new(NullBackend::new(100)).unwrap();
+    let looping = audio
+        .play_music(
+            path,
+            PlaySettings {
+                looping: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+    // It goes straight from the end of the file into the start again.
+    let expected: Vec<f32> = ramp
+        .iter()
+        .chain(&ramp)
+        .chain(&ramp[..5])
+        .copied()
+        .collect();
+    assert_eq!(left(&mix(&audio, 25)), expected);
+
+    looping.stop();
+    let once = audio.play_music(path, PlaySettings::default()).unwrap();
+    audio.update(0.0).unwrap();
+
+    let mut expected = ramp.clone();
+    expected.extend([0.0; 5]);
+    assert_eq!(left(&mix(&audio, 15)), expected);
+    assert!(!once.is_playing());
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn streams_files_with_more_than_two_channels() {
+    let path = std::env::temp_dir().join("bgf_audio_mixer_surround_test.wav");
+    let path = path.to_str().unwrap();
+
+    // 5.1, which doesn't fit evenly into the decoder's chunks. Each channel
+    // has its own value, so a chunk that started in the middle of a frame
+    // would swap them around.
+    let spec = hound::WavSpec {
+        channels: 6,
+        sample_rate: 1000,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(path, spec).unwrap();
+
+    for _ in 0..1000 {
+        for channel in 0..6 {
+            writer.write_sample((channel + 1) as f32 / 10.0).unwrap();
+        }
+    }
+
+    writer.finalize().unwrap();
+
+    let audio = Audio::new(NullBackend::new(1000)).unwrap();
+    audio.play_music(path, PlaySettings::default()).unwrap();
+    let output = mix(&audio, 1000);
+    std::fs::remove_file(path).unwrap();
+
+    // Only the front left and right are played.
+    for frame in output.chunks_exact(2) {
+        assert_eq!(frame, [0.1, 0.2]);
+    }
+}
+
+#[test]
+fn applies_bus_volume_and_mute() {
+    let audio = Audio::new(NullBackend::new(100)).unwrap();
+    let sound = Sound::from_samples(vec![1.0; 100], 1, 100);
+
+    audio.play(&sound);
+    audio.set_bus_volume("sfx", 0.5);
+
+    // Volume changes are spread over the next mix so they don't click.
+    assert_eq!(left(&mix(&audio, 2)), vec![0.75, 0.5]);
+    assert_eq!(left(&mix(&audio, 2)), vec![0.5, 0.5]);
+
+    audio.set_bus_muted("master", true);
+    mix(&audio, 1);
+    assert_eq!(left(&mix(&audio, 1)), vec![0.0]);
+    assert_eq!(audio.is_bus_muted("master"), Some(true));
+    assert_eq!(audio.get_bus_volume("nonexistent"), None);
+}
+
+#[test]
+fn ducks_music_while_voice_plays() {
+    let audio = Audio::new(NullBackend::new(100)).unwrap();
+    audio.duck("music", "voice", 0.25, 0.1, 0.1);
+
+    audio.play_with(
+        &Sound::from_samples(vec![1.0; 10], 1, 100),
+        PlaySettings {
+            looping: true,
+            bus: Some("music".to_string()),
+            ..Default::default()
+        },
+    );
+    assert_eq!(left(&mix(&audio, 10)), vec![1.0; 10]);
+
+    let voice = audio.play_with(
+        &Sound::from_samples(vec![0.0; 1000], 1, 100),
+        PlaySettings {
+            bus: Some("voice".to_string()),
+            ..Default::default()
+        },
+    );
+    mix(&audio, 10);
+    assert_eq!(left(&mix(&audio, 10)), vec![0.25; 10]);
+
+    voice.stop();
+    mix(&audio, 10);
+    assert_eq!(left(&mix(&audio, 10)), vec![1.0; 10]);
+}
+
+#[test]
+fn low_pass_smooths_out_sudden_changes() {
+    let audio = Audio::new(NullBackend::new(1000)).unwrap();
+    audio.add_bus_effect("sfx", bgf::audio::LowPass::new(50.0));
+    audio.play(&Sound::from_samples(vec![1.0; 100], 1, 1000));
+
+    let output = left(&mix(&audio, 50));
+    assert!(output[0] < 0.5);
+    assert!(output.windows(2).all(|pair| pair[0] < pair[1]));
+
+    audio.clear_bus_effects("sfx");
+    assert_eq!(left(&mix(&audio, 1)), vec![1.0]);
+}
+
+#[test]
+fn reverb_rings_on_after_the_sound_ends() {
+    let audio = Audio::new(NullBackend::new(8000)).unwrap();
+    audio.add_bus_effect("sfx", bgf::audio::Reverb::new(0.8, 0.5, 0.5));
+    audio.play(&Sound::from_samples(vec![0.5; 100], 1, 8000));
+
+    mix(&audio, 100);
+    let tail = mix(&audio, 400);
+
+    assert!(tail.iter().any(|sample| sample.abs() > 1e-4));
+    assert!(tail.iter().all(|sample| sample.is_finite()));
+}
+
+#[test]
+fn saves_and_restores_volume_settings() {
+    let audio = Audio::new(NullBackend::new(100)).unwrap();
+    audio.add_bus("ambience");
+    audio.set_bus_volume("music", 0.8);
+    audio.set_bus_muted("ambience", true);
+
+    let path = std::env::temp_dir().join("bgf_audio_mixer_test.cfg");
+    let path = path.to_str().unwrap();
+    audio.get_volume_settings().save(path).unwrap();
+
+    let loaded = VolumeSettings::load(path).unwrap();
+    std::fs::remove_file(path).unwrap();
+    assert_eq!(loaded, audio.get_volume_settings());
+
+    let other = Audio::new(NullBackend::new(100)).unwrap();
+    other.apply_volume_settings(&loaded);
+    assert_eq!(other.get_bus_volume("music"), Some(0.8));
+    assert_eq!(other.is_bus_muted("ambience"), Some(true));
+
+    let error = "master = 1\nmusic = loud"
+        .parse::<VolumeSettings>()
+        .err()
+        .unwrap();
+    assert!(matches!(error, bgf::Error::Parse { line: 2, .. }));
+}
@@ -0,0 +1,118 @@
+use bgf::audio::{Attenuation, Audio, NullBackend, PlaySettings, Rolloff, Sound};
+use bgf::math::Vector2;
+
+fn mix(audio: &Audio, frames: usize) -> Vec<f32> {
+    let mut output = vec![0.0; frames * 2];
+    audio.mix(&mut output);
+    return output;
+}
+
+fn attenuation(rolloff: Rolloff, factor: f32) -> Attenuation {
+    Attenuation {
+        rolloff,
+        factor,
+        reference_distance: 10.0,
+        max_distance: 110.0,
+    }
+}
+
+fn assert_close(a: f32, b: f32) {
+    assert!((a - b).abs() < 1e-5, "{} is not close to {}", a, b);
+}
+
+#[test]
+fn rolloff_models() {
+    let linear = attenuation(Rolloff::Linear, 1.0);
+    assert_close(linear.get_gain(5.0), 1.0);
+    assert_close(linear.get_gain(60.0), 0.5);
+    assert_close(linear.get_gain(500.0), 0.0);
+
+    let inverse = attenuation(Rolloff::Inverse, 1.0);
+    assert_close(inverse.get_gain(20.0), 0.5);
+    assert_close(inverse.get_gain(40.0), 0.25);
+    // It stops getting quieter past the maximum distance.
+    assert_close(inverse.get_gain(1000.0), inverse.get_gain(110.0));
+
+    let exponential = attenuation(Rolloff::Exponential, 2.0);
+    assert_close(exponential.get_gain(20.0), 0.25);
+    assert_close(attenuation(Rolloff::Exponential, 0.0).get_gain(80.0), 1.0);
+}
+
+#[test]
+fn attenuates_and_pans_by_position() {
+    let mut audio = Audio::new(NullBackend::new(100)).unwrap();
+    audio.set_default_attenuation(Attenuation {
+        rolloff: Rolloff::Inverse,
+        factor: 1.0,
+        reference_distance: 100.0,
+        max_distance: 1000.0,
+    });
+    audio.set_listener(Vector2 { x: 50.0, y: 0.0 }, Vector2 { x: 0.0, y: 0.0 });
+
+    let sound = Sound::from_samples(vec![1.0; 100], 1, 100);
+
+    let far = audio.play_at(
+        &sound,
+        Vector2 { x: 250.0, y: 0.0 },
+        PlaySettings::default(),
+    );
+    let output = mix(&audio, 1);
+    assert_close(output[0], 0.0);
+    assert_close(output[1], 0.5);
+
+    // Within the reference distance it's at full volume and pans less.
+    far.set_position(Vector2 { x: 0.0, y: 0.0 });
+    let output = mix(&audio, 1);
+    assert_close(output[0], 1.0);
+    assert_close(output[1], 0.5);
+}
+
+#[test]
+fn silent_past_the_maximum_distance_with_linear_rolloff() {
+    let mut audio = Audio::new(NullBackend::new(100)).unwrap();
+    audio.set_default_attenuation(attenuation(Rolloff::Linear, 1.0));
+
+    let sound = Sound::from_samples(vec![1.0; 100], 1, 100);
+    let handle = audio.play_at(
+        &sound,
+        Vector2 { x: 0.0, y: 500.0 },
+        PlaySettings::default(),
+    );
+
+    assert!(mix(&audio, 10).iter().all(|sample| *sample == 0.0));
+    assert!(handle.is_playing());
+}
+
+#[test]
+fn doppler_raises_pitch_when_approaching() {
+    let mut audio = Audio::new(NullBackend::new(100)).unwrap();
+    audio.set_default_attenuation(attenuation(Rolloff::Inverse, 0.0));
+    audio.set_listener(Vector2 { x: 100.0, y: 0.0 }, Vector2 { x: 0.0, y: 0.0 });
+
+    // A ramp, so how fast it's played can be read off the output.
+    let ramp: Vec<f32> = (0..100).map(|i| i as f32 / 100.0).collect();
+    let sound = Sound::from_samples(ramp, 1, 100);
+
+    let play = |velocity: Vector2<f32>| {
+        let handle = audio.play_at(&sound, Vector2 { x: 0.0, y: 0.0 }, PlaySettings::default());
+        handle.set_velocity(velocity);
+
+        let output = mix(&audio, 2);
+        handle.stop();
+        return output[2];
+    };
+
+    // Without a speed of sound, velocity doesn't matter.
+    assert_close(play(Vector2 { x: 50.0, y: 0.0 }), 0.01);
+
+    audio.set_speed_of_sound(Some(100.0));
+    assert_close(play(Vector2 { x: 50.0, y: 0.0 }), 0.02);
+    assert_close(play(Vector2 { x: -50.0, y: 0.0 }), 0.01 * 100.0 / 150.0);
+
+    // Moving sideways doesn't change the distance, so there's no shift.
+    assert_close(play(Vector2 { x: 0.0, y: 50.0 }), 0.01);
+
+    // Neither does moving along with the listener.
+    audio.set_listener(Vector2 { x: 100.0, y: 0.0 }, Vector2 { x: 50.0, y: 0.0 });
+    assert_close(play(Vector2 { x: 50.0, y: 0.0 }), 0.01);
+}
@@ -0,0 +1,118 @@
+use bgf::graphics::{colors, Color};
+use bgf::math::Vector4;
+use proptest::prelude::*;
+
+fn close(a: &Color, b: &Color) -> bool {
+    let (a, b): ([f32; 4], [f32; 4]) = ((*a).into(), (*b).into());
+    a.iter().zip(b).all(|(a, b)| (a - b).abs() < 1e-4)
+}
+
+fn color() -> impl Strategy<Value = Color> {
+    (0.0..=1.0f32, 0.0..=1.0f32, 0.0..=1.0f32, 0.0..=1.0f32)
+        .prop_map(|(red, green, blue, alpha)| Color::new(red, green, blue, alpha))
+}
+
+proptest! {
+    #[test]
+    fn hsv_and_hsl_round_trip(c in color()) {
+        let (hue, saturation, value) = c.to_hsv();
+        prop_assert!(close(&Color::from_hsv(hue, saturation, value, c.alpha), &c));
+
+        let (hue, saturation, lightness) = c.to_hsl();
+        prop_assert!(close(&Color::from_hsl(hue, saturation, lightness, c.alpha), &c));
+    }
+
+    #[test]
+    fn linear_round_trips(c in color()) {
+        prop_assert!(close(&c.to_linear().to_srgb(), &c));
+        prop_assert_eq!(c.to_linear().alpha, c.alpha);
+    }
+
+    #[test]
+    fn hex_and_bytes_round_trip(bytes in any::<[u8; 4]>()) {
+        let [red, green, blue, alpha] = bytes;
+        let c = Color::from_rgba8(red, green, blue, alpha);
+
+        prop_assert_eq!(c.to_rgba8(), bytes);
+        prop_assert_eq!(Color::from_hex(&c.to_hex()), Some(c));
+    }
+}
+
+#[test]
+fn parses_hex_strings() {
+    assert_eq!(
+        Color::from_hex("#ff8000"),
+        Some(Color::from_rgb8(255, 128, 0))
+    );
+    assert_eq!(
+        Color::from_hex("FF800080"),
+        Some(Color::from_rgba8(255, 128, 0, 128))
+    );
+    assert_eq!(Color::from_hex("#f80"), Some(Color::from_rgb8(255, 136, 0)));
+    assert_eq!(
+        Color::from_hex("#f808"),
+        Some(Color::from_rgba8(255, 136, 0, 136))
+    );
+
+    assert_eq!(
+        Color::from_hex("#ff80"),
+        Some(Color::from_rgba8(255, 255, 136, 0))
+    );
+    assert_eq!(Color::from_hex("#ff800"), None);
+    assert_eq!(Color::from_hex("#gg8000"), None);
+    assert_eq!(Color::from_hex("#ÿÿÿ"), None);
+
+    assert_eq!(colors::ORANGE.to_hex(), "#ff8000");
+    assert_eq!(colors::WHITE.fade(0.5).to_hex(), "#ffffff80");
+}
+
+#[test]
+fn converts_hues() {
+    assert!(close(
+        &Color::from_hsv(120.0, 1.0, 1.0, 1.0),
+        &colors::GREEN
+    ));
+    assert!(close(
+        &Color::from_hsv(-120.0, 1.0, 1.0, 1.0),
+        &colors::BLUE
+    ));
+    assert!(close(
+        &Color::from_hsl(60.0, 1.0, 0.5, 1.0),
+        &colors::YELLOW
+    ));
+    assert!(close(&Color::from_hsl(0.0, 0.0, 0.5, 1.0), &colors::GRAY));
+
+    assert_eq!(colors::MAGENTA.to_hsv(), (300.0, 1.0, 1.0));
+    assert_eq!(colors::BLACK.to_hsl(), (0.0, 0.0, 0.0));
+}
+
+#[test]
+fn blends_and_adjusts() {
+    let half = colors::BLACK.lerp(&colors::WHITE, 0.5);
+    assert_eq!(half, Color::rgb(0.5, 0.5, 0.5));
+
+    let faded = colors::RED.with_alpha(0.5);
+    assert_eq!(faded.premultiply(), Color::new(0.5, 0.0, 0.0, 0.5));
+    assert_eq!(faded.lighten(1.0), Color::new(1.0, 1.0, 1.0, 0.5));
+    assert_eq!(faded.darken(0.5), Color::new(0.5, 0.0, 0.0, 0.5));
+
+    // Half of sRGB's range is only about a fifth as bright.
+    assert!((colors::GRAY.to_linear().red - 0.214).abs() < 1e-3);
+    assert!((colors::WHITE.get_luminance() - 1.0).abs() < 1e-5);
+    assert!(colors::YELLOW.get_luminance() > colors::BLUE.get_luminance());
+}
+
+#[test]
+fn looks_up_named_colors() {
+    assert_eq!(Color::from_name("Sky Blue"), Some(colors::SKY_BLUE));
+    assert_eq!(Color::from_name("light-gray"), Some(colors::LIGHT_GRAY));
+    assert_eq!(Color::from_name("chartreuse"), None);
+
+    for (name, color) in colors::ALL {
+        assert_eq!(Color::from_name(name), Some(color));
+    }
+
+    let vector: Vector4<f32> = colors::CYAN.into();
+    assert_eq!(vector, Vector4::new(0.0, 1.0, 1.0, 1.0));
+    assert_eq!(Color::from(vector), colors::CYAN);
+}
@@ -0,0 +1,71 @@
+use bgf::graphics::{colors, DebugDraw, DebugShape};
+use bgf::math::Vector2;
+
+#[test]
+fn shapes_queue_up_in_order() {
+    let mut debug = DebugDraw::new();
+    assert!(debug.is_enabled());
+
+    debug.rect(1.0, 2.0, 3.0, 4.0, &colors::RED);
+    debug.arrow(
+        &Vector2::new(0.0, 0.0),
+        &Vector2::new(5.0, 0.0),
+        &colors::GREEN,
+    );
+    debug.text(10.0, 20.0, "enemy", &colors::WHITE);
+
+    assert_eq!(
+        debug.get_shapes(),
+        &[
+            (
+                DebugShape::Rect {
+                    x: 1.0,
+                    y: 2.0,
+                    width: 3.0,
+                    height: 4.0,
+                },
+                colors::RED,
+            ),
+            (
+                DebugShape::Arrow {
+                    start: Vector2::new(0.0, 0.0),
+                    end: Vector2::new(5.0, 0.0),
+                },
+                colors::GREEN,
+            ),
+            (
+                DebugShape::Text {
+                    x: 10.0,
+                    y: 20.0,
+                    text: "enemy".to_string(),
+                },
+                colors::WHITE,
+            ),
+        ]
+    );
+
+    debug.clear();
+    assert!(debug.get_shapes().is_empty());
+}
+
+#[test]
+fn turning_it_off_drops_everything() {
+    let mut debug = DebugDraw::new();
+    debug.point(&Vector2::new(1.0, 1.0), &colors::RED);
+
+    debug.set_enabled(false);
+    assert!(debug.get_shapes().is_empty());
+
+    // Nothing gets queued while it's off.
+    debug.circle(&Vector2::new(0.0, 0.0), 4.0, &colors::RED);
+    debug.line(
+        &Vector2::new(0.0, 0.0),
+        &Vector2::new(1.0, 1.0),
+        &colors::RED,
+    );
+    assert!(debug.get_shapes().is_empty());
+
+    debug.set_enabled(true);
+    debug.circle(&Vector2::new(0.0, 0.0), 4.0, &colors::RED);
+    assert_eq!(debug.get_shapes().len(), 1);
+}
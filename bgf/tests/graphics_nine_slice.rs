@@ -0,0 +1,90 @@
+use bgf::graphics::{NineSlice, SliceMode, SlicePiece};
+use bgf::math::{Rect, Vector2};
+
+// A 32 by 32 texture with 8 pixel corners.
+const TEXTURE_SIZE: Vector2<f32> = Vector2 { x: 32.0, y: 32.0 };
+
+fn total_area(pieces: &[SlicePiece]) -> f32 {
+    pieces
+        .iter()
+        .map(|piece| piece.rect.width * piece.rect.height)
+        .sum()
+}
+
+#[test]
+fn stretching_keeps_the_corners() {
+    let slice = NineSlice::new(0.0, 8.0);
+    let pieces = slice.get_pieces(&Rect::new(10.0, 20.0, 100.0, 50.0), TEXTURE_SIZE);
+
+    assert_eq!(pieces.len(), 9);
+    assert_eq!(total_area(&pieces), 100.0 * 50.0);
+
+    // Top left corner, as it is in the texture.
+    assert_eq!(pieces[0].rect, Rect::new(10.0, 20.0, 8.0, 8.0));
+    assert_eq!(pieces[0].uv, Rect::new(0.0, 0.0, 0.25, 0.25));
+
+    // The middle gets everything else, from the middle of the texture.
+    assert_eq!(pieces[4].rect, Rect::new(18.0, 28.0, 84.0, 34.0));
+    assert_eq!(pieces[4].uv, Rect::new(0.25, 0.25, 0.5, 0.5));
+
+    // Bottom right corner.
+    assert_eq!(pieces[8].rect, Rect::new(102.0, 62.0, 8.0, 8.0));
+    assert_eq!(pieces[8].uv, Rect::new(0.75, 0.75, 0.25, 0.25));
+}
+
+#[test]
+fn tiling_repeats_and_cuts_the_last_one_short() {
+    // The edges and middle are 16 pixels in the texture. A 50 wide rect has a
+    // 34 pixel middle, so that's two whole tiles and one an eighth of one.
+    let slice = NineSlice::new(0.0, 8.0)
+        .with_edge_mode(SliceMode::Tile)
+        .with_center_mode(SliceMode::Tile);
+    let pieces = slice.get_pieces(&Rect::new(0.0, 0.0, 50.0, 24.0), TEXTURE_SIZE);
+
+    let top: Vec<&SlicePiece> = pieces
+        .iter()
+        .filter(|piece| piece.rect.y == 0.0 && piece.rect.x >= 8.0 && piece.rect.x < 42.0)
+        .collect();
+
+    assert_eq!(top.len(), 3);
+    assert_eq!(top[1].rect, Rect::new(24.0, 0.0, 16.0, 8.0));
+    assert_eq!(top[2].rect, Rect::new(40.0, 0.0, 2.0, 8.0));
+    assert_eq!(top[2].uv, Rect::new(0.25, 0.0, 0.0625, 0.25));
+
+    // The middle is 8 tall, so half a tile down and three across.
+    let middle: Vec<&SlicePiece> = pieces.iter().filter(|piece| piece.rect.y == 8.0).collect();
+    assert_eq!(middle.len(), 2 + 3);
+    assert_eq!(middle[1].uv.height, 0.25);
+
+    assert_eq!(total_area(&pieces), 50.0 * 24.0);
+}
+
+#[test]
+fn small_rects_squash_the_corners() {
+    let slice = NineSlice::new(0.0, 8.0).with_borders(8.0, 8.0, 24.0, 8.0);
+    let pieces = slice.get_pieces(&Rect::new(0.0, 0.0, 16.0, 40.0), TEXTURE_SIZE);
+
+    // No middle column, and the corners keep their proportions.
+    assert_eq!(pieces.len(), 6);
+    assert_eq!(pieces[0].rect.width, 4.0);
+    assert_eq!(pieces[1].rect, Rect::new(4.0, 0.0, 12.0, 8.0));
+    assert_eq!(total_area(&pieces), 16.0 * 40.0);
+
+    assert!(slice
+        .get_pieces(&Rect::new(0.0, 0.0, 0.0, 40.0), TEXTURE_SIZE)
+        .is_empty());
+}
+
+#[test]
+fn clipping_cuts_the_uvs_too() {
+    let piece = SlicePiece {
+        rect: Rect::new(0.0, 0.0, 100.0, 10.0),
+        uv: Rect::new(0.5, 0.0, 0.5, 1.0),
+    };
+
+    let clipped = piece.clip(&Rect::new(50.0, 5.0, 100.0, 100.0)).unwrap();
+    assert_eq!(clipped.rect, Rect::new(50.0, 5.0, 50.0, 5.0));
+    assert_eq!(clipped.uv, Rect::new(0.75, 0.5, 0.25, 0.5));
+
+    assert_eq!(piece.clip(&Rect::new(200.0, 0.0, 10.0, 10.0)), None);
+}
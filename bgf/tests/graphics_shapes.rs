@@ -0,0 +1,188 @@
+use bgf::graphics::shapes::{self, Geometry};
+use bgf::graphics::{LineCap, LineJoin};
+use bgf::math::Vector2;
+use std::f32::consts::PI;
+
+fn v(x: f32, y: f32) -> Vector2<f32> {
+    Vector2::new(x, y)
+}
+
+// Adds up the triangles, counting any overlaps twice.
+fn area(geometry: &Geometry) -> f32 {
+    geometry
+        .indices
+        .chunks(3)
+        .map(|triangle| {
+            let [a, b, c] = [0, 1, 2].map(|i| geometry.positions[triangle[i] as usize]);
+            (b - a).cross(c - a).abs() / 2.0
+        })
+        .sum()
+}
+
+fn has_position(geometry: &Geometry, position: Vector2<f32>) -> bool {
+    geometry
+        .positions
+        .iter()
+        .any(|other| other.distance(position) < 1e-4)
+}
+
+#[test]
+fn lines_and_their_caps() {
+    let line = shapes::polyline(
+        &[v(0.0, 0.0), v(10.0, 0.0)],
+        2.0,
+        LineCap::Butt,
+        LineJoin::Miter,
+        false,
+    );
+
+    assert_eq!(line.positions.len(), 4);
+    assert_eq!(line.indices.len(), 6);
+    assert!(has_position(&line, v(0.0, 1.0)));
+    assert!(has_position(&line, v(10.0, -1.0)));
+    assert_eq!(area(&line), 20.0);
+
+    // Square caps stick out by half the thickness at both ends.
+    let square = shapes::polyline(
+        &[v(0.0, 0.0), v(10.0, 0.0)],
+        2.0,
+        LineCap::Square,
+        LineJoin::Miter,
+        false,
+    );
+    assert!(has_position(&square, v(-1.0, 1.0)));
+    assert!(has_position(&square, v(11.0, -1.0)));
+    assert_eq!(area(&square), 24.0);
+
+    // Round ones add half a circle at both ends.
+    let round = shapes::polyline(
+        &[v(0.0, 0.0), v(10.0, 0.0)],
+        2.0,
+        LineCap::Round,
+        LineJoin::Miter,
+        false,
+    );
+    assert!(round
+        .positions
+        .iter()
+        .all(|p| p.x >= -1.0 - 1e-4 && p.x <= 11.0 + 1e-4));
+    assert!((area(&round) - (20.0 + PI)).abs() < 0.1);
+}
+
+#[test]
+fn too_few_points_draw_nothing() {
+    let point = shapes::polyline(
+        &[v(1.0, 1.0), v(1.0, 1.0)],
+        2.0,
+        LineCap::Round,
+        LineJoin::Round,
+        false,
+    );
+    assert_eq!(point, Geometry::default());
+
+    assert_eq!(
+        shapes::convex_polygon(&[v(0.0, 0.0), v(1.0, 0.0)]),
+        Geometry::default()
+    );
+
+    // Repeated points are skipped rather than leaving gaps.
+    let repeated = shapes::polyline(
+        &[v(0.0, 0.0), v(10.0, 0.0), v(10.0, 0.0)],
+        2.0,
+        LineCap::Butt,
+        LineJoin::Miter,
+        false,
+    );
+    assert_eq!(repeated.positions.len(), 4);
+}
+
+#[test]
+fn joins_fill_the_outside_of_corners() {
+    let corner = [v(0.0, 0.0), v(10.0, 0.0), v(10.0, 10.0)];
+
+    // The miter's tip is where the two outside edges meet.
+    let miter = shapes::polyline(&corner, 2.0, LineCap::Butt, LineJoin::Miter, false);
+    assert!(has_position(&miter, v(11.0, -1.0)));
+
+    let bevel = shapes::polyline(&corner, 2.0, LineCap::Butt, LineJoin::Bevel, false);
+    assert!(!has_position(&bevel, v(11.0, -1.0)));
+    assert!(has_position(&bevel, v(10.0, -1.0)) && has_position(&bevel, v(11.0, 0.0)));
+    assert_eq!(area(&miter) - area(&bevel), 0.5);
+
+    // The round one goes through the middle of the corner.
+    let round = shapes::polyline(&corner, 2.0, LineCap::Butt, LineJoin::Round, false);
+    let middle = v(10.0, 0.0) + v(1.0, -1.0).normalize();
+    assert!(has_position(&round, middle));
+
+    // Sharp corners would spike out a long way, so they're beveled instead.
+    let sharp = [v(0.0, 0.0), v(10.0, 0.0), v(0.0, 1.0)];
+    let spike = shapes::polyline(&sharp, 2.0, LineCap::Butt, LineJoin::Miter, false);
+
+    assert!(spike
+        .positions
+        .iter()
+        .all(|p| sharp.iter().any(|point| p.distance(*point) <= 1.0 + 1e-4)));
+}
+
+#[test]
+fn closed_polylines_join_instead_of_capping() {
+    let square = [
+        v(0.0, 0.0),
+        v(10.0, 0.0),
+        v(10.0, 10.0),
+        v(0.0, 10.0),
+        v(0.0, 0.0),
+    ];
+    let outline = shapes::polyline(&square, 2.0, LineCap::Round, LineJoin::Miter, true);
+
+    for tip in [v(-1.0, -1.0), v(11.0, -1.0), v(11.0, 11.0), v(-1.0, 11.0)] {
+        assert!(has_position(&outline, tip));
+    }
+
+    // 12 by 12 on the outside and 8 by 8 on the inside, with no round caps.
+    // The sides overlap on the inside of each corner, which counts twice.
+    assert!((area(&outline) - (144.0 - 64.0 + 4.0)).abs() < 1e-3);
+}
+
+#[test]
+fn ellipses_and_arcs() {
+    let circle = shapes::ellipse(v(5.0, 5.0), v(4.0, 4.0), 64);
+    assert!((area(&circle) - PI * 16.0).abs() < 0.1);
+
+    let ellipse = shapes::ellipse(v(0.0, 0.0), v(4.0, 2.0), 64);
+    assert!((area(&ellipse) - PI * 8.0).abs() < 0.1);
+
+    // The outline goes on the inside of the edge.
+    let outline = shapes::ellipse_outline(v(0.0, 0.0), v(4.0, 4.0), 1.0, 64);
+    assert!((area(&outline) - PI * (16.0 - 9.0)).abs() < 0.1);
+    assert!(outline.positions.iter().all(|p| p.length() <= 4.0 + 1e-4));
+
+    // Arcs are centered on the radius.
+    let arc = shapes::arc(v(0.0, 0.0), 10.0, 0.0, PI / 2.0, 2.0, 16);
+    assert!(arc.positions.iter().all(|p| {
+        let distance = p.length();
+        distance >= 9.0 - 1e-4 && distance <= 11.0 + 1e-4 && p.x >= -1e-4 && p.y >= -1e-4
+    }));
+    assert!((area(&arc) - PI / 4.0 * (121.0 - 81.0)).abs() < 0.2);
+}
+
+#[test]
+fn rounded_rects() {
+    let sharp = shapes::rounded_rect(0.0, 0.0, 10.0, 4.0, 0.0, 4);
+    assert!((area(&sharp) - 40.0).abs() < 1e-4);
+
+    let rounded = shapes::rounded_rect(0.0, 0.0, 10.0, 4.0, 1.0, 32);
+    assert!((area(&rounded) - (40.0 - (4.0 - PI))).abs() < 0.01);
+
+    // The radius can't be more than half the shortest side, which makes a
+    // stadium.
+    let stadium = shapes::rounded_rect(0.0, 0.0, 10.0, 4.0, 100.0, 32);
+    assert!((area(&stadium) - (6.0 * 4.0 + PI * 4.0)).abs() < 0.05);
+
+    let outline = shapes::rounded_rect_outline(0.0, 0.0, 10.0, 10.0, 0.0, 1.0, 4);
+    assert!((area(&outline) - (100.0 - 64.0)).abs() < 1e-3);
+    assert!(outline
+        .positions
+        .iter()
+        .all(|p| p.x >= 0.0 && p.x <= 10.0 && p.y >= 0.0 && p.y <= 10.0));
+}
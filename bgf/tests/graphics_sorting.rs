@@ -0,0 +1,106 @@
+use bgf::graphics::sorting::{sort_draw_items, DrawItem};
+use bgf::graphics::{MaterialId, SortMode};
+
+// A 10 by 10 draw at `x`, `y`, numbered by where it was submitted through
+// `first_index`.
+fn item(number: usize, layer: i32, material: Option<usize>, x: f32, y: f32) -> DrawItem {
+    DrawItem {
+        layer,
+        material: material.map(MaterialId),
+        min_x: x,
+        min_y: y,
+        max_x: x + 10.0,
+        max_y: y + 10.0,
+        first_index: number,
+        index_count: 6,
+    }
+}
+
+fn sorted(mut items: Vec<DrawItem>, mode: SortMode) -> Vec<usize> {
+    sort_draw_items(&mut items, mode);
+    return items.iter().map(|item| item.first_index).collect();
+}
+
+#[test]
+fn submission_order_ignores_everything() {
+    let items = vec![
+        item(0, 5, Some(1), 0.0, 50.0),
+        item(1, 0, None, 100.0, 0.0),
+        item(2, 5, Some(1), 200.0, 0.0),
+        item(3, -1, None, 300.0, 10.0),
+    ];
+
+    assert_eq!(sorted(items, SortMode::Submission), vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn lower_layers_go_first_and_ties_keep_their_order() {
+    // Everything overlaps, so nothing can be grouped.
+    let items = vec![
+        item(0, 2, None, 0.0, 0.0),
+        item(1, 0, Some(0), 1.0, 0.0),
+        item(2, 2, Some(0), 2.0, 0.0),
+        item(3, -3, None, 3.0, 0.0),
+        item(4, 0, None, 4.0, 0.0),
+        item(5, 2, None, 5.0, 0.0),
+    ];
+
+    assert_eq!(sorted(items, SortMode::Layer), vec![3, 1, 4, 0, 2, 5]);
+}
+
+#[test]
+fn y_sort_goes_by_the_bottom_edge_within_a_layer() {
+    let items = vec![
+        item(0, 0, None, 0.0, 30.0),
+        item(1, 0, None, 0.0, 10.0),
+        item(2, -1, None, 0.0, 90.0),
+        item(3, 0, None, 0.0, 20.0),
+        // Same bottom edge as the first, so it stays after it.
+        item(4, 0, None, 5.0, 30.0),
+        item(5, 1, None, 0.0, 0.0),
+    ];
+
+    assert_eq!(sorted(items, SortMode::YSort), vec![2, 1, 3, 0, 4, 5]);
+}
+
+#[test]
+fn materials_are_grouped_when_nothing_is_in_the_way() {
+    // Nothing overlaps, so the second material 1 draw can join the first.
+    let apart = vec![
+        item(0, 0, Some(1), 0.0, 0.0),
+        item(1, 0, None, 20.0, 0.0),
+        item(2, 0, Some(1), 40.0, 0.0),
+        item(3, 0, None, 60.0, 0.0),
+    ];
+
+    assert_eq!(sorted(apart, SortMode::Layer), vec![0, 2, 1, 3]);
+
+    // Here the last one is on top of the one in between, so moving it would
+    // change what the frame looks like.
+    let overlapping = vec![
+        item(0, 0, Some(1), 0.0, 0.0),
+        item(1, 0, None, 20.0, 0.0),
+        item(2, 0, Some(1), 25.0, 0.0),
+    ];
+
+    assert_eq!(sorted(overlapping, SortMode::Layer), vec![0, 1, 2]);
+
+    // Draws never join ones on another layer.
+    let layers = vec![
+        item(0, 0, Some(1), 0.0, 0.0),
+        item(1, 1, None, 20.0, 0.0),
+        item(2, 1, Some(1), 40.0, 0.0),
+    ];
+
+    assert_eq!(sorted(layers, SortMode::Layer), vec![0, 1, 2]);
+
+    // Y-sorting only groups draws with the same bottom edge.
+    let rows = vec![
+        item(0, 0, Some(1), 0.0, 0.0),
+        item(1, 0, None, 20.0, 0.0),
+        item(2, 0, Some(1), 40.0, 0.0),
+        item(3, 0, Some(1), 60.0, 5.0),
+    ];
+
+    assert_eq!(sorted(rows, SortMode::YSort), vec![0, 2, 1, 3]);
+}
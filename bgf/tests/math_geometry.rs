@@ -0,0 +1,211 @@
+use bgf::math::{Circle, LineSegment, Polygon, Ray2D, Rect, Vector2};
+use bgf::ui::ButtonChecker;
+use proptest::prelude::*;
+
+fn v(x: f32, y: f32) -> Vector2<f32> {
+    Vector2::new(x, y)
+}
+
+fn close2(a: Vector2<f32>, b: Vector2<f32>) -> bool {
+    (a - b).length() < 1e-4
+}
+
+// Whole numbers, so corners survive being added up and taken apart again.
+fn rect() -> impl Strategy<Value = Rect<f32>> {
+    (-100..100, -100..100, 1..50, 1..50).prop_map(|(x, y, width, height)| {
+        Rect::new(x as f32, y as f32, width as f32, height as f32)
+    })
+}
+
+fn point() -> impl Strategy<Value = Vector2<f32>> {
+    (-200.0..200.0f32, -200.0..200.0f32).prop_map(Vector2::from)
+}
+
+proptest! {
+    #[test]
+    fn rect_intersection_is_inside_both(a in rect(), b in rect()) {
+        match a.intersection(&b) {
+            Some(overlap) => {
+                prop_assert!(a.overlaps(&b) && b.overlaps(&a));
+                prop_assert!(a.contains_rect(&overlap) && b.contains_rect(&overlap));
+            }
+            None => prop_assert!(!a.overlaps(&b)),
+        }
+
+        let union = a.union(&b);
+        prop_assert!(union.contains_rect(&a) && union.contains_rect(&b));
+    }
+
+    #[test]
+    fn closest_points_are_in_the_shape(r in rect(), p in point(), radius in 0.5..50.0f32) {
+        let on_rect = r.closest_point(p);
+        prop_assert!(r.expand(1e-3).contains_point(on_rect));
+        prop_assert_eq!(r.contains_point(p), on_rect == p);
+
+        let circle = Circle::new(r.get_center(), radius);
+        prop_assert!(circle.center.distance(circle.closest_point(p)) <= radius + 1e-3);
+
+        // Far away, two edges can be the same distance as far as f32 can tell,
+        // so the points might not match, but they have to be as close.
+        let polygon = r.to_polygon();
+        let on_polygon = polygon.closest_point(p);
+        prop_assert!((on_polygon.distance(p) - on_rect.distance(p)).abs() < 1e-3);
+        prop_assert!(r.expand(1e-3).contains_point(on_polygon));
+        prop_assert_eq!(polygon.get_bounds(), r);
+    }
+
+    #[test]
+    fn polygon_tests_agree_with_rects(a in rect(), b in rect(), p in point()) {
+        let inside_rect = a.contains_point(p);
+        let inside_polygon = a.to_polygon().contains_point(p);
+
+        // The polygon includes every edge, so it can only say yes more often.
+        prop_assert!(!inside_rect || inside_polygon);
+        prop_assert!(!a.overlaps(&b) || a.to_polygon().overlaps_rect(&b));
+    }
+
+    #[test]
+    fn rays_hit_the_edge_nearest_the_origin(r in rect(), p in point()) {
+        prop_assume!(!r.expand(1e-2).contains_point(p));
+        let ray = Ray2D::<f32>::new(p, r.get_center() - p);
+
+        let hit = ray.cast_rect(&r).unwrap();
+        prop_assert!(close2(r.closest_point(hit.point), hit.point));
+        prop_assert!(hit.normal.dot(ray.get_direction()) < 0.0);
+
+        let through_polygon = ray.cast_polygon(&r.to_polygon()).unwrap();
+        prop_assert!((through_polygon.distance - hit.distance).abs() < 1e-3);
+        prop_assert!(close2(through_polygon.normal, hit.normal));
+    }
+}
+
+#[test]
+fn rects_own_their_top_and_left_edges() {
+    let left = Rect::new(0.0f32, 0.0, 10.0, 10.0);
+    let right = Rect::new(10.0f32, 0.0, 10.0, 10.0);
+
+    assert!(left.contains_point(v(0.0, 0.0)));
+    assert!(!left.contains_point(v(10.0, 5.0)));
+    assert!(right.contains_point(v(10.0, 5.0)));
+    assert!(!left.contains_point(v(5.0, 10.0)));
+
+    assert!(!left.overlaps(&right));
+    assert_eq!(left.intersection(&right), None);
+    assert_eq!(left.union(&right), Rect::new(0.0, 0.0, 20.0, 10.0));
+    assert_eq!(left.expand(2.0), Rect::new(-2.0, -2.0, 14.0, 14.0));
+    assert_eq!(
+        left.expand_to_include(v(-5.0, 15.0)),
+        Rect::new(-5.0, 0.0, 15.0, 15.0)
+    );
+    assert_eq!(
+        Rect::<f32>::from_corners(v(10.0, 10.0), v(0.0, 5.0)),
+        Rect::new(0.0, 5.0, 10.0, 5.0)
+    );
+}
+
+#[test]
+fn button_hovering_uses_the_same_edges_as_rects() {
+    let mut checker = ButtonChecker::new();
+
+    checker.update_mouse_position(10.0, 20.0);
+    assert!(checker.is_button_hovered(10.0, 20.0, 100.0, 50.0));
+
+    checker.update_mouse_position(110.0, 30.0);
+    assert!(!checker.is_button_hovered(10.0, 20.0, 100.0, 50.0));
+    assert!(checker.is_rect_hovered(&Rect::new(110.0, 20.0, 100.0, 50.0)));
+}
+
+#[test]
+fn circles_include_their_edges() {
+    let circle = Circle::new(v(0.0, 0.0), 5.0f32);
+
+    assert!(circle.contains_point(v(5.0, 0.0)));
+    assert!(!circle.contains_point(v(5.1, 0.0)));
+    assert!(circle.overlaps(&Circle::new(v(10.0, 0.0), 5.0)));
+    assert!(!circle.overlaps(&Circle::new(v(10.1, 0.0), 5.0)));
+
+    assert!(circle.overlaps_rect(&Rect::new(5.0, -1.0, 2.0, 2.0)));
+    assert!(!circle.overlaps_rect(&Rect::new(4.0, 4.0, 2.0, 2.0)));
+    assert!(Rect::new(-1.0f32, -1.0, 2.0, 2.0).overlaps_circle(&circle));
+
+    assert_eq!(circle.closest_point(v(10.0, 0.0)), v(5.0, 0.0));
+    assert_eq!(circle.get_bounds(), Rect::new(-5.0, -5.0, 10.0, 10.0));
+}
+
+#[test]
+fn segments_cross_and_find_closest_points() {
+    let a = LineSegment::new(v(0.0, 0.0), v(10.0, 10.0));
+    let b = LineSegment::new(v(0.0, 10.0), v(10.0, 0.0));
+    let c = LineSegment::new(v(20.0, 0.0), v(30.0, 10.0));
+
+    assert!(close2(a.intersection(&b).unwrap(), v(5.0, 5.0)));
+    assert_eq!(a.intersection(&c), None);
+    assert_eq!(a.intersection(&a), None);
+
+    assert_eq!(a.closest_point(v(-5.0, -5.0)), v(0.0, 0.0));
+    assert!(close2(a.closest_point(v(10.0, 0.0)), v(5.0, 5.0)));
+    assert!((a.distance_to_point(v(10.0, 0.0)) - 50.0f32.sqrt()).abs() < 1e-4);
+    assert_eq!(b.get_bounds(), Rect::new(0.0, 0.0, 10.0, 10.0));
+}
+
+#[test]
+fn rays_hit_circles_and_segments() {
+    let ray = Ray2D::<f32>::new(v(-10.0, 0.0), v(2.0, 0.0));
+    assert_eq!(ray.get_direction(), v(1.0, 0.0));
+
+    let hit = ray.cast_circle(&Circle::new(v(0.0, 0.0), 2.0)).unwrap();
+    assert!((hit.distance - 8.0).abs() < 1e-5);
+    assert!(close2(hit.normal, v(-1.0, 0.0)));
+
+    assert!(ray.cast_circle(&Circle::new(v(0.0, 5.0), 2.0)).is_none());
+    assert!(ray.cast_circle(&Circle::new(v(-20.0, 0.0), 2.0)).is_none());
+
+    let inside = ray.cast_circle(&Circle::new(v(-10.0, 0.0), 2.0)).unwrap();
+    assert_eq!((inside.distance, inside.normal), (0.0, v(0.0, 0.0)));
+
+    let wall = LineSegment::new(v(0.0, -5.0), v(0.0, 5.0));
+    let hit = ray.cast_segment(&wall).unwrap();
+    assert_eq!(
+        (hit.distance, hit.point, hit.normal),
+        (10.0, v(0.0, 0.0), v(-1.0, 0.0))
+    );
+
+    let short_wall = LineSegment::new(v(0.0, 1.0), v(0.0, 5.0));
+    assert!(ray.cast_segment(&short_wall).is_none());
+}
+
+#[test]
+fn polygons_overlap_by_separating_axes() {
+    let triangle = Polygon::new(vec![v(0.0, 0.0), v(10.0, 0.0), v(0.0, 10.0)]);
+    let backwards = Polygon::new(vec![v(0.0, 10.0), v(10.0, 0.0), v(0.0, 0.0)]);
+
+    assert!(triangle.contains_point(v(2.0, 2.0)));
+    assert!(backwards.contains_point(v(2.0, 2.0)));
+    assert!(triangle.contains_point(v(5.0, 5.0)));
+    assert!(!triangle.contains_point(v(6.0, 6.0)));
+
+    // Their bounding boxes overlap, but the diagonal edge keeps them apart.
+    let square = Rect::new(6.0f32, 6.0, 4.0, 4.0);
+    assert!(triangle.get_bounds().overlaps(&square));
+    assert!(!triangle.overlaps_rect(&square));
+    assert!(triangle.overlaps_rect(&Rect::new(4.0, 4.0, 4.0, 4.0)));
+
+    assert!(!triangle.overlaps_circle(&Circle::new(v(8.0, 8.0), 2.0)));
+    assert!(triangle.overlaps_circle(&Circle::new(v(8.0, 8.0), 5.0)));
+}
+
+#[test]
+fn polygons_without_edges_overlap_nothing() {
+    let triangle = Polygon::new(vec![v(0.0, 0.0), v(10.0, 0.0), v(0.0, 10.0)]);
+    let point = Polygon::new(vec![v(2.0, 2.0)]);
+    let repeated = Polygon::new(vec![v(2.0, 2.0), v(2.0, 2.0), v(2.0, 2.0)]);
+
+    assert!(!point.overlaps(&triangle) && !triangle.overlaps(&point));
+    assert!(!repeated.overlaps(&triangle) && !triangle.overlaps(&repeated));
+    assert!(!point.overlaps_rect(&Rect::new(0.0, 0.0, 4.0, 4.0)));
+    assert!(!Polygon::<f32>::new(Vec::new()).overlaps(&triangle));
+
+    // Two points make a line, which can still cross things.
+    let line = Polygon::new(vec![v(-5.0, 2.0), v(5.0, 2.0)]);
+    assert!(line.overlaps(&triangle));
+}
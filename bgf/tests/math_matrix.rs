@@ -0,0 +1,138 @@
+use bgf::math::{self, Matrix3, Matrix4, Transform2D, Vector2, Vector3};
+use proptest::prelude::*;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() <= 1e-3 * (1.0 + a.abs().max(b.abs()))
+}
+
+fn close2(a: Vector2<f32>, b: Vector2<f32>) -> bool {
+    close(a.x, b.x) && close(a.y, b.y)
+}
+
+fn transform() -> impl Strategy<Value = Transform2D> {
+    (
+        -500.0..500.0f32,
+        -500.0..500.0f32,
+        -3.0..3.0f32,
+        0.1..4.0f32,
+        0.1..4.0f32,
+    )
+        .prop_map(|(x, y, rotation, scale_x, scale_y)| {
+            Transform2D::new(Vector2::new(x, y), rotation, Vector2::new(scale_x, scale_y))
+        })
+}
+
+fn point() -> impl Strategy<Value = Vector2<f32>> {
+    (-500.0..500.0f32, -500.0..500.0f32).prop_map(Vector2::from)
+}
+
+proptest! {
+    #[test]
+    fn transform_matches_its_matrices(t in transform(), p in point()) {
+        let expected = t.transform_point(p);
+
+        prop_assert!(close2(t.to_matrix3().transform_point(p), expected));
+
+        let through_matrix4 = t.to_matrix4().transform_point(Vector3::new(p.x, p.y, 0.0));
+        prop_assert!(close2(Vector2::new(through_matrix4.x, through_matrix4.y), expected));
+    }
+
+    #[test]
+    fn parent_times_child_applies_the_child_first(
+        parent in transform(),
+        child in transform(),
+        p in point(),
+    ) {
+        let combined = parent * child;
+        prop_assert!(close2(
+            combined.transform_point(p),
+            parent.transform_point(child.transform_point(p))
+        ));
+
+        let matrix = parent.to_matrix3() * child.to_matrix3();
+        prop_assert!(close2(matrix.transform_point(p), combined.transform_point(p)));
+    }
+
+    #[test]
+    fn inverses_undo_the_transform(t in transform(), p in point()) {
+        let moved = t.transform_point(p);
+
+        prop_assert!(close2(t.inverse().unwrap().transform_point(moved), p));
+        prop_assert!(close2(t.to_matrix3().inverse().unwrap().transform_point(moved), p));
+
+        let matrix4 = t.to_matrix4().inverse().unwrap();
+        let back = matrix4.transform_point(Vector3::new(moved.x, moved.y, 0.0));
+        prop_assert!(close2(Vector2::new(back.x, back.y), p));
+    }
+
+    #[test]
+    fn decomposes_back_into_position_rotation_and_scale(
+        x in -500.0..500.0f32,
+        rotation in -3.0..3.0f32,
+        scale in 0.1..4.0f32,
+    ) {
+        let t = Transform2D::new(Vector2::new(x, 0.0), rotation, Vector2::new(scale, -scale));
+
+        prop_assert!(close(t.get_position().x, x));
+        prop_assert!(close(t.get_rotation(), rotation));
+        prop_assert!(close2(t.get_scale(), Vector2::new(scale, -scale)));
+    }
+}
+
+#[test]
+fn transpose_and_storage_order() {
+    let matrix = Matrix4::from_rows([
+        [1.0, 2.0, 3.0, 4.0],
+        [5.0, 6.0, 7.0, 8.0],
+        [9.0, 10.0, 11.0, 12.0],
+        [13.0, 14.0, 15.0, 16.0],
+    ]);
+
+    // Row-major, so the first four values in memory are the first row.
+    let memory = unsafe { std::slice::from_raw_parts(matrix.as_ptr(), 16) };
+    assert_eq!(&memory[..4], &[1.0f32, 2.0, 3.0, 4.0]);
+
+    assert_eq!(matrix.transpose().get(0, 3), 13.0);
+    assert_eq!(matrix.transpose().transpose(), matrix);
+    assert_eq!(matrix.determinant(), 0.0);
+    assert!(matrix.inverse().is_none());
+}
+
+#[test]
+fn builds_3d_transforms() {
+    let matrix = Matrix4::<f32>::translation(Vector3::new(1.0, 2.0, 3.0))
+        * Matrix4::<f32>::rotation_z(std::f32::consts::FRAC_PI_2)
+        * Matrix4::<f32>::scale(Vector3::new(2.0, 2.0, 2.0));
+
+    let moved = matrix.transform_point(Vector3::new(1.0, 0.0, 1.0));
+    assert!((moved - Vector3::new(1.0, 4.0, 5.0)).length() < 1e-5);
+
+    let turned = Matrix4::<f32>::rotation_x(std::f32::consts::FRAC_PI_2)
+        .transform_vector(Vector3::new(0.0, 1.0, 0.0));
+    assert!((turned - Vector3::new(0.0, 0.0, 1.0)).length() < 1e-5);
+
+    let identity =
+        Matrix4::<f32>::rotation_y(0.7) * Matrix4::<f32>::rotation_y(0.7).inverse().unwrap();
+    for row in 0..4 {
+        for column in 0..4 {
+            let expected = if row == column { 1.0 } else { 0.0 };
+            assert!((identity.get(row, column) - expected).abs() < 1e-5);
+        }
+    }
+
+    assert_eq!(
+        Matrix3::<f64>::scale(Vector2::new(2.0, 3.0)).determinant(),
+        6.0
+    );
+}
+
+#[test]
+fn orthographic_maps_the_screen_to_clip_space() {
+    let projection = math::orthographic(0.0, 800.0, 0.0, 600.0, 1.0, 0.0);
+
+    let corner = projection.transform_point(Vector3::new(800.0, 600.0, 0.0));
+    assert_eq!((corner.x, corner.y), (1.0, -1.0));
+
+    let origin = projection.transform_point(Vector3::new(0.0, 0.0, 0.0));
+    assert_eq!((origin.x, origin.y), (-1.0, 1.0));
+}
@@ -0,0 +1,148 @@
+use bgf::math::{Vector2, Vector3, Vector4};
+use proptest::prelude::*;
+
+const EPSILON: f64 = 1e-9;
+
+fn component() -> impl Strategy<Value = f64> {
+    -1000.0..1000.0
+}
+
+fn vector2() -> impl Strategy<Value = Vector2<f64>> {
+    (component(), component()).prop_map(Vector2::from)
+}
+
+fn vector3() -> impl Strategy<Value = Vector3<f64>> {
+    (component(), component(), component()).prop_map(Vector3::from)
+}
+
+fn vector4() -> impl Strategy<Value = Vector4<f64>> {
+    (component(), component(), component(), component()).prop_map(Vector4::from)
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= EPSILON * (1.0 + a.abs().max(b.abs()))
+}
+
+fn close2(a: Vector2<f64>, b: Vector2<f64>) -> bool {
+    close(a.x, b.x) && close(a.y, b.y)
+}
+
+proptest! {
+    #[test]
+    fn adding_then_subtracting_gives_back_the_original(a in vector3(), b in vector3()) {
+        let result = a + b - b;
+        prop_assert!(close(result.x, a.x) && close(result.y, a.y) && close(result.z, a.z));
+    }
+
+    #[test]
+    fn scalar_multiplication_works_on_either_side(a in vector4(), s in component()) {
+        prop_assert_eq!(a * s, s * a);
+        prop_assert_eq!(-a, a * -1.0);
+    }
+
+    #[test]
+    fn dot_product_is_commutative(a in vector3(), b in vector3()) {
+        prop_assert!(close(a.dot(b), b.dot(a)));
+    }
+
+    #[test]
+    fn cross_product_is_perpendicular_to_both(a in vector3(), b in vector3()) {
+        let cross = a.cross(b);
+        let scale = a.length() * b.length() * (a.length() + b.length()) + 1.0;
+
+        prop_assert!(cross.dot(a).abs() / scale < 1e-9);
+        prop_assert!(cross.dot(b).abs() / scale < 1e-9);
+    }
+
+    #[test]
+    fn normalized_vectors_have_length_one(a in vector2()) {
+        prop_assume!(a.length() > 1e-6);
+        prop_assert!(close(a.normalize().length(), 1.0));
+    }
+
+    #[test]
+    fn rotating_keeps_the_length_and_turns_by_the_angle(a in vector2(), angle in -3.0..3.0f64) {
+        prop_assume!(a.length() > 1e-6);
+        let rotated = a.rotate(angle);
+
+        prop_assert!(close(rotated.length(), a.length()));
+        prop_assert!((a.angle_between(rotated) - angle.abs()).abs() < 1e-6);
+        prop_assert!(close2(rotated.rotate(-angle), a));
+    }
+
+    #[test]
+    fn perpendicular_is_a_quarter_turn(a in vector2()) {
+        prop_assert!(close(a.dot(a.perpendicular()), 0.0));
+        prop_assert!(close2(a.perpendicular(), a.rotate(std::f64::consts::FRAC_PI_2)));
+    }
+
+    #[test]
+    fn reflecting_keeps_the_length(a in vector2(), angle in -3.0..3.0f64) {
+        let normal = Vector2::<f64>::from_angle(angle);
+        let reflected = a.reflect(normal);
+
+        prop_assert!(close(reflected.length(), a.length()));
+        prop_assert!(close(reflected.dot(normal), -a.dot(normal)));
+    }
+
+    #[test]
+    fn lerp_goes_from_one_to_the_other(a in vector2(), b in vector2(), t in 0.0..1.0f64) {
+        prop_assert_eq!(a.lerp(b, 0.0), a);
+        prop_assert!(close2(a.lerp(b, 1.0), b));
+
+        let between = a.lerp(b, t);
+        prop_assert!(close(a.distance(between) + between.distance(b), a.distance(b)));
+    }
+
+    #[test]
+    fn clamp_stays_within_min_and_max(a in vector3(), b in vector3(), c in vector3()) {
+        let min = b.min(c);
+        let max = b.max(c);
+        let clamped = a.clamp(min, max);
+
+        prop_assert!(min.x <= clamped.x && clamped.x <= max.x);
+        prop_assert!(min.y <= clamped.y && clamped.y <= max.y);
+        prop_assert!(min.z <= clamped.z && clamped.z <= max.z);
+    }
+
+    #[test]
+    fn converts_to_and_from_tuples_and_arrays(a in vector4()) {
+        let tuple: (f64, f64, f64, f64) = a.into();
+        let array: [f64; 4] = a.into();
+
+        prop_assert_eq!(Vector4::from(tuple), a);
+        prop_assert_eq!(Vector4::from(array), a);
+    }
+}
+
+#[test]
+fn integer_vectors_work_too() {
+    let mut a = Vector2::new(3, 4);
+    a += Vector2::from_scalar(1);
+    a *= 2;
+
+    assert_eq!(a, Vector2::new(8, 10));
+    assert_eq!(a.length_squared(), 164);
+    assert_eq!(a.cross(Vector2::new(1, 0)), -10);
+}
+
+#[test]
+fn angles_follow_screen_coordinates() {
+    let right = Vector2::new(1.0f32, 0.0);
+    let down = Vector2::new(0.0f32, 1.0);
+
+    assert!((right.rotate(std::f32::consts::FRAC_PI_2) - down).length() < 1e-6);
+    assert!((down.angle() - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+    assert!(right.cross(down) > 0.0);
+    assert_eq!(Vector2::<f32>::default().normalize(), Vector2::default());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serializes_as_named_fields() {
+    let vector = Vector3::new(1.0, 2.5, -3.0);
+    let json = serde_json::to_string(&vector).unwrap();
+
+    assert_eq!(json, r#"{"x":1.0,"y":2.5,"z":-3.0}"#);
+    assert_eq!(serde_json::from_str::<Vector3<f64>>(&json).unwrap(), vector);
+}
@@ -0,0 +1,149 @@
+use bgf::random::{Fbm, Noise2D, Perlin, Random, Simplex, ValueNoise};
+use rand::RngCore;
+
+#[test]
+fn matches_the_reference_pcg32() {
+    // The first numbers from the PCG reference demo, seeded with 42 and 54.
+    let mut random = Random::with_stream(42, 54);
+    let numbers: Vec<u32> = (0..6).map(|_| random.next_u32()).collect();
+
+    assert_eq!(
+        numbers,
+        [0xa15c02b7, 0x7b47f409, 0xba1d3330, 0x83d2f293, 0xbfa4784b, 0xcbed606e]
+    );
+}
+
+#[test]
+fn same_seed_gives_the_same_numbers() {
+    let mut random = Random::new(7);
+    let numbers: Vec<u32> = (0..4).map(|_| random.next_u32()).collect();
+    assert_eq!(numbers, [1352667803, 3706616521, 2188350268, 3036842621]);
+
+    let mut random = Random::new(7);
+    let rolls: Vec<i32> = (0..8).map(|_| random.range(1..=6)).collect();
+    assert_eq!(rolls, [4, 4, 6, 6, 2, 6, 5, 2]);
+
+    let mut a = Random::new(7);
+    let mut b = a.clone();
+    let mut other_stream = Random::with_stream(7, 1);
+    assert_eq!(a.next_u64(), b.next_u64());
+    assert_ne!(a.next_u64(), other_stream.next_u64());
+
+    // Going through rand's trait gives the same numbers.
+    let mut bytes = [0; 6];
+    RngCore::fill_bytes(&mut Random::new(7), &mut bytes);
+    assert_eq!(bytes[..4], 1352667803u32.to_le_bytes());
+}
+
+#[test]
+fn ranges_stay_in_bounds() {
+    let mut random = Random::new(1);
+
+    for _ in 0..1000 {
+        assert!((-100..100i8).contains(&random.range(-100..100i8)));
+        assert!((5..=5u32).contains(&random.range(5..=5u32)));
+        assert!((-1.0..1.0).contains(&random.range(-1.0..1.0f32)));
+        assert!((0.0..1.0).contains(&random.next_f64()));
+    }
+
+    // Every number in a full range is fine.
+    random.range(i64::MIN..=i64::MAX);
+
+    let mut counts = [0; 4];
+    for _ in 0..4000 {
+        counts[random.range(0..4usize)] += 1;
+    }
+    assert!(counts.iter().all(|count| (900..1100).contains(count)));
+}
+
+#[test]
+fn picks_weighted_and_shuffles() {
+    let mut random = Random::new(3);
+
+    let items = [
+        ("common", 9.0),
+        ("rare", 1.0),
+        ("never", 0.0),
+        ("broken", -5.0),
+    ];
+    let mut rare = 0;
+
+    for _ in 0..1000 {
+        match *random.choose_weighted(&items).unwrap() {
+            "rare" => rare += 1,
+            "common" => (),
+            other => panic!("picked {}", other),
+        }
+    }
+    assert!((60..140).contains(&rare));
+
+    assert_eq!(random.weighted_index(&[0.0, -1.0]), None);
+    assert_eq!(random.weighted_index(&[0.0, 2.0]), Some(1));
+    assert_eq!(random.choose::<u8>(&[]), None);
+    assert_eq!(random.choose(&[5]), Some(&5));
+
+    let mut cards: Vec<u32> = (0..52).collect();
+    random.shuffle(&mut cards);
+    assert_ne!(cards, (0..52).collect::<Vec<_>>());
+
+    cards.sort();
+    assert_eq!(cards, (0..52).collect::<Vec<_>>());
+}
+
+#[test]
+fn picks_points_and_colors() {
+    let mut random = Random::new(9);
+
+    for _ in 0..1000 {
+        assert!(random.in_circle(5.0).length() <= 5.0);
+        assert!((random.on_circle(5.0).length() - 5.0).abs() < 1e-4);
+
+        let color = random.hue_color(1.0, 1.0);
+        let (_, saturation, value) = color.to_hsv();
+        assert!((saturation - 1.0).abs() < 1e-4 && (value - 1.0).abs() < 1e-4);
+        assert_eq!(random.color().alpha, 1.0);
+    }
+
+    // Every side of the circle gets points.
+    let points: Vec<_> = (0..100).map(|_| random.on_circle(1.0)).collect();
+    assert!(points.iter().any(|p| p.x > 0.5) && points.iter().any(|p| p.x < -0.5));
+    assert!(points.iter().any(|p| p.y > 0.5) && points.iter().any(|p| p.y < -0.5));
+}
+
+#[test]
+fn noise_snapshots() {
+    let perlin = Perlin::new(1);
+    let simplex = Simplex::new(1);
+    let value = ValueNoise::new(1);
+    let fbm = Fbm::new(Perlin::new(1), 4);
+
+    let points = [(0.5, 0.5), (1.25, -3.75), (10.1, 20.7)];
+    let sample = |noise: &dyn Noise2D| points.map(|(x, y)| noise.get(x, y));
+
+    assert_eq!(sample(&perlin), [-0.5, 0.08476353, -0.28780994]);
+    assert_eq!(sample(&simplex), [0.0, 0.4425077, 0.49024218]);
+    assert_eq!(sample(&value), [-0.09215684, -0.63287485, -0.06263596]);
+    assert_eq!(sample(&fbm), [-0.26666668, -0.021459453, -0.1891758]);
+
+    assert_eq!(perlin.get(3.0, -7.0), 0.0);
+    assert_ne!(sample(&Perlin::new(2)), sample(&perlin));
+}
+
+#[test]
+fn noise_stays_in_range() {
+    let noises: [Box<dyn Noise2D>; 4] = [
+        Box::new(Perlin::new(5)),
+        Box::new(Simplex::new(5)),
+        Box::new(ValueNoise::new(5)),
+        Box::new(Fbm::new(Simplex::new(5), 6)),
+    ];
+
+    for noise in &noises {
+        for i in 0..100 {
+            for j in 0..100 {
+                let n = noise.get(i as f32 * 0.137 - 5.0, j as f32 * 0.173 - 5.0);
+                assert!((-1.0..=1.0).contains(&n), "{}", n);
+            }
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use bgf::graphics::preprocessor::Preprocessor;
+
+#[test]
+fn includes_virtual_files() {
+    let mut preprocessor = Preprocessor::new();
+    preprocessor.add_virtual_file("common/noise.glsl", "float noise() { return 0.5; }");
+
+    let processed = preprocessor
+        .process(
+            "effect.fs",
+            "#version 330 core\n#include \"common/noise.glsl\"\nvoid main() {}",
+        )
+        .unwrap();
+
+    assert_eq!(
+        processed.get_source(),
+        "#version 330 core\nfloat noise() { return 0.5; }\nvoid main() {}\n"
+    );
+}
+
+#[test]
+fn resolves_includes_relative_to_the_including_file() {
+    let mut preprocessor = Preprocessor::new();
+    preprocessor.add_virtual_file("shaders/lib/color.glsl", "#include \"../common.glsl\"");
+    preprocessor.add_virtual_file("shaders/common.glsl", "// common");
+
+    let processed = preprocessor
+        .process("shaders/effect.fs", "#include \"lib/color.glsl\"")
+        .unwrap();
+
+    assert_eq!(processed.get_source(), "// common\n");
+}
+
+#[test]
+fn injects_defines_after_version() {
+    let mut preprocessor = Preprocessor::new();
+    preprocessor.define("QUALITY", "2");
+    preprocessor.define("USE_BLOOM", "1");
+    preprocessor.define("QUALITY", "3");
+
+    let processed = preprocessor
+        .process("effect.fs", "// header\n#version 330 core\nvoid main() {}")
+        .unwrap();
+
+    assert_eq!(
+        processed.get_source(),
+        "// header\n#version 330 core\n#define QUALITY 3\n#define USE_BLOOM 1\nvoid main() {}\n"
+    );
+
+    let processed = preprocessor.process("effect.fs", "void main() {}").unwrap();
+
+    assert_eq!(
+        processed.get_source(),
+        "#define QUALITY 3\n#define USE_BLOOM 1\nvoid main() {}\n"
+    );
+}
+
+#[test]
+fn pragma_once_includes_a_file_once() {
+    let mut preprocessor = Preprocessor::new();
+    preprocessor.add_virtual_file("a.glsl", "#pragma once\nfloat a;");
+
+    let processed = preprocessor
+        .process("effect.fs", "#include \"a.glsl\"\n#include \"a.glsl\"")
+        .unwrap();
+
+    assert_eq!(processed.get_source(), "float a;\n");
+}
+
+#[test]
+fn reports_include_cycles_and_bad_includes() {
+    let mut preprocessor = Preprocessor::new();
+    preprocessor.add_virtual_file("a.glsl", "#include \"b.glsl\"");
+    preprocessor.add_virtual_file("b.glsl", "\n#include \"a.glsl\"");
+
+    let error = preprocessor
+        .process("effect.fs", "#include \"a.glsl\"")
+        .err()
+        .unwrap();
+    assert_eq!(error.file, "b.glsl");
+    assert_eq!(error.line, 2);
+
+    let error = preprocessor
+        .process("effect.fs", "\n\n#include <a.glsl>")
+        .err()
+        .unwrap();
+    assert_eq!(error.file, "effect.fs");
+    assert_eq!(error.line, 3);
+}
+
+#[test]
+fn maps_lines_back_to_their_files() {
+    let mut preprocessor = Preprocessor::new();
+    preprocessor.add_virtual_file("noise.glsl", "float noise;\nfloat broken");
+    preprocessor.define("QUALITY", "1");
+
+    let processed = preprocessor
+        .process(
+            "effect.fs",
+            "#version 330 core\n#include \"noise.glsl\"\nvoid main() {}",
+        )
+        .unwrap();
+
+    assert_eq!(processed.original_line(1), Some(("effect.fs", 1)));
+    assert_eq!(processed.original_line(2), Some(("<defines>", 1)));
+    assert_eq!(processed.original_line(4), Some(("noise.glsl", 2)));
+    assert_eq!(processed.original_line(5), Some(("effect.fs", 3)));
+    assert_eq!(processed.original_line(0), None);
+    assert_eq!(processed.original_line(6), None);
+
+    let log = "0(4) : error C0000: syntax error\n\
+               0:5(3): error: `x' undeclared\n\
+               ERROR: 0:4: '' : syntax error\n\
+               unrelated 10(4) line";
+
+    assert_eq!(
+        processed.remap_log(log),
+        "noise.glsl(2) : error C0000: syntax error\n\
+         effect.fs:3(3): error: `x' undeclared\n\
+         ERROR: noise.glsl:2: '' : syntax error\n\
+         unrelated 10(4) line"
+    );
+}
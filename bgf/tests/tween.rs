@@ -0,0 +1,239 @@
+use bgf::graphics::{colors, Color};
+use bgf::math::{Transform2D, Vector2};
+use bgf::tween::{self, Easing, Parallel, Sequence, Tween, TweenManager, TweenValue, Tweenable};
+use bgf::{Scene, SceneManager};
+use std::cell::Cell;
+use std::f32::consts::PI;
+use std::rc::Rc;
+
+const EASINGS: [Easing; 30] = [
+    Easing::SineIn,
+    Easing::SineOut,
+    Easing::SineInOut,
+    Easing::QuadIn,
+    Easing::QuadOut,
+    Easing::QuadInOut,
+    Easing::CubicIn,
+    Easing::CubicOut,
+    Easing::CubicInOut,
+    Easing::QuartIn,
+    Easing::QuartOut,
+    Easing::QuartInOut,
+    Easing::QuintIn,
+    Easing::QuintOut,
+    Easing::QuintInOut,
+    Easing::ExpoIn,
+    Easing::ExpoOut,
+    Easing::ExpoInOut,
+    Easing::CircIn,
+    Easing::CircOut,
+    Easing::CircInOut,
+    Easing::BackIn,
+    Easing::BackOut,
+    Easing::BackInOut,
+    Easing::ElasticIn,
+    Easing::ElasticOut,
+    Easing::ElasticInOut,
+    Easing::BounceIn,
+    Easing::BounceOut,
+    Easing::BounceInOut,
+];
+
+fn counter() -> (Rc<Cell<u32>>, impl FnMut() + 'static) {
+    let count = Rc::new(Cell::new(0));
+    let inner = count.clone();
+
+    (count, move || inner.set(inner.get() + 1))
+}
+
+#[test]
+fn easings_start_at_zero_and_end_at_one() {
+    for easing in EASINGS {
+        assert!(easing.apply(0.0).abs() < 1e-3, "{:?} at 0", easing);
+        assert!((easing.apply(1.0) - 1.0).abs() < 1e-3, "{:?} at 1", easing);
+
+        // Every InOut curve is symmetric, so it goes through the middle.
+        let name = format!("{:?}", easing);
+        if name.ends_with("InOut") {
+            assert!((easing.apply(0.5) - 0.5).abs() < 1e-3, "{:?}", easing);
+        }
+    }
+
+    assert_eq!(Easing::QuadIn.apply(0.5), 0.25);
+    assert_eq!(Easing::QuadOut.apply(0.5), 0.75);
+    assert!(Easing::BackIn.apply(0.2) < 0.0);
+    assert!(Easing::ElasticOut.apply(0.2) > 1.0);
+    assert_eq!(Easing::Custom(|t| t * t * t).apply(0.5), 0.125);
+    assert_eq!(Easing::Linear.apply(2.0), 1.0);
+}
+
+#[test]
+fn tweens_ease_towards_the_target() {
+    let value = TweenValue::new(10.0f32);
+    let (completed, on_complete) = counter();
+    let mut tweens = TweenManager::new();
+
+    let id = tweens.add(
+        Tween::new(&value, 20.0, 1.0)
+            .with_easing(Easing::QuadIn)
+            .with_delay(0.5)
+            .on_complete(on_complete),
+    );
+
+    tweens.update(0.25);
+    assert_eq!(value.get(), 10.0);
+
+    // The start is taken when the delay's over, so this gets overwritten.
+    value.set(0.0);
+    tweens.update(0.75);
+    assert_eq!(value.get(), 0.0 + 20.0 * 0.25);
+
+    tweens.update(0.75);
+    assert_eq!(value.get(), 20.0);
+    assert_eq!(completed.get(), 1);
+    assert!(!tweens.is_running(id));
+    assert!(tweens.is_empty());
+}
+
+#[test]
+fn repeats_and_yoyos() {
+    let value = TweenValue::new(0.0f32);
+    let mut tweens = TweenManager::new();
+
+    tweens.add(Tween::new(&value, 1.0, 1.0).with_repeats(2).with_yoyo());
+
+    let mut seen = Vec::new();
+    for _ in 0..7 {
+        tweens.update(0.5);
+        seen.push(value.get());
+    }
+
+    assert_eq!(seen, [0.5, 1.0, 0.5, 0.0, 0.5, 1.0, 1.0]);
+    assert!(tweens.is_empty());
+
+    let forever = tweens.add(Tween::new(&value, 4.0, 1.0).from(0.0).repeat_forever());
+    tweens.update(100.25);
+    assert_eq!(value.get(), 1.0);
+    assert!(tweens.cancel(forever));
+    assert!(!tweens.cancel(forever));
+}
+
+#[test]
+fn sequences_pass_left_over_time_along() {
+    let x = TweenValue::new(0.0f32);
+    let y = TweenValue::new(0.0f32);
+    let (completed, on_complete) = counter();
+    let mut tweens = TweenManager::new();
+
+    tweens.add(
+        Sequence::new()
+            .then(Tween::new(&x, 10.0, 1.0))
+            .wait(0.5)
+            .then(Tween::new(&y, 10.0, 1.0))
+            .on_complete(on_complete),
+    );
+
+    tweens.update(1.25);
+    assert_eq!((x.get(), y.get()), (10.0, 0.0));
+
+    tweens.update(0.5);
+    assert_eq!((x.get(), y.get()), (10.0, 2.5));
+
+    tweens.update(10.0);
+    assert_eq!(y.get(), 10.0);
+    assert_eq!(completed.get(), 1);
+    assert!(tweens.is_empty());
+}
+
+#[test]
+fn parallel_groups_wait_for_the_longest() {
+    let short = TweenValue::new(0.0f32);
+    let long = TweenValue::new(0.0f32);
+    let after = TweenValue::new(0.0f32);
+    let (completed, on_complete) = counter();
+    let mut tweens = TweenManager::new();
+
+    tweens.add(
+        Sequence::new()
+            .then(
+                Parallel::new()
+                    .with(Tween::new(&short, 1.0, 0.5))
+                    .with(Tween::new(&long, 1.0, 2.0))
+                    .on_complete(on_complete),
+            )
+            .then(Tween::new(&after, 1.0, 1.0)),
+    );
+
+    tweens.update(1.0);
+    assert_eq!((short.get(), long.get(), completed.get()), (1.0, 0.5, 0));
+
+    tweens.update(1.5);
+    assert_eq!((long.get(), after.get(), completed.get()), (1.0, 0.5, 1));
+}
+
+#[test]
+fn interpolates_colors_vectors_and_transforms() {
+    assert_eq!(
+        colors::BLACK.interpolate(&colors::WHITE, 0.25),
+        Color::rgb(0.25, 0.25, 0.25)
+    );
+    assert_eq!(
+        Vector2::new(0.0f64, 10.0).interpolate(&Vector2::new(10.0, 0.0), 0.5),
+        Vector2::new(5.0, 5.0)
+    );
+
+    // Going from just under a half turn to just over it takes the short way.
+    let from = Transform2D::new(Vector2::new(0.0, 0.0), PI - 0.1, Vector2::new(1.0, 1.0));
+    let to = Transform2D::new(Vector2::new(10.0, 0.0), -PI + 0.1, Vector2::new(3.0, 3.0));
+    let halfway = from.interpolate(&to, 0.5);
+
+    assert!((halfway.get_position() - Vector2::new(5.0, 0.0)).length() < 1e-4);
+    assert!((halfway.get_rotation().abs() - PI).abs() < 1e-4);
+    assert!((halfway.get_scale() - Vector2::new(2.0, 2.0)).length() < 1e-4);
+}
+
+struct Menu {
+    offset: TweenValue<f32>,
+    seen: Rc<Cell<f32>>,
+}
+
+impl Scene for Menu {
+    fn update(&mut self, _delta_time: f64) -> Option<Box<dyn Scene>> {
+        self.seen.set(self.offset.get());
+        return None;
+    }
+
+    fn render(&mut self) {}
+}
+
+#[test]
+fn scene_manager_drives_the_shared_tweens() {
+    let offset = TweenValue::new(0.0f32);
+    let seen = Rc::new(Cell::new(-1.0));
+    let back = offset.clone();
+
+    // The callback adds another tween while the shared ones are updating.
+    let id = tween::add(Tween::new(&offset, 100.0, 1.0).on_complete(move || {
+        tween::add(Tween::new(&back, 0.0, 1.0));
+    }));
+
+    let mut scenes = SceneManager::new(Box::new(Menu {
+        offset: offset.clone(),
+        seen: seen.clone(),
+    }));
+
+    scenes.update_active(0.5);
+    assert_eq!(seen.get(), 50.0);
+    assert!(tween::is_running(id));
+
+    scenes.update_active(0.5);
+    assert_eq!(seen.get(), 100.0);
+    assert!(!tween::is_running(id));
+
+    scenes.update_active(0.25);
+    assert_eq!(seen.get(), 75.0);
+
+    tween::clear();
+    scenes.update_active(0.25);
+    assert_eq!(seen.get(), 75.0);
+}
@@ -0,0 +1,805 @@
+use bgf::graphics::{colors, Color, NineSlice, SliceMode};
+use bgf::math::{Rect, Vector2};
+use bgf::ui::{
+    NavAction, NavigationInput, Response, TextFilter, TextInputSettings, Theme, Ui, UiEvent,
+    UiInput, UiKey,
+};
+use bgf::Error;
+
+const A: Rect<f32> = Rect {
+    x: 10.0,
+    y: 10.0,
+    width: 100.0,
+    height: 30.0,
+};
+
+const B: Rect<f32> = Rect {
+    x: 10.0,
+    y: 50.0,
+    width: 100.0,
+    height: 30.0,
+};
+
+fn mouse(x: f32, y: f32, down: bool) -> UiInput {
+    UiInput {
+        mouse_position: Vector2::new(x, y),
+        mouse_down: down,
+        ..UiInput::default()
+    }
+}
+
+// Runs a frame with a button in A and another in B.
+fn two_buttons(ui: &mut Ui, input: &UiInput) -> (Response, Response) {
+    ui.begin_frame(input, 1.0 / 60.0);
+    let a = ui.button("A", A);
+    let b = ui.button("B", B);
+    ui.end_frame();
+
+    return (a, b);
+}
+
+#[test]
+fn clicking_a_button() {
+    let mut ui = Ui::new();
+
+    let (a, b) = two_buttons(&mut ui, &mouse(20.0, 20.0, false));
+    assert!(a.hovered && !a.active && !a.clicked);
+    assert!(!b.hovered);
+    assert!(ui.wants_mouse());
+
+    let (a, _) = two_buttons(&mut ui, &mouse(20.0, 20.0, true));
+    assert!(a.active && !a.clicked);
+
+    let (a, _) = two_buttons(&mut ui, &mouse(25.0, 20.0, false));
+    assert!(a.clicked && !a.active);
+
+    // Clicking it gave it the focus, which it keeps.
+    let (a, _) = two_buttons(&mut ui, &mouse(200.0, 200.0, false));
+    assert_eq!(
+        a,
+        Response {
+            focused: true,
+            ..Response::default()
+        }
+    );
+    assert!(!ui.wants_mouse());
+}
+
+#[test]
+fn dragging_between_buttons_clicks_neither() {
+    let mut ui = Ui::new();
+
+    two_buttons(&mut ui, &mouse(20.0, 20.0, false));
+    two_buttons(&mut ui, &mouse(20.0, 20.0, true));
+
+    // A stays active while the mouse is over B, and B doesn't light up.
+    let (a, b) = two_buttons(&mut ui, &mouse(20.0, 60.0, true));
+    assert!(a.active && !a.hovered);
+    assert!(!b.hovered && !b.active);
+
+    let (a, b) = two_buttons(&mut ui, &mouse(20.0, 60.0, false));
+    assert!(!a.clicked && !b.clicked);
+
+    // Pressing on nothing and dragging onto a button doesn't do anything
+    // either.
+    two_buttons(&mut ui, &mouse(200.0, 200.0, true));
+    let (_, b) = two_buttons(&mut ui, &mouse(20.0, 60.0, true));
+    assert!(!b.hovered && !b.active);
+
+    let (_, b) = two_buttons(&mut ui, &mouse(20.0, 60.0, false));
+    assert!(!b.clicked);
+
+    let (_, b) = two_buttons(&mut ui, &mouse(20.0, 60.0, false));
+    assert!(b.hovered);
+}
+
+#[test]
+fn same_labels_need_different_ids() {
+    let ui = Ui::new();
+
+    assert_eq!(ui.get_id("Play"), ui.get_id("Play"));
+    assert_ne!(ui.get_id("Play##menu"), ui.get_id("Play##options"));
+
+    let mut ui = Ui::new();
+    let mut ids = Vec::new();
+
+    ui.begin_frame(&mouse(0.0, 0.0, false), 0.0);
+    for i in 0..3 {
+        ui.push_id(i);
+        ids.push(ui.get_id("Delete"));
+        ui.pop_id();
+    }
+    ui.end_frame();
+
+    assert_ne!(ids[0], ids[1]);
+    assert_ne!(ids[1], ids[2]);
+}
+
+#[test]
+fn checkbox_and_slider() {
+    let mut ui = Ui::new();
+    let mut checked = false;
+    let mut volume = 0.0;
+
+    let frame = |ui: &mut Ui, input: &UiInput, checked: &mut bool, volume: &mut f32| {
+        ui.begin_frame(input, 1.0 / 60.0);
+        let checkbox = ui.checkbox("Fullscreen", A, checked);
+        let slider = ui.slider("Volume", B, volume, 0.0, 10.0);
+        ui.end_frame();
+
+        return (checkbox, slider);
+    };
+
+    frame(&mut ui, &mouse(20.0, 20.0, true), &mut checked, &mut volume);
+    let (checkbox, _) = frame(
+        &mut ui,
+        &mouse(20.0, 20.0, false),
+        &mut checked,
+        &mut volume,
+    );
+    assert!(checkbox.changed && checked);
+
+    // The slider follows the mouse, even past its ends.
+    let (_, slider) = frame(&mut ui, &mouse(60.0, 60.0, true), &mut checked, &mut volume);
+    assert!(slider.changed);
+    assert_eq!(volume, 5.0);
+
+    frame(
+        &mut ui,
+        &mouse(500.0, 200.0, true),
+        &mut checked,
+        &mut volume,
+    );
+    assert_eq!(volume, 10.0);
+
+    frame(
+        &mut ui,
+        &mouse(500.0, 200.0, false),
+        &mut checked,
+        &mut volume,
+    );
+    let (_, slider) = frame(
+        &mut ui,
+        &mouse(20.0, 60.0, false),
+        &mut checked,
+        &mut volume,
+    );
+    assert!(!slider.changed);
+    assert_eq!(volume, 10.0);
+}
+
+#[test]
+fn typing_into_a_text_input() {
+    let mut ui = Ui::new();
+    let mut name = String::new();
+
+    let frame = |ui: &mut Ui, input: &UiInput, name: &mut String| {
+        ui.begin_frame(input, 1.0 / 60.0);
+        let response = ui.text_input("Name", A, name);
+        ui.end_frame();
+
+        return response;
+    };
+
+    // Typing before it's been clicked doesn't go anywhere.
+    let mut input = mouse(20.0, 20.0, false);
+    input.text = "x".to_string();
+    frame(&mut ui, &input, &mut name);
+    assert!(name.is_empty() && !ui.wants_keyboard());
+
+    frame(&mut ui, &mouse(20.0, 20.0, true), &mut name);
+    assert!(ui.wants_keyboard());
+
+    let mut input = mouse(20.0, 20.0, false);
+    input.text = "Bobb\n".to_string();
+    assert!(frame(&mut ui, &input, &mut name).changed);
+    assert_eq!(name, "Bobb");
+
+    let mut input = mouse(20.0, 20.0, false);
+    input.keys = vec![UiKey::Backspace, UiKey::Enter];
+    frame(&mut ui, &input, &mut name);
+    assert_eq!(name, "Bob");
+    assert!(!ui.wants_keyboard());
+}
+
+#[test]
+fn dropdown_blocks_what_is_under_it() {
+    let mut ui = Ui::new();
+    let options = ["Low", "Medium", "High"];
+    let mut selected = 0;
+
+    // The list opens over B.
+    let frame = |ui: &mut Ui, input: &UiInput, selected: &mut usize| {
+        ui.begin_frame(input, 1.0 / 60.0);
+        let dropdown = ui.dropdown("Quality", A, &options, selected);
+        let button = ui.button("B", B);
+        ui.end_frame();
+
+        return (dropdown, button);
+    };
+
+    frame(&mut ui, &mouse(20.0, 20.0, true), &mut selected);
+    frame(&mut ui, &mouse(20.0, 20.0, false), &mut selected);
+
+    // The third option is at y = 100 to 130.
+    let (_, button) = frame(&mut ui, &mouse(20.0, 60.0, false), &mut selected);
+    assert!(!button.hovered);
+    assert!(ui.wants_mouse());
+
+    frame(&mut ui, &mouse(20.0, 110.0, true), &mut selected);
+    let (dropdown, _) = frame(&mut ui, &mouse(20.0, 110.0, false), &mut selected);
+    assert!(dropdown.changed);
+    assert_eq!(selected, 2);
+
+    // Closed again, so B works.
+    let (_, button) = frame(&mut ui, &mouse(20.0, 60.0, false), &mut selected);
+    assert!(button.hovered);
+
+    // Clicking anywhere else closes it without changing anything.
+    frame(&mut ui, &mouse(20.0, 20.0, true), &mut selected);
+    frame(&mut ui, &mouse(20.0, 20.0, false), &mut selected);
+    frame(&mut ui, &mouse(300.0, 300.0, true), &mut selected);
+    frame(&mut ui, &mouse(300.0, 300.0, false), &mut selected);
+
+    let (_, button) = frame(&mut ui, &mouse(20.0, 60.0, false), &mut selected);
+    assert!(button.hovered);
+    assert_eq!(selected, 2);
+}
+
+#[test]
+fn scroll_panel_clips_and_scrolls() {
+    let mut ui = Ui::new();
+    let panel = Rect::new(0.0, 0.0, 200.0, 100.0);
+
+    // Ten 30 pixel rows, so it can scroll by 200.
+    let frame = |ui: &mut Ui, input: &UiInput| {
+        ui.begin_frame(input, 1.0 / 60.0);
+        let content = ui.begin_scroll_panel("List", panel, 300.0);
+
+        let mut responses = Vec::new();
+        for i in 0..10 {
+            let row = Rect::new(content.x, content.y + i as f32 * 30.0, 100.0, 30.0);
+            responses.push(ui.button(&format!("Row {}", i), row));
+        }
+
+        ui.end_scroll_panel();
+        ui.end_frame();
+
+        return (content, responses);
+    };
+
+    let (content, rows) = frame(&mut ui, &mouse(50.0, 50.0, false));
+    assert_eq!(content.y, 0.0);
+    assert!(rows[1].hovered);
+
+    // Row 5 is below the panel, so it can't be hovered from outside it.
+    let (_, rows) = frame(&mut ui, &mouse(50.0, 160.0, false));
+    assert!(!rows[5].hovered);
+
+    let mut input = mouse(50.0, 50.0, false);
+    input.scroll = -2.0;
+    frame(&mut ui, &input);
+
+    let (content, rows) = frame(&mut ui, &mouse(50.0, 50.0, false));
+    assert_eq!(content.y, -80.0);
+    assert!(rows[4].hovered);
+
+    // Can't scroll past the end.
+    input.scroll = -100.0;
+    frame(&mut ui, &input);
+    let (content, _) = frame(&mut ui, &mouse(50.0, 50.0, false));
+    assert_eq!(content.y, -200.0);
+}
+
+#[test]
+fn loading_a_theme() {
+    let text = "
+        # Dark blue, with skinned buttons.
+        text = white
+        text_scale = 3
+        widget = #203050
+        widget_slice = button.png 6
+        widget_hovered_slice = button_hovered.png 6 4 6 8 tile_center
+        field_slice = button.png 4
+        panel_slice = panel.png 12 tile_edges
+    ";
+
+    let mut loaded = Vec::new();
+    let theme = Theme::from_str_with_textures(text, |path| {
+        loaded.push(path.to_string());
+        return Ok(loaded.len() as f32);
+    })
+    .unwrap();
+
+    assert_eq!(
+        loaded,
+        vec!["button.png", "button_hovered.png", "panel.png"]
+    );
+    assert_eq!(theme.text, colors::WHITE);
+    assert_eq!(theme.text_scale, 3.0);
+    assert_eq!(theme.widget.normal, Color::from_hex("#203050").unwrap());
+    assert_eq!(theme.padding, Theme::default().padding);
+
+    let hovered = NineSlice::new(2.0, 6.0)
+        .with_borders(6.0, 4.0, 6.0, 8.0)
+        .with_center_mode(SliceMode::Tile);
+    assert_eq!(theme.widget_slices.hovered, Some(hovered));
+
+    // The button texture was only loaded the first time it came up.
+    assert_eq!(theme.field_slice, Some(NineSlice::new(1.0, 4.0)));
+
+    // Active falls back to the normal one.
+    let active = Response {
+        active: true,
+        ..Response::default()
+    };
+    assert_eq!(
+        theme.widget_slices.get(&active),
+        Some(NineSlice::new(1.0, 6.0))
+    );
+
+    let panel = theme.panel_slice.unwrap();
+    assert_eq!(panel.edge_mode, SliceMode::Tile);
+    assert_eq!(panel.center_mode, SliceMode::Stretch);
+}
+
+#[test]
+fn broken_themes_say_where() {
+    let parse = |text: &str| Theme::from_str_with_textures(text, |_| Ok(0.0));
+
+    let lines = |result: Result<Theme, Error>| match result {
+        Err(Error::Parse { line, .. }) => line,
+        other => panic!("expected a parse error, got {:?}", other),
+    };
+
+    assert_eq!(lines(parse("padding = 4\n\nrounding = round")), 3);
+    assert_eq!(lines(parse("text = not a color")), 1);
+    assert_eq!(lines(parse("colour = red")), 1);
+    assert_eq!(lines(parse("padding")), 1);
+    assert_eq!(lines(parse("panel_slice = panel.png 1 2")), 1);
+    assert_eq!(lines(parse("panel_slice = panel.png 4 wobbly")), 1);
+    assert_eq!(lines(parse("panel_slice = panel.png 4 -2 4 4")), 1);
+
+    // Texture errors come straight through.
+    let result = Theme::from_str_with_textures("panel_slice = missing.png 4", |path| {
+        Err(Error::ImageDecode {
+            path: path.to_string(),
+        })
+    });
+    assert!(matches!(result, Err(Error::ImageDecode { path }) if path == "missing.png"));
+}
+
+// No mouse, just navigation presses.
+fn nav(actions: &[NavAction]) -> UiInput {
+    UiInput {
+        mouse_position: Vector2::new(-100.0, -100.0),
+        navigation: actions.to_vec(),
+        ..UiInput::default()
+    }
+}
+
+#[test]
+fn held_directions_repeat() {
+    let mut navigation = NavigationInput::new();
+    let down = [NavAction::Down, NavAction::Activate];
+
+    assert_eq!(navigation.update(&down, 0.1), down);
+    assert_eq!(navigation.update(&down, 0.3), vec![]);
+
+    // Past the 0.4 second delay, then every 0.1 seconds, but activate only
+    // goes once.
+    assert_eq!(navigation.update(&down, 0.15), vec![NavAction::Down]);
+    assert_eq!(navigation.update(&down, 0.05), vec![]);
+    assert_eq!(navigation.update(&down, 0.05), vec![NavAction::Down]);
+
+    assert_eq!(navigation.update(&[], 0.1), vec![]);
+    assert_eq!(navigation.update(&down, 0.1), down);
+}
+
+// Runs a frame with a 2 by 2 grid of buttons, returning which were clicked.
+fn grid(ui: &mut Ui, input: &UiInput) -> Vec<bool> {
+    ui.begin_frame(input, 1.0 / 60.0);
+
+    let clicked = (0..4)
+        .map(|i| {
+            let rect = Rect::new((i % 2) as f32 * 120.0, (i / 2) as f32 * 40.0, 100.0, 30.0);
+            ui.button(&format!("{}", i), rect).clicked
+        })
+        .collect();
+
+    ui.end_frame();
+    return clicked;
+}
+
+#[test]
+fn moving_the_focus_around() {
+    let mut ui = Ui::new();
+    let id = |i: usize| Ui::new().get_id(&format!("{}", i));
+
+    grid(&mut ui, &nav(&[]));
+    assert_eq!(ui.get_focus(), None);
+
+    grid(&mut ui, &nav(&[NavAction::Next]));
+    assert_eq!(ui.get_focus(), Some(id(0)));
+    assert_eq!(
+        ui.get_events(),
+        &[UiEvent::FocusChanged {
+            from: None,
+            to: Some(id(0)),
+        }]
+    );
+
+    grid(&mut ui, &nav(&[NavAction::Right]));
+    assert_eq!(ui.get_focus(), Some(id(1)));
+
+    grid(&mut ui, &nav(&[NavAction::Down]));
+    assert_eq!(ui.get_focus(), Some(id(3)));
+
+    // Nothing further down, so it stays put.
+    grid(&mut ui, &nav(&[NavAction::Down]));
+    assert_eq!(ui.get_focus(), Some(id(3)));
+    assert!(ui.get_events().is_empty());
+
+    grid(&mut ui, &nav(&[NavAction::Previous]));
+    assert_eq!(ui.get_focus(), Some(id(2)));
+
+    // Tabbing wraps around.
+    grid(&mut ui, &nav(&[NavAction::Next, NavAction::Next]));
+    assert_eq!(ui.get_focus(), Some(id(0)));
+
+    let clicked = grid(&mut ui, &nav(&[NavAction::Activate]));
+    assert_eq!(clicked, vec![true, false, false, false]);
+
+    grid(&mut ui, &nav(&[NavAction::Cancel]));
+    assert_eq!(ui.get_events(), &[UiEvent::Cancelled]);
+
+    // Clicking moves the focus too.
+    grid(&mut ui, &mouse(130.0, 50.0, true));
+    assert_eq!(ui.get_focus(), Some(id(3)));
+}
+
+#[test]
+fn navigating_a_dropdown() {
+    let mut ui = Ui::new();
+    let options = ["Low", "Medium", "High"];
+    let mut selected = 0;
+    let mut volume = 0.5;
+
+    let frame = |ui: &mut Ui, input: &UiInput, selected: &mut usize, volume: &mut f32| {
+        ui.begin_frame(input, 1.0 / 60.0);
+        let dropdown = ui.dropdown("Quality", A, &options, selected);
+        ui.slider("Volume", B, volume, 0.0, 1.0);
+        ui.end_frame();
+
+        return dropdown;
+    };
+
+    frame(
+        &mut ui,
+        &nav(&[NavAction::Next]),
+        &mut selected,
+        &mut volume,
+    );
+    frame(
+        &mut ui,
+        &nav(&[NavAction::Activate]),
+        &mut selected,
+        &mut volume,
+    );
+    let quality = ui.get_id("Quality");
+    assert_ne!(ui.get_focus(), Some(quality));
+
+    // Only the options can be focused while it's open.
+    frame(
+        &mut ui,
+        &nav(&[NavAction::Down]),
+        &mut selected,
+        &mut volume,
+    );
+    frame(
+        &mut ui,
+        &nav(&[NavAction::Down]),
+        &mut selected,
+        &mut volume,
+    );
+    let dropdown = frame(
+        &mut ui,
+        &nav(&[NavAction::Activate]),
+        &mut selected,
+        &mut volume,
+    );
+    assert!(dropdown.changed);
+    assert_eq!(selected, 2);
+    assert_eq!(ui.get_focus(), Some(quality));
+
+    // Cancel just closes it.
+    frame(
+        &mut ui,
+        &nav(&[NavAction::Activate]),
+        &mut selected,
+        &mut volume,
+    );
+    frame(
+        &mut ui,
+        &nav(&[NavAction::Cancel]),
+        &mut selected,
+        &mut volume,
+    );
+    assert_eq!(ui.get_focus(), Some(quality));
+    assert!(!ui.get_events().contains(&UiEvent::Cancelled));
+    assert_eq!(selected, 2);
+
+    // Left and right move the slider rather than the focus.
+    frame(
+        &mut ui,
+        &nav(&[NavAction::Down]),
+        &mut selected,
+        &mut volume,
+    );
+    frame(
+        &mut ui,
+        &nav(&[NavAction::Right]),
+        &mut selected,
+        &mut volume,
+    );
+    assert_eq!(volume, 0.55);
+    assert_eq!(ui.get_focus(), Some(ui.get_id("Volume")));
+}
+
+#[test]
+fn text_inputs_keep_the_arrow_keys() {
+    let mut ui = Ui::new();
+    let mut name = String::new();
+
+    let frame = |ui: &mut Ui, input: &UiInput, name: &mut String| {
+        ui.begin_frame(input, 1.0 / 60.0);
+        ui.text_input("Name", A, name);
+        ui.button("OK", B);
+        ui.end_frame();
+    };
+
+    frame(&mut ui, &nav(&[NavAction::Next]), &mut name);
+    frame(&mut ui, &nav(&[NavAction::Activate]), &mut name);
+    assert!(ui.wants_keyboard());
+
+    frame(&mut ui, &nav(&[NavAction::Down]), &mut name);
+    assert_eq!(ui.get_focus(), Some(ui.get_id("Name")));
+
+    // Tabbing away stops the typing.
+    frame(&mut ui, &nav(&[NavAction::Next]), &mut name);
+    frame(&mut ui, &nav(&[]), &mut name);
+    assert_eq!(ui.get_focus(), Some(ui.get_id("OK")));
+    assert!(!ui.wants_keyboard());
+}
+
+#[test]
+fn focusing_scrolls_into_view() {
+    let mut ui = Ui::new();
+    let panel = Rect::new(0.0, 0.0, 200.0, 100.0);
+
+    let frame = |ui: &mut Ui, input: &UiInput| {
+        ui.begin_frame(input, 1.0 / 60.0);
+        let content = ui.begin_scroll_panel("List", panel, 300.0);
+
+        for i in 0..10 {
+            let row = Rect::new(content.x, content.y + i as f32 * 30.0, 100.0, 30.0);
+            ui.button(&format!("Row {}", i), row);
+        }
+
+        ui.end_scroll_panel();
+        ui.end_frame();
+
+        return content;
+    };
+
+    frame(&mut ui, &nav(&[NavAction::Next]));
+
+    for _ in 0..4 {
+        frame(&mut ui, &nav(&[NavAction::Down]));
+    }
+
+    // Row 4 goes down to 150, so it's scrolled by 50.
+    assert_eq!(frame(&mut ui, &nav(&[])).y, -50.0);
+
+    // Row 2 is still in view, but row 1 starts at 30.
+    frame(&mut ui, &nav(&[NavAction::Previous]));
+    frame(&mut ui, &nav(&[NavAction::Previous]));
+    assert_eq!(frame(&mut ui, &nav(&[])).y, -50.0);
+    frame(&mut ui, &nav(&[NavAction::Previous]));
+    assert_eq!(frame(&mut ui, &nav(&[])).y, -30.0);
+}
+
+// Types into a text input in A, which has already been clicked on.
+struct Typing {
+    ui: Ui,
+    text: String,
+    settings: TextInputSettings,
+}
+
+impl Typing {
+    fn new(text: &str, settings: TextInputSettings) -> Typing {
+        let mut typing = Typing {
+            ui: Ui::new(),
+            text: text.to_string(),
+            settings,
+        };
+
+        typing.frame(mouse(500.0, 20.0, false));
+        typing.frame(mouse(20.0, 20.0, true));
+        typing.frame(mouse(20.0, 20.0, false));
+        assert!(typing.ui.wants_keyboard());
+
+        return typing;
+    }
+
+    fn frame(&mut self, input: UiInput) -> Response {
+        self.ui.begin_frame(&input, 1.0 / 60.0);
+        let response = self
+            .ui
+            .text_input_with("Name", A, &mut self.text, self.settings);
+        self.ui.end_frame();
+
+        return response;
+    }
+
+    fn typed(&mut self, text: &str) -> Response {
+        let mut input = mouse(20.0, 20.0, false);
+        input.text = text.to_string();
+        return self.frame(input);
+    }
+
+    fn pressed(&mut self, keys: &[UiKey], shift: bool, control: bool) -> Response {
+        let mut input = mouse(20.0, 20.0, false);
+        input.keys = keys.to_vec();
+        input.shift = shift;
+        input.control = control;
+        return self.frame(input);
+    }
+}
+
+#[test]
+fn moving_the_caret_and_selecting() {
+    let mut typing = Typing::new("", TextInputSettings::default());
+
+    // Clicking at the start put the caret there.
+    typing.typed("hello world");
+    typing.pressed(&[UiKey::Left], false, true);
+    typing.typed("big ");
+    assert_eq!(typing.text, "hello big world");
+
+    typing.pressed(&[UiKey::Home, UiKey::Right], false, false);
+    typing.pressed(&[UiKey::Right, UiKey::Right], true, false);
+    assert!(typing.typed("EL").changed);
+    assert_eq!(typing.text, "hELlo big world");
+
+    // Backspace and delete take whole words with control held.
+    typing.pressed(&[UiKey::End, UiKey::Backspace], false, true);
+    assert_eq!(typing.text, "hELlo big ");
+    typing.pressed(&[UiKey::Home, UiKey::Delete], false, true);
+    assert_eq!(typing.text, "big ");
+    typing.pressed(&[UiKey::Delete], false, false);
+    assert_eq!(typing.text, "ig ");
+
+    // Nothing to delete, so nothing changes.
+    assert!(
+        !typing
+            .pressed(&[UiKey::Home, UiKey::Backspace], false, false)
+            .changed
+    );
+
+    // Characters aren't split up, even ones that take more than one byte.
+    typing.typed("\u{e9}t\u{e9}");
+    typing.pressed(&[UiKey::Left, UiKey::Backspace], false, false);
+    assert_eq!(typing.text, "\u{e9}\u{e9}ig ");
+    typing.pressed(&[UiKey::Right, UiKey::Backspace], false, false);
+    assert_eq!(typing.text, "\u{e9}ig ");
+}
+
+#[test]
+fn dragging_selects() {
+    let mut typing = Typing::new("replace me", TextInputSettings::default());
+
+    typing.frame(mouse(12.0, 20.0, true));
+    typing.frame(mouse(200.0, 20.0, true));
+    typing.frame(mouse(200.0, 20.0, false));
+    typing.typed("done");
+    assert_eq!(typing.text, "done");
+}
+
+#[test]
+fn copying_cutting_and_pasting() {
+    let mut typing = Typing::new("copy", TextInputSettings::default());
+
+    // Nothing's selected yet.
+    typing.pressed(&[UiKey::Copy], false, false);
+    assert_eq!(typing.ui.get_copied_text(), None);
+
+    typing.pressed(&[UiKey::SelectAll, UiKey::Copy], false, false);
+    assert_eq!(typing.ui.get_copied_text(), Some("copy"));
+
+    // It's only there for the frame it was copied in.
+    typing.pressed(&[], false, false);
+    assert_eq!(typing.ui.get_copied_text(), None);
+
+    typing.pressed(&[UiKey::Home], false, false);
+    typing.pressed(&[UiKey::Right, UiKey::Right], true, false);
+    assert!(typing.pressed(&[UiKey::Cut], false, false).changed);
+    assert_eq!(typing.ui.get_copied_text(), Some("co"));
+    assert_eq!(typing.text, "py");
+
+    // Line breaks don't make it into a single line.
+    let mut input = mouse(20.0, 20.0, false);
+    input.keys = vec![UiKey::End, UiKey::Paste, UiKey::Paste];
+    input.clipboard = Some("th\non".to_string());
+    typing.frame(input);
+    assert_eq!(typing.text, "pythonthon");
+}
+
+#[test]
+fn undoing_and_redoing() {
+    let mut typing = Typing::new("", TextInputSettings::default());
+
+    typing.typed("a");
+    typing.typed("b");
+    typing.pressed(&[UiKey::Backspace], false, false);
+    typing.typed("c");
+    assert_eq!(typing.text, "ac");
+
+    typing.pressed(&[UiKey::Undo], false, false);
+    assert_eq!(typing.text, "a");
+    typing.pressed(&[UiKey::Undo], false, false);
+    assert_eq!(typing.text, "ab");
+
+    // Both letters were typed in one go.
+    typing.pressed(&[UiKey::Undo], false, false);
+    assert_eq!(typing.text, "");
+    assert!(!typing.pressed(&[UiKey::Undo], false, false).changed);
+
+    typing.pressed(&[UiKey::Redo, UiKey::Redo], false, false);
+    assert_eq!(typing.text, "a");
+
+    // Changing it again loses what could have been redone.
+    typing.typed("z");
+    assert!(!typing.pressed(&[UiKey::Redo], false, false).changed);
+    assert_eq!(typing.text, "az");
+}
+
+#[test]
+fn filters_and_max_length() {
+    assert!(TextFilter::Numeric.accepts("-12.5"));
+    assert!(TextFilter::Numeric.accepts("-"));
+    assert!(!TextFilter::Numeric.accepts("1-2"));
+    assert!(!TextFilter::Numeric.accepts("1.2.3"));
+    assert!(TextFilter::Alphanumeric.accepts("Zo\u{eb}42"));
+    assert!(!TextFilter::Alphanumeric.accepts("a b"));
+
+    let mut typing = Typing::new(
+        "",
+        TextInputSettings {
+            max_length: Some(5),
+            filter: TextFilter::Numeric,
+        },
+    );
+
+    typing.typed("-1a2.3.45");
+    assert_eq!(typing.text, "-12.3");
+
+    // Full up, so typing over a selection replaces it, but nothing else fits.
+    assert!(!typing.typed("9").changed);
+    typing.pressed(&[UiKey::SelectAll], false, false);
+    typing.typed("x");
+    assert_eq!(typing.text, "-12.3");
+    typing.typed("987654");
+    assert_eq!(typing.text, "98765");
+
+    let mut typing = Typing::new(
+        "",
+        TextInputSettings {
+            max_length: None,
+            filter: TextFilter::Alphanumeric,
+        },
+    );
+
+    typing.typed("Bob the 2nd!");
+    assert_eq!(typing.text, "Bobthe2nd");
+}
@@ -0,0 +1,143 @@
+use bgf::math::{Rect, Vector2};
+use bgf::ui::layout::{self, Anchor, Grid, Justify, Margins, Size, Stack};
+
+const SCREEN: Rect<f32> = Rect {
+    x: 0.0,
+    y: 0.0,
+    width: 800.0,
+    height: 600.0,
+};
+
+#[test]
+fn anchors_follow_the_parent() {
+    let size = Vector2::new(100.0, 50.0);
+    let margins = Margins::all(10.0);
+
+    let corner = layout::anchor(&SCREEN, size, Anchor::BottomRight, &margins);
+    assert_eq!(corner, Rect::new(690.0, 540.0, 100.0, 50.0));
+
+    let centered = layout::anchor(&SCREEN, size, Anchor::Center, &Margins::default());
+    assert_eq!(centered, Rect::new(350.0, 275.0, 100.0, 50.0));
+
+    // Resizing the window keeps it in the corner.
+    let bigger = Rect::new(0.0, 0.0, 1280.0, 720.0);
+    let corner = layout::anchor(&bigger, size, Anchor::BottomRight, &margins);
+    assert_eq!(corner, Rect::new(1170.0, 660.0, 100.0, 50.0));
+
+    let top = layout::anchor(
+        &SCREEN,
+        size,
+        Anchor::Top,
+        &Margins::new(0.0, 20.0, 0.0, 0.0),
+    );
+    assert_eq!(top, Rect::new(350.0, 20.0, 100.0, 50.0));
+}
+
+#[test]
+fn margins_never_go_negative() {
+    let rect = Rect::new(0.0, 0.0, 10.0, 10.0);
+    let shrunk = Margins::symmetric(8.0, 2.0).shrink(&rect);
+
+    assert_eq!(shrunk, Rect::new(8.0, 2.0, 0.0, 6.0));
+}
+
+#[test]
+fn growing_shares_out_the_leftover_space() {
+    let sizes = [Size::fixed(100.0), Size::flex(1.0), Size::flex(3.0)];
+    assert_eq!(layout::distribute(&sizes, 500.0), vec![100.0, 100.0, 300.0]);
+
+    // Hitting the max gives the rest to the others.
+    let sizes = [
+        Size::flex(1.0).with_max(50.0),
+        Size::flex(1.0),
+        Size::flex(2.0),
+    ];
+    assert_eq!(layout::distribute(&sizes, 350.0), vec![50.0, 100.0, 200.0]);
+
+    // Nothing grows, so there's some left over.
+    let sizes = [Size::new(50.0).with_shrink(0.0), Size::fixed(20.0)];
+    assert_eq!(layout::distribute(&sizes, 100.0), vec![50.0, 20.0]);
+}
+
+#[test]
+fn shrinking_goes_by_size_and_stops_at_the_min() {
+    // 100 too much, taken out two to one.
+    let sizes = [Size::new(200.0), Size::new(100.0)];
+    let result = layout::distribute(&sizes, 200.0);
+    assert!((result[0] - 133.33333).abs() < 0.001);
+    assert!((result[1] - 66.66667).abs() < 0.001);
+
+    let sizes = [Size::new(200.0).with_min(180.0), Size::new(100.0)];
+    assert_eq!(layout::distribute(&sizes, 200.0), vec![180.0, 20.0]);
+
+    // Everything at its min already, so it just doesn't fit.
+    let sizes = [Size::fixed(100.0), Size::new(100.0).with_min(80.0)];
+    assert_eq!(layout::distribute(&sizes, 100.0), vec![100.0, 80.0]);
+}
+
+#[test]
+fn stacks_put_things_in_a_row() {
+    let parent = Rect::new(0.0, 0.0, 320.0, 40.0);
+    let stack = Stack::horizontal()
+        .with_spacing(10.0)
+        .with_padding(Margins::all(5.0));
+
+    let rects = stack.layout(
+        &parent,
+        &[Size::fixed(50.0), Size::flex(1.0), Size::fixed(50.0)],
+    );
+
+    assert_eq!(
+        rects,
+        vec![
+            Rect::new(5.0, 5.0, 50.0, 30.0),
+            Rect::new(65.0, 5.0, 190.0, 30.0),
+            Rect::new(265.0, 5.0, 50.0, 30.0),
+        ]
+    );
+
+    let column = Stack::vertical().with_spacing(4.0);
+    let rects = column.layout(&parent, &[Size::fixed(10.0), Size::fixed(10.0)]);
+    assert_eq!(rects[1], Rect::new(0.0, 14.0, 320.0, 10.0));
+}
+
+#[test]
+fn justifying_the_leftover_space() {
+    let parent = Rect::new(0.0, 0.0, 100.0, 20.0);
+    let sizes = [Size::fixed(20.0), Size::fixed(20.0)];
+
+    let starts = |justify: Justify| {
+        let rects = Stack::horizontal()
+            .with_justify(justify)
+            .layout(&parent, &sizes);
+
+        return (rects[0].x, rects[1].x);
+    };
+
+    assert_eq!(starts(Justify::Start), (0.0, 20.0));
+    assert_eq!(starts(Justify::Center), (30.0, 50.0));
+    assert_eq!(starts(Justify::End), (60.0, 80.0));
+    assert_eq!(starts(Justify::SpaceBetween), (0.0, 80.0));
+}
+
+#[test]
+fn grids_cut_up_the_parent() {
+    let parent = Rect::new(0.0, 0.0, 320.0, 210.0);
+    let grid = Grid::new(3, 2)
+        .with_spacing(Vector2::new(10.0, 10.0))
+        .with_padding(Margins::all(10.0));
+
+    assert_eq!(grid.get_cell_size(&parent), Vector2::new(93.333336, 90.0));
+    assert_eq!(
+        grid.cell(&parent, 0, 1),
+        Rect::new(10.0, 110.0, 93.333336, 90.0)
+    );
+
+    let wide = grid.span(&parent, 1, 0, 2, 1);
+    assert!((wide.x - 113.33333).abs() < 0.001);
+    assert!((wide.width - 196.66667).abs() < 0.001);
+
+    let cells = grid.layout(&parent, 4);
+    assert_eq!(cells.len(), 4);
+    assert_eq!(cells[3], grid.cell(&parent, 0, 1));
+}